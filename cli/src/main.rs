@@ -1,14 +1,41 @@
 use clap::builder::PossibleValuesParser;
-use clap::{crate_authors, crate_description, crate_version, Arg, ArgMatches, Command};
+use clap::{
+    crate_authors, crate_description, crate_version, value_parser, Arg, ArgAction, ArgMatches,
+    Command,
+};
+use console::Term;
 use reqlang::prelude::*;
 use reqlang::{
-    diagnostics::get_diagnostics,
+    auth::resolve_auth,
+    dependencies::{parse_with_dependencies, resolve_dependencies},
+    diagnostics::{get_diagnostics, get_lint_diagnostics},
+    errors::ReqlangError,
     export::{export, export_response, RequestFormat, ResponseFormat},
-    types::ParseResult,
+    extractor::capture,
+    lint::{lint, LintFinding},
+    parser::{parse_with_context, ParseContext},
+    report::{report, ReportFormat, RequestFileReport},
+    secrets::{referenced_secrets, resolve_secrets, SecretProviderConfig},
+    templater::template_with_context,
+    types::{
+        client::{ClientDuration, ParsedConfigClient},
+        http::HttpStatusCode,
+        lint::{Lint, LintLevel},
+        ParseResult, ReferenceType, TemplatedRequestFile,
+    },
+    workspace::{discover_request_files, Workspace},
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::exit,
+    time::{Duration, Instant},
 };
-use std::{collections::HashMap, fs, process::exit};
 
 use std::error::Error;
+use std::io::IsTerminal;
 
 /// Parse a single key-value pair
 fn parse_key_val<T, U>(value: &str) -> Result<(T, U), Box<dyn Error + Send + Sync + 'static>>
@@ -32,12 +59,296 @@ where
     Ok((key, value))
 }
 
-fn export_command(matches: &ArgMatches) {
+/// Parse a report format with an optional output path: `format[=path]`
+fn parse_report(value: &str) -> Result<(ReportFormat, Option<PathBuf>), String> {
+    match value.split_once('=') {
+        Some((format, path)) => Ok((format.parse()?, Some(PathBuf::from(path)))),
+        None => Ok((value.parse()?, None)),
+    }
+}
+
+/// Write reports to their paths, or stdout if no path was specified
+fn write_reports(reports: &[(ReportFormat, Option<PathBuf>)], results: &[RequestFileReport]) {
+    for (format, path) in reports {
+        let output = report(results, *format);
+
+        match path {
+            Some(path) => {
+                if let Err(err) = fs::write(path, format!("{output}\n")) {
+                    eprintln!("Unable to write report to '{}': {err}", path.display());
+                    exit(1);
+                }
+            }
+            None => println!("{output}"),
+        }
+    }
+}
+
+/// Secret providers passed as flags, in order of precedence
+fn secret_provider_configs(matches: &ArgMatches) -> Vec<SecretProviderConfig> {
+    let mut configs = vec![];
+
+    if let Some(prefix) = matches.get_one::<String>("secret_env_prefix") {
+        configs.push(SecretProviderConfig::Env {
+            prefix: prefix.clone(),
+        });
+    }
+
+    if let Some(path) = matches.get_one::<String>("secret_dotenv") {
+        configs.push(SecretProviderConfig::Dotenv { path: path.clone() });
+    }
+
+    if let Some(command) = matches.get_one::<String>("secret_command") {
+        configs.push(SecretProviderConfig::Command {
+            command: command.clone(),
+        });
+    }
+
+    configs
+}
+
+/// Client options passed as flags
+///
+/// These take precedence over the options in the request file and workspace manifest.
+fn client_options(matches: &ArgMatches) -> ParsedConfigClient {
+    ParsedConfigClient {
+        timeout: matches.get_one::<ClientDuration>("timeout").copied(),
+        connect_timeout: matches
+            .get_one::<ClientDuration>("connect_timeout")
+            .copied(),
+        follow_redirects: matches.get_flag("no_follow_redirects").then_some(false),
+        max_redirects: matches.get_one::<usize>("max_redirects").copied(),
+        proxy: matches.get_one::<String>("proxy").cloned(),
+        ca_cert: matches.get_one::<String>("ca_cert").cloned(),
+        client_cert: matches.get_one::<String>("client_cert").cloned(),
+        insecure: matches.get_flag("insecure").then_some(true),
+    }
+}
+
+/// Lint levels passed as flags
+///
+/// These take precedence over the levels in the request file. Denying a lint
+/// takes precedence over allowing it.
+fn lint_levels(matches: &ArgMatches) -> HashMap<Lint, LintLevel> {
+    let mut levels = HashMap::new();
+
+    for (id, level) in [("allow", LintLevel::Allow), ("deny", LintLevel::Deny)] {
+        for lint in matches.get_many::<Lint>(id).into_iter().flatten() {
+            levels.insert(*lint, level);
+        }
+    }
+
+    levels
+}
+
+/// Findings from denied lints in a request file
+///
+/// Errors parsing the request file are reported when it's resolved.
+fn denied_lints(
+    contents: &str,
+    context: &ParseContext,
+    levels: &HashMap<Lint, LintLevel>,
+) -> Vec<LintFinding> {
+    match parse_with_context(&Ast::from(contents), context) {
+        Ok(reqfile) => lint(&reqfile, contents, levels)
+            .into_iter()
+            .filter(LintFinding::is_denied)
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Apply the client options passed as flags to a templated request file
+fn with_client_options(
+    mut reqfile: TemplatedRequestFile,
+    client: &ParsedConfigClient,
+) -> TemplatedRequestFile {
+    reqfile.client = Some(client.merge(&reqfile.client.unwrap_or_default()));
+
+    reqfile
+}
+
+/// Get the values of secrets that weren't passed from the secret providers
+///
+/// Providers passed as flags take precedence over the providers in the workspace manifest.
+fn provide_secrets(
+    path: &Path,
+    contents: &str,
+    secrets: &HashMap<String, String>,
+    configs: &[SecretProviderConfig],
+    context: &ParseContext,
+) -> Result<HashMap<String, String>, String> {
+    let mut providers = configs
+        .iter()
+        .map(|config| config.provider(Path::new("")))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    if let Some(workspace) = Workspace::discover(path).map_err(|err| err.to_string())? {
+        providers.extend(
+            workspace
+                .secret_providers()
+                .map_err(|err| err.to_string())?,
+        );
+    }
+
+    let names = referenced_secrets(path, contents, context);
+
+    resolve_secrets(&names, secrets, &providers).map_err(|err| err.to_string())
+}
+
+/// If missing prompt and secret values should be asked for
+///
+/// Only when stdin is a terminal and the `--no-input` flag wasn't passed.
+fn is_interactive(matches: &ArgMatches) -> bool {
+    !matches.get_flag("no_input") && std::io::stdin().is_terminal()
+}
+
+/// Ask for the values of prompts and secrets that weren't passed
+///
+/// Prompts show their description and default value, which is used if
+/// nothing is entered. Secret values are read without echoing them.
+fn input_missing_values(
+    path: impl AsRef<Path>,
+    contents: &str,
+    prompts: &mut HashMap<String, String>,
+    secrets: &mut HashMap<String, String>,
+    context: &ParseContext,
+) {
+    let term = Term::stderr();
+
+    let read = |label: String, is_secret: bool| -> String {
+        let input = term.write_str(&label).and_then(|_| {
+            if is_secret {
+                term.read_secure_line()
+            } else {
+                term.read_line()
+            }
+        });
+
+        input.unwrap_or_else(|err| {
+            eprintln!("Unable to read input: {err}");
+            exit(1);
+        })
+    };
+
+    let reqfiles = parse_with_dependencies(path, contents, context);
+
+    for reqfile in &reqfiles {
+        let declared_prompts = reqfile
+            .config
+            .as_ref()
+            .and_then(|(config, _)| config.prompts.clone())
+            .unwrap_or_default();
+
+        for prompt in declared_prompts {
+            // Unused prompts aren't needed to resolve the selected request
+            let is_referenced = reqfile.is_referenced(&ReferenceType::Prompt(prompt.name.clone()));
+
+            if prompts.contains_key(&prompt.name) || !is_referenced {
+                continue;
+            }
+
+            let mut label = prompt.name.clone();
+
+            if let Some(description) = &prompt.description {
+                label.push_str(&format!(" ({description})"));
+            }
+
+            if let Some(default) = &prompt.default {
+                label.push_str(&format!(" [{default}]"));
+            }
+
+            let value = read(format!("{label}: "), false);
+
+            // Leave the value out so the default is used
+            if value.is_empty() && prompt.default.is_some() {
+                continue;
+            }
+
+            prompts.insert(prompt.name, value);
+        }
+    }
+
+    for reqfile in &reqfiles {
+        for name in reqfile.secrets() {
+            // Unused secrets aren't needed to resolve the selected request
+            let is_referenced = reqfile.is_referenced(&ReferenceType::Secret(name.clone()));
+
+            if secrets.contains_key(&name) || !is_referenced {
+                continue;
+            }
+
+            let value = read(format!("{name} (secret): "), true);
+
+            secrets.insert(name, value);
+        }
+    }
+}
+
+/// Get the [ParseContext] for the request file at `path`
+fn parse_context(path: impl AsRef<Path>) -> ParseContext {
+    match ParseContext::for_path(path) {
+        Ok(context) => context,
+        Err(err) => {
+            eprintln!("{err}");
+            exit(1);
+        }
+    }
+}
+
+/// Execute dependencies, obtain auth values, then template the request file
+async fn resolve_reqfile(
+    path: impl AsRef<Path>,
+    contents: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    client: &ParsedConfigClient,
+    context: &ParseContext,
+) -> Result<TemplatedRequestFile, Vec<Spanned<ReqlangError>>> {
+    let mut provider_values = HashMap::from([(
+        String::from("env"),
+        env.map(|x| x.to_string()).unwrap_or_default(),
+    )]);
+
+    // Execute any request files this one depends on
+    let dependency_values = resolve_dependencies(
+        path,
+        contents,
+        env,
+        prompts,
+        secrets,
+        &provider_values,
+        context.shared_config.as_ref(),
+    )
+    .await?;
+
+    provider_values.extend(dependency_values);
+
+    // Obtain any auth values (e.g. an OAuth2 access token)
+    let auth_values = resolve_auth(
+        contents,
+        env,
+        prompts,
+        secrets,
+        &provider_values,
+        client,
+        context,
+    )
+    .await?;
+
+    provider_values.extend(auth_values);
+
+    template_with_context(contents, env, prompts, secrets, &provider_values, context)
+}
+
+async fn export_command(matches: &ArgMatches) {
     let path = matches.get_one::<String>("path").unwrap();
 
     let env: Option<&str> = matches.get_one::<String>("env").map(|x| x.as_str());
 
-    let prompts = matches
+    let mut prompts = matches
         .get_many::<(String, String)>("prompts")
         .map(|values| values.cloned().collect::<HashMap<String, String>>())
         .unwrap_or_default();
@@ -52,18 +363,54 @@ fn export_command(matches: &ArgMatches) {
         .map(|f| f.parse::<RequestFormat>().unwrap())
         .unwrap_or(RequestFormat::HttpMessage);
 
+    let redact = matches.get_flag("redact");
+
     let contents = fs::read_to_string(path).expect("Should have been able to read the file");
 
-    let provider_values = HashMap::from([(
-        String::from("env"),
-        env.map(|x| x.to_string()).unwrap_or_default(),
-    )]);
+    let context = ParseContext {
+        request: matches.get_one::<String>("request").cloned(),
+        ..parse_context(path)
+    };
+
+    let denied = denied_lints(&contents, &context, &lint_levels(matches));
+
+    if !denied.is_empty() {
+        eprintln!("Request file has denied lint findings");
+        let json = serde_json::to_string_pretty(&get_lint_diagnostics(&denied, &contents)).unwrap();
+        println!("{json}");
+        exit(1);
+    }
+
+    let mut secrets = provide_secrets(
+        Path::new(path),
+        &contents,
+        &secrets,
+        &secret_provider_configs(matches),
+        &context,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1);
+    });
+
+    if is_interactive(matches) {
+        input_missing_values(path, &contents, &mut prompts, &mut secrets, &context);
+    }
 
-    let reqfile = template(&contents, env, &prompts, &secrets, &provider_values);
+    let reqfile = resolve_reqfile(
+        path,
+        &contents,
+        env,
+        &prompts,
+        &secrets,
+        &ParsedConfigClient::default(),
+        &context,
+    )
+    .await;
 
     match reqfile {
         Ok(reqfile) => {
-            let exported_request = export(&reqfile.request, format);
+            let exported_request = export(&reqfile.authenticated_request(redact), format);
 
             println!("{exported_request}");
         }
@@ -94,9 +441,23 @@ fn parse_command(matches: &ArgMatches) {
     let path = matches.get_one::<String>("path").unwrap();
     let contents = fs::read_to_string(path).expect("Should have been able to read the file");
     let ast = Ast::from(&contents);
+    let context = parse_context(path);
 
-    match parse(&ast) {
+    match parse_with_context(&ast, &context) {
         Ok(parsed_reqfile) => {
+            let denied: Vec<LintFinding> = lint(&parsed_reqfile, &contents, &lint_levels(matches))
+                .into_iter()
+                .filter(LintFinding::is_denied)
+                .collect();
+
+            if !denied.is_empty() {
+                eprintln!("Request file has denied lint findings");
+                let json = serde_json::to_string_pretty(&get_lint_diagnostics(&denied, &contents))
+                    .unwrap();
+                println!("{json}");
+                exit(1);
+            }
+
             let parse_results: ParseResult = parsed_reqfile.into();
 
             let json = serde_json::to_string_pretty(&parse_results).unwrap();
@@ -116,6 +477,39 @@ fn parse_command(matches: &ArgMatches) {
     }
 }
 
+fn lint_command(matches: &ArgMatches) {
+    let path = matches.get_one::<String>("path").unwrap();
+    let contents = fs::read_to_string(path).expect("Should have been able to read the file");
+    let ast = Ast::from(&contents);
+    let context = parse_context(path);
+
+    match parse_with_context(&ast, &context) {
+        Ok(parsed_reqfile) => {
+            let findings = lint(&parsed_reqfile, &contents, &lint_levels(matches));
+
+            let json =
+                serde_json::to_string_pretty(&get_lint_diagnostics(&findings, &contents)).unwrap();
+
+            println!("{json}");
+
+            if findings.iter().any(LintFinding::is_denied) {
+                eprintln!("Request file has denied lint findings");
+                exit(1);
+            }
+        }
+        Err(errs) => {
+            let diagnostics = get_diagnostics(&errs, &contents);
+
+            if !diagnostics.is_empty() {
+                eprintln!("Invalid request file");
+                let json = serde_json::to_string_pretty(&diagnostics).unwrap();
+                println!("{json}");
+                exit(1);
+            }
+        }
+    }
+}
+
 async fn run_command(matches: &ArgMatches) {
     // CLI Args
 
@@ -123,7 +517,7 @@ async fn run_command(matches: &ArgMatches) {
 
     let env = matches.get_one::<String>("env").map(|s| s.as_str());
 
-    let prompts = matches
+    let mut prompts = matches
         .get_many::<(String, String)>("prompts")
         .map(|values| values.cloned().collect::<HashMap<String, String>>())
         .unwrap_or_default();
@@ -135,72 +529,564 @@ async fn run_command(matches: &ArgMatches) {
 
     let format = matches
         .get_one::<String>("format")
-        .map(|f| f.parse::<ResponseFormat>().unwrap())
-        .unwrap_or(ResponseFormat::HttpMessage);
+        .map(|f| f.as_str())
+        .unwrap_or("http");
 
     let is_testing_response = matches.get_flag("test");
 
+    let is_streaming = matches.get_flag("stream");
+
+    let is_printing_timing = matches.get_flag("timing");
+
+    let output_path = matches.get_one::<PathBuf>("output");
+
+    let reports: Vec<(ReportFormat, Option<PathBuf>)> = matches
+        .get_many::<(ReportFormat, Option<PathBuf>)>("report")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+
+    // Reports without a path are written to stdout instead of the response
+    let is_printing_response =
+        reports.iter().all(|(_, path)| path.is_some()) && !is_streaming && output_path.is_none();
+
     // Read the request file
 
     let contents = fs::read_to_string(path).expect("Should have been able to read the file");
-    let provider_values = HashMap::from([(
-        String::from("env"),
-        env.map(|x| x.to_string()).unwrap_or_default(),
-    )]);
-    let reqfile = template(&contents, env, &prompts, &secrets, &provider_values);
+    let context = ParseContext {
+        request: matches.get_one::<String>("request").cloned(),
+        ..parse_context(path)
+    };
+
+    let denied = denied_lints(&contents, &context, &lint_levels(matches));
+
+    if !denied.is_empty() {
+        let messages: Vec<String> = denied
+            .iter()
+            .map(|finding| format!("{}: {}", finding.lint, finding.message))
+            .collect();
+
+        write_reports(
+            &reports,
+            &[RequestFileReport::errored(
+                path,
+                Duration::ZERO,
+                messages.join("\n"),
+            )],
+        );
+
+        eprintln!("Request file has denied lint findings");
+
+        if is_printing_response {
+            let json =
+                serde_json::to_string_pretty(&get_lint_diagnostics(&denied, &contents)).unwrap();
+            println!("{json}");
+        }
+
+        exit(1);
+    }
+
+    let mut secrets = provide_secrets(
+        Path::new(path),
+        &contents,
+        &secrets,
+        &secret_provider_configs(matches),
+        &context,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1);
+    });
+
+    if is_interactive(matches) {
+        input_missing_values(path, &contents, &mut prompts, &mut secrets, &context);
+    }
+
+    let client = client_options(matches);
+
+    let reqfile =
+        resolve_reqfile(path, &contents, env, &prompts, &secrets, &client, &context).await;
 
     // Execute the request
 
     match reqfile {
         Ok(reqfile) => {
-            let fetcher: HttpRequestFetcher = reqfile.request.into();
-            let response = fetcher.fetch().await;
+            let fetcher: HttpRequestFetcher = with_client_options(reqfile.clone(), &client).into();
+
+            // Write the body as it's received to the output file or, if streaming, stdout
+            let mut body_writer: Option<Box<dyn Write + Send>> = match output_path {
+                Some(output_path) => match File::create(output_path) {
+                    Ok(file) => Some(Box::new(file)),
+                    Err(err) => {
+                        eprintln!("Unable to create '{}': {err}", output_path.display());
+                        exit(1);
+                    }
+                },
+                None if is_streaming => Some(Box::new(io::stdout())),
+                None => None,
+            };
+
+            let started = Instant::now();
+            let response = fetcher
+                .fetch_streaming(|chunk| {
+                    if let Some(writer) = &mut body_writer {
+                        if let Err(err) = writer.write_all(chunk).and_then(|_| writer.flush()) {
+                            eprintln!("Unable to write the response body: {err}");
+                            exit(1);
+                        }
+                    }
+                })
+                .await;
+            let duration = started.elapsed();
 
             match &response {
                 Ok(response) => {
                     // Format the response as specified by the `--format` flag
-                    let formatted_response = export_response(response, format);
+                    let formatted_response = match format {
+                        // Output the values captured from the response
+                        "captures" => {
+                            let captures = parse_with_context(&Ast::from(&contents), &context)
+                                .map(|reqfile| reqfile.captures())
+                                .unwrap_or_default();
+
+                            serde_json::to_string_pretty(&capture(&captures, response)).unwrap()
+                        }
+                        format => {
+                            export_response(response, format.parse::<ResponseFormat>().unwrap())
+                        }
+                    };
+
+                    if is_printing_response {
+                        match format {
+                            // Binary bodies are written as is
+                            "body" if response.binary_body.is_some() => {
+                                io::stdout()
+                                    .write_all(&response.body_bytes())
+                                    .expect("should write the response body to stdout");
+                            }
+                            _ => println!("{formatted_response}"),
+                        }
+                    }
 
-                    println!("{formatted_response}");
+                    if is_printing_timing {
+                        if let Some(metrics) = &response.metrics {
+                            eprintln!("{metrics}");
+                        }
+                    }
 
                     // Check if the `--test` flag was passed
                     if is_testing_response {
-                        // Check if the request file has a response assertion defined
-                        if let Some(expected_response) = &reqfile.response {
-                            // Compare the actual response with the expected response
-                            if let Err(diffs) = assert_response(expected_response, response) {
-                                eprintln!("Response assertion failed:\n{diffs}");
+                        let assert_options = parse_with_context(&Ast::from(&contents), &context)
+                            .map(|reqfile| reqfile.assert_options())
+                            .unwrap_or_default();
 
-                                exit(1);
+                        // Compare the actual response with the expected response, if defined
+                        let result = match &reqfile.response {
+                            Some(expected_response) => {
+                                assert_response_with(expected_response, response, &assert_options)
                             }
+                            None => Ok(()),
+                        };
+
+                        write_reports(
+                            &reports,
+                            &[RequestFileReport::from_response(
+                                path,
+                                duration,
+                                reqfile.response.as_ref(),
+                                &result,
+                            )],
+                        );
+
+                        if let Err(diffs) = result {
+                            eprintln!("Response assertion failed:\n{diffs}");
+
+                            exit(1);
                         }
                     }
 
                     exit(0);
                 }
                 Err(err) => {
+                    write_reports(
+                        &reports,
+                        &[RequestFileReport::errored(path, duration, err.to_string())],
+                    );
+
                     eprintln!("Error occurred while making the request: {err:?}");
                     exit(1);
                 }
             }
         }
         Err(errs) => {
+            let messages: Vec<String> = errs.iter().map(|(err, _)| err.to_string()).collect();
+
+            write_reports(
+                &reports,
+                &[RequestFileReport::errored(
+                    path,
+                    Duration::ZERO,
+                    messages.join("\n"),
+                )],
+            );
+
             let diagnostics = get_diagnostics(&errs, &contents);
 
             if !diagnostics.is_empty() {
                 eprintln!("Invalid request file or errors with input");
-                let json = serde_json::to_string_pretty(&diagnostics).unwrap();
-                println!("{json}");
+
+                if is_printing_response {
+                    let json = serde_json::to_string_pretty(&diagnostics).unwrap();
+                    println!("{json}");
+                }
+
                 exit(1);
             }
         }
     };
 }
 
+/// Outcome of running a request file with `run-all`
+enum RunAllResult {
+    /// The request was executed and the response matched the expected response, if tested
+    Passed(HttpStatusCode),
+    /// The response didn't match the expected response
+    Failed(HttpStatusCode, String),
+    /// The request file couldn't be resolved or the request failed
+    Errored(String),
+}
+
+/// Values and options passed to `run-all` for every request file
+struct RunAllOptions<'a> {
+    env: Option<&'a str>,
+    prompts: HashMap<String, String>,
+    secrets: HashMap<String, String>,
+    secret_provider_configs: Vec<SecretProviderConfig>,
+    client: ParsedConfigClient,
+    lint_levels: HashMap<Lint, LintLevel>,
+    is_testing_response: bool,
+}
+
+/// Resolve, execute, and optionally test each request in a request file
+///
+/// The results and reports use `name` as the path of the request file, with
+/// `#request-name` appended for named requests.
+async fn run_request_file(
+    path: &Path,
+    name: &str,
+    options: &RunAllOptions<'_>,
+) -> Vec<(String, RunAllResult, RequestFileReport)> {
+    let RunAllOptions {
+        env,
+        prompts,
+        secrets,
+        secret_provider_configs,
+        client,
+        lint_levels,
+        is_testing_response,
+    } = options;
+
+    let errored = |name: &str, duration: Duration, message: String| {
+        (
+            name.to_string(),
+            RunAllResult::Errored(message.clone()),
+            RequestFileReport::errored(name, duration, message),
+        )
+    };
+
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => return vec![errored(name, Duration::ZERO, err.to_string())],
+    };
+
+    let context = match ParseContext::for_path(path) {
+        Ok(context) => context,
+        Err(err) => return vec![errored(name, Duration::ZERO, err.to_string())],
+    };
+
+    let denied = denied_lints(&contents, &context, lint_levels);
+
+    if !denied.is_empty() {
+        let messages: Vec<String> = denied
+            .iter()
+            .map(|finding| format!("{}: {}", finding.lint, finding.message))
+            .collect();
+
+        return vec![errored(name, Duration::ZERO, messages.join("\n"))];
+    }
+
+    // Errors parsing the request file are reported when it's resolved
+    let request_names: Vec<Option<String>> =
+        match parse_with_context(&Ast::from(&contents), &context) {
+            Ok(reqfile) => reqfile.requests.into_iter().map(|x| x.name).collect(),
+            Err(_) => vec![None],
+        };
+
+    let mut results = vec![];
+
+    for request_name in request_names {
+        let name = match &request_name {
+            Some(request_name) => format!("{name}#{request_name}"),
+            None => name.to_string(),
+        };
+
+        let context = ParseContext {
+            request: request_name,
+            ..context.clone()
+        };
+
+        // Each request only needs the secrets it references
+        let secrets =
+            match provide_secrets(path, &contents, secrets, secret_provider_configs, &context) {
+                Ok(secrets) => secrets,
+                Err(message) => {
+                    results.push(errored(&name, Duration::ZERO, message));
+                    continue;
+                }
+            };
+
+        let reqfile =
+            match resolve_reqfile(path, &contents, *env, prompts, &secrets, client, &context).await
+            {
+                Ok(reqfile) => with_client_options(reqfile, client),
+                Err(errs) => {
+                    let messages: Vec<String> =
+                        errs.iter().map(|(err, _)| err.to_string()).collect();
+
+                    results.push(errored(&name, Duration::ZERO, messages.join("\n")));
+                    continue;
+                }
+            };
+
+        let fetcher: HttpRequestFetcher = reqfile.clone().into();
+
+        let started = Instant::now();
+
+        let response = match fetcher.fetch().await {
+            Ok(response) => response,
+            Err(err) => {
+                results.push(errored(&name, started.elapsed(), err.to_string()));
+                continue;
+            }
+        };
+
+        let duration = started.elapsed();
+
+        let result = match (&reqfile.response, *is_testing_response) {
+            (Some(expected_response), true) => {
+                let assert_options = parse_with_context(&Ast::from(&contents), &context)
+                    .map(|reqfile| reqfile.assert_options())
+                    .unwrap_or_default();
+
+                assert_response_with(expected_response, &response, &assert_options)
+            }
+            _ => Ok(()),
+        };
+
+        let expected_response = reqfile.response.as_ref().filter(|_| *is_testing_response);
+
+        let report = RequestFileReport::from_response(&name, duration, expected_response, &result);
+
+        let result = match result {
+            Ok(_) => RunAllResult::Passed(response.status_code),
+            Err(diffs) => RunAllResult::Failed(response.status_code, diffs.to_string()),
+        };
+
+        results.push((name, result, report));
+    }
+
+    results
+}
+
+async fn run_all_command(matches: &ArgMatches) {
+    let dir = matches.get_one::<String>("dir").unwrap();
+
+    let env = matches.get_one::<String>("env").map(|s| s.as_str());
+
+    let prompts = matches
+        .get_many::<(String, String)>("prompts")
+        .map(|values| values.cloned().collect::<HashMap<String, String>>())
+        .unwrap_or_default();
+
+    let secrets = matches
+        .get_many::<(String, String)>("secrets")
+        .map(|values| values.cloned().collect::<HashMap<String, String>>())
+        .unwrap_or_default();
+
+    let options = RunAllOptions {
+        env,
+        prompts,
+        secrets,
+        secret_provider_configs: secret_provider_configs(matches),
+        client: client_options(matches),
+        lint_levels: lint_levels(matches),
+        is_testing_response: matches.get_flag("test"),
+    };
+
+    let reports: Vec<(ReportFormat, Option<PathBuf>)> = matches
+        .get_many::<(ReportFormat, Option<PathBuf>)>("report")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+
+    // Reports without a path are written to stdout instead of the summary
+    let is_printing_summary = reports.iter().all(|(_, path)| path.is_some());
+
+    let paths = discover_request_files(dir);
+
+    if paths.is_empty() {
+        eprintln!("No request files found in '{dir}'");
+        exit(1);
+    }
+
+    let mut results: Vec<(String, RunAllResult)> = vec![];
+    let mut request_file_reports: Vec<RequestFileReport> = vec![];
+
+    for path in paths {
+        let display_path = path.strip_prefix(dir).unwrap_or(&path).to_path_buf();
+
+        let request_results =
+            run_request_file(&path, &display_path.display().to_string(), &options).await;
+
+        for (name, result, request_file_report) in request_results {
+            request_file_reports.push(request_file_report);
+
+            // Report the details of failures as they happen
+            match &result {
+                RunAllResult::Failed(_, diffs) => {
+                    eprintln!("{name}: Response assertion failed:\n{diffs}")
+                }
+                RunAllResult::Errored(message) => eprintln!("{name}: {message}\n"),
+                RunAllResult::Passed(_) => {}
+            }
+
+            results.push((name, result));
+        }
+    }
+
+    write_reports(&reports, &request_file_reports);
+
+    let count = |f: fn(&RunAllResult) -> bool| results.iter().filter(|(_, x)| f(x)).count();
+
+    let passed = count(|x| matches!(x, RunAllResult::Passed(_)));
+    let failed = count(|x| matches!(x, RunAllResult::Failed(..)));
+    let errored = count(|x| matches!(x, RunAllResult::Errored(_)));
+
+    if is_printing_summary {
+        // Summary table of the results
+        println!("{:<8}{:<8}REQUEST FILE", "RESULT", "STATUS");
+
+        for (name, result) in &results {
+            let (label, status) = match result {
+                RunAllResult::Passed(status) => ("PASS", status.to_string()),
+                RunAllResult::Failed(status, _) => ("FAIL", status.to_string()),
+                RunAllResult::Errored(_) => ("ERROR", "-".to_string()),
+            };
+
+            println!("{label:<8}{status:<8}{name}");
+        }
+
+        println!("\n{passed} passed; {failed} failed; {errored} errored");
+    }
+
+    if failed > 0 || errored > 0 {
+        exit(1);
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let path_arg = Arg::new("path").required(true).help("Path to request file");
 
+    let report_arg = Arg::new("report")
+        .long("report")
+        .value_name("FORMAT[=PATH]")
+        .value_parser(parse_report)
+        .action(ArgAction::Append)
+        .requires("test")
+        .help("Report test results as junit or tap, to a file or stdout");
+
+    let request_arg = Arg::new("request")
+        .short('r')
+        .long("request")
+        .value_name("name")
+        .help("Name of the request to use when the request file has more than one");
+
+    let no_input_arg = Arg::new("no_input")
+        .long("no-input")
+        .num_args(0)
+        .help("Don't ask for missing prompt and secret values when stdin is a terminal");
+
+    let secret_provider_args = [
+        Arg::new("secret_env_prefix")
+            .long("secret-env-prefix")
+            .value_name("prefix")
+            .help("Read secret values from environment variables starting with a prefix"),
+        Arg::new("secret_dotenv")
+            .long("secret-dotenv")
+            .value_name("path")
+            .help("Read secret values from a dotenv file"),
+        Arg::new("secret_command")
+            .long("secret-command")
+            .value_name("command")
+            .help("Read secret values from the output of a command, {name} is replaced with the secret name"),
+    ];
+
+    let client_args = [
+        Arg::new("timeout")
+            .long("timeout")
+            .value_name("duration")
+            .value_parser(value_parser!(ClientDuration))
+            .help("Time allowed for the whole request, e.g. 30s or 500ms"),
+        Arg::new("connect_timeout")
+            .long("connect-timeout")
+            .value_name("duration")
+            .value_parser(value_parser!(ClientDuration))
+            .help("Time allowed to connect to the server"),
+        Arg::new("no_follow_redirects")
+            .long("no-follow-redirects")
+            .num_args(0)
+            .conflicts_with("max_redirects")
+            .help("Don't follow redirect responses"),
+        Arg::new("max_redirects")
+            .long("max-redirects")
+            .value_name("count")
+            .value_parser(value_parser!(usize))
+            .help("The most redirects to follow"),
+        Arg::new("proxy")
+            .long("proxy")
+            .value_name("url")
+            .help("Send requests through a proxy"),
+        Arg::new("ca_cert")
+            .long("ca-cert")
+            .value_name("path")
+            .help("Trust the PEM encoded certificates in a file"),
+        Arg::new("client_cert")
+            .long("client-cert")
+            .value_name("path")
+            .help("Authenticate with a PEM encoded client certificate and private key"),
+        Arg::new("insecure")
+            .short('k')
+            .long("insecure")
+            .num_args(0)
+            .help("Accept invalid TLS certificates"),
+    ];
+
+    let lint_args = [
+        Arg::new("allow")
+            .short('A')
+            .long("allow")
+            .value_name("lint")
+            .value_parser(value_parser!(Lint))
+            .action(ArgAction::Append)
+            .help("Don't check a lint, e.g. unused_value"),
+        Arg::new("deny")
+            .short('D')
+            .long("deny")
+            .value_name("lint")
+            .value_parser(value_parser!(Lint))
+            .action(ArgAction::Append)
+            .help("Treat findings from a lint as errors, e.g. secret_in_url"),
+    ];
+
     let matches = Command::new("reqlang")
         .version(crate_version!())
         .author(crate_authors!("\n"))
@@ -210,6 +1096,7 @@ async fn main() {
             Command::new("export")
                 .about("Export request to specified format")
                 .arg(path_arg.clone())
+                .arg(request_arg.clone())
                 .arg(
                     Arg::new("env")
                         .short('e')
@@ -230,6 +1117,9 @@ async fn main() {
                         .value_parser(parse_key_val::<String, String>)
                         .help("Pass secret values to resolve with"),
                 )
+                .args(secret_provider_args.clone())
+                .args(lint_args.clone())
+                .arg(no_input_arg.clone())
                 .arg(
                     Arg::new("format")
                         .short('f')
@@ -237,6 +1127,12 @@ async fn main() {
                         .default_value("json")
                         .value_parser(PossibleValuesParser::new(["http", "curl", "json"]))
                         .help("Format to export"),
+                )
+                .arg(
+                    Arg::new("redact")
+                        .long("redact")
+                        .num_args(0)
+                        .help("Redact credentials from auth in the exported request"),
                 ),
         )
         .subcommand(
@@ -247,12 +1143,20 @@ async fn main() {
         .subcommand(
             Command::new("parse")
                 .about("Parse a request file")
-                .arg(path_arg.clone()),
+                .arg(path_arg.clone())
+                .args(lint_args.clone()),
+        )
+        .subcommand(
+            Command::new("lint")
+                .about("Lint a request file")
+                .arg(path_arg.clone())
+                .args(lint_args.clone()),
         )
         .subcommand(
             Command::new("run")
                 .about("Run a request file")
                 .arg(path_arg.clone())
+                .arg(request_arg.clone())
                 .arg(
                     Arg::new("env")
                         .short('e')
@@ -273,14 +1177,41 @@ async fn main() {
                         .value_parser(parse_key_val::<String, String>)
                         .help("Input a secret value"),
                 )
+                .args(secret_provider_args.clone())
+                .args(client_args.clone())
+                .args(lint_args.clone())
+                .arg(no_input_arg)
                 .arg(
                     Arg::new("format")
                         .short('f')
                         .long("format")
                         .default_value("http")
-                        .value_parser(PossibleValuesParser::new(["http", "json", "body"]))
+                        .value_parser(PossibleValuesParser::new([
+                            "http", "json", "body", "captures",
+                        ]))
                         .help("Format the response"),
                 )
+                .arg(
+                    Arg::new("stream")
+                        .long("stream")
+                        .num_args(0)
+                        .conflicts_with("output")
+                        .help("Print the response body as it's received instead of the formatted response"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("file")
+                        .value_parser(value_parser!(PathBuf))
+                        .help("Save the response body to a file instead of printing the response"),
+                )
+                .arg(
+                    Arg::new("timing")
+                        .long("timing")
+                        .num_args(0)
+                        .help("Print the response's timing, size, and redirects to stderr"),
+                )
                 .arg(
                     // an bool flag called test
                     Arg::new("test")
@@ -288,15 +1219,58 @@ async fn main() {
                         .long("test")
                         .num_args(0)
                         .help("Test if the response matches the expected response, if defined"),
-                ),
+                )
+                .arg(report_arg.clone()),
+        )
+        .subcommand(
+            Command::new("run-all")
+                .about("Run all request files in a directory")
+                .arg(
+                    Arg::new("dir")
+                        .required(true)
+                        .help("Path to a directory of request files"),
+                )
+                .arg(
+                    Arg::new("env")
+                        .short('e')
+                        .long("env")
+                        .help("Resolve with an environment"),
+                )
+                .arg(
+                    Arg::new("prompts")
+                        .short('P')
+                        .long("prompt")
+                        .value_parser(parse_key_val::<String, String>)
+                        .help("Input a prompt value"),
+                )
+                .arg(
+                    Arg::new("secrets")
+                        .short('S')
+                        .long("secret")
+                        .value_parser(parse_key_val::<String, String>)
+                        .help("Input a secret value"),
+                )
+                .args(secret_provider_args.clone())
+                .args(client_args.clone())
+                .args(lint_args)
+                .arg(
+                    Arg::new("test")
+                        .short('t')
+                        .long("test")
+                        .num_args(0)
+                        .help("Test if the responses match the expected responses, if defined"),
+                )
+                .arg(report_arg),
         )
         .get_matches();
 
     match matches.subcommand() {
-        Some(("export", sub_matches)) => export_command(sub_matches),
+        Some(("export", sub_matches)) => export_command(sub_matches).await,
         Some(("ast", sub_matches)) => ast_command(sub_matches),
         Some(("parse", sub_matches)) => parse_command(sub_matches),
+        Some(("lint", sub_matches)) => lint_command(sub_matches),
         Some(("run", sub_matches)) => run_command(sub_matches).await,
+        Some(("run-all", sub_matches)) => run_all_command(sub_matches).await,
         _ => eprintln!("Invalid subcommand. Use --help for more information."),
     }
 }
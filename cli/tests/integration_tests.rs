@@ -74,11 +74,13 @@ mod cli_integration_tests {
             Usage: reqlang [COMMAND]
 
             Commands:
-              export  Export request to specified format
-              ast     Produce an AST for a request file
-              parse   Parse a request file
-              run     Run a request file
-              help    Print this message or the help of the given subcommand(s)
+              export   Export request to specified format
+              ast      Produce an AST for a request file
+              parse    Parse a request file
+              lint     Lint a request file
+              run      Run a request file
+              run-all  Run all request files in a directory
+              help     Print this message or the help of the given subcommand(s)
 
             Options:
               -h, --help     Print help
@@ -201,6 +203,245 @@ mod cli_integration_tests {
         );
     }
 
+    #[test]
+    fn lint_reqfile() {
+        let assert = assert_command!("reqlang lint ../examples/valid/post.reqlang");
+
+        assert_success!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 0\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 38\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 3,\n",
+                "    \"code\": \"missing_content_type\",\n",
+                "    \"message\": \"Request has a body but no content-type header\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn lint_reqfile_with_allowed_lint() {
+        let assert =
+            assert_command!("reqlang lint ../examples/valid/post.reqlang -A missing_content_type");
+
+        assert_success!(assert, Some("[]\n"), Some(""));
+    }
+
+    #[test]
+    fn lint_reqfile_with_denied_lint() {
+        let assert =
+            assert_command!("reqlang lint ../examples/valid/post.reqlang -D missing_content_type");
+
+        assert_failure!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 0\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 38\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 1,\n",
+                "    \"code\": \"missing_content_type\",\n",
+                "    \"message\": \"Request has a body but no content-type header\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("Request file has denied lint findings\n")
+        );
+    }
+
+    #[test]
+    fn lint_reqfile_with_config_levels() {
+        let assert = assert_command!("reqlang lint ../examples/valid/lints.reqlang");
+
+        assert_success!(assert, Some("[]\n"), Some(""));
+    }
+
+    #[test]
+    fn lint_unused_prompt() {
+        let assert = assert_command!("reqlang lint ../examples/valid/unused_prompt.reqlang");
+
+        assert_success!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 2,\n",
+                "        \"character\": 8\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 2,\n",
+                "        \"character\": 16\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 2,\n",
+                "    \"code\": \"unused_value\",\n",
+                "    \"message\": \"Value was declared but not used. Try adding the template reference {{?base_url}} to the request or response.\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn lint_unused_secret() {
+        let assert = assert_command!("reqlang lint ../examples/valid/unused_secret.reqlang");
+
+        assert_success!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 1,\n",
+                "        \"character\": 12\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 1,\n",
+                "        \"character\": 20\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 2,\n",
+                "    \"code\": \"unused_value\",\n",
+                "    \"message\": \"Value was declared but not used. Try adding the template reference {{!base_url}} to the request or response.\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("")
+        );
+    }
+
+    #[test]
+    fn lint_unused_secret_with_denied_lint() {
+        let assert =
+            assert_command!("reqlang lint ../examples/valid/unused_secret.reqlang -D unused_value");
+
+        assert_failure!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 1,\n",
+                "        \"character\": 12\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 1,\n",
+                "        \"character\": 20\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 1,\n",
+                "    \"code\": \"unused_value\",\n",
+                "    \"message\": \"Value was declared but not used. Try adding the template reference {{!base_url}} to the request or response.\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("Request file has denied lint findings\n")
+        );
+    }
+
+    #[test]
+    fn lint_unknown_lint() {
+        let assert = assert_command!("reqlang lint ../examples/valid/post.reqlang --deny unused");
+
+        assert_failure!(
+            assert,
+            Some(""),
+            Some(concat!(
+                "error: invalid value 'unused' for '--deny <lint>': Unknown lint 'unused', expected one of: unused_value, missing_content_type, insecure_prod_target, secret_in_url\n",
+                "\n",
+                "For more information, try '--help'.\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_reqfile_with_denied_lint() {
+        let assert = assert_command!(
+            "reqlang parse ../examples/valid/post.reqlang --deny missing_content_type"
+        );
+
+        assert_failure!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 0\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 38\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 1,\n",
+                "    \"code\": \"missing_content_type\",\n",
+                "    \"message\": \"Request has a body but no content-type header\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("Request file has denied lint findings\n")
+        );
+    }
+
+    #[test]
+    fn run_reqfile_with_denied_lint() {
+        let assert = assert_command!(
+            "reqlang run ../examples/valid/post.reqlang -e test -P prompt_value=foo -S super_secret_value=123 -D missing_content_type"
+        );
+
+        assert_failure!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 0\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 20,\n",
+                "        \"character\": 38\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 1,\n",
+                "    \"code\": \"missing_content_type\",\n",
+                "    \"message\": \"Request has a body but no content-type header\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("Request file has denied lint findings\n")
+        );
+    }
+
     #[test]
     fn export_no_args() {
         let assert = assert_command!("reqlang export");
@@ -232,12 +473,12 @@ mod cli_integration_tests {
                 "  {\n",
                 "    \"range\": {\n",
                 "      \"start\": {\n",
-                "        \"line\": 0,\n",
-                "        \"character\": 0\n",
+                "        \"line\": 25,\n",
+                "        \"character\": 21\n",
                 "      },\n",
                 "      \"end\": {\n",
-                "        \"line\": 0,\n",
-                "        \"character\": 0\n",
+                "        \"line\": 25,\n",
+                "        \"character\": 38\n",
                 "      }\n",
                 "    },\n",
                 "    \"severity\": 1,\n",
@@ -249,6 +490,82 @@ mod cli_integration_tests {
         );
     }
 
+    #[test]
+    fn export_missing_prompt_without_input() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/post.reqlang -e test -S super_secret_value=123 --no-input"
+        );
+
+        assert_failure!(
+            assert,
+            Some(concat!(
+                "[\n",
+                "  {\n",
+                "    \"range\": {\n",
+                "      \"start\": {\n",
+                "        \"line\": 25,\n",
+                "        \"character\": 21\n",
+                "      },\n",
+                "      \"end\": {\n",
+                "        \"line\": 25,\n",
+                "        \"character\": 38\n",
+                "      }\n",
+                "    },\n",
+                "    \"severity\": 1,\n",
+                "    \"message\": \"ResolverError: Prompt required but not passed: prompt_value\"\n",
+                "  }\n",
+                "]\n"
+            )),
+            Some("Invalid request file or errors when exporting\n")
+        );
+    }
+
+    #[test]
+    fn export_with_secret_from_dotenv() {
+        let dotenv_path = std::env::temp_dir().join("reqlang-export-secret.env");
+        fs::write(&dotenv_path, "super_secret_value=from_dotenv\n").unwrap();
+
+        let command = format!(
+            "reqlang export ../examples/valid/post.reqlang -e test -P prompt_value=foo -f http --secret-dotenv {}",
+            dotenv_path.display()
+        );
+
+        let assert = assert_command!(command);
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(output.status.success());
+        assert!(stdout.contains("\"secret_value\": \"from_dotenv\""));
+    }
+
+    #[test]
+    fn export_with_secret_passed_over_provider() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/post.reqlang -e test -P prompt_value=foo -S super_secret_value=123 -f http --secret-env-prefix REQLANG_UNDEFINED_"
+        );
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(output.status.success());
+        assert!(stdout.contains("\"secret_value\": \"123\""));
+    }
+
+    #[test]
+    fn export_without_unreferenced_secret_from_failed_command() {
+        // The secret is only referenced by a request that isn't selected
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/named_requests.reqlang -f http --secret-command false"
+        );
+
+        assert_success!(
+            assert,
+            Some("GET https://httpbin.org/anything/users HTTP/1.1\n\n"),
+            Some("")
+        );
+    }
+
     #[test]
     fn export_missing_secret() {
         let assert = assert_command!(
@@ -262,12 +579,12 @@ mod cli_integration_tests {
                 "  {\n",
                 "    \"range\": {\n",
                 "      \"start\": {\n",
-                "        \"line\": 0,\n",
-                "        \"character\": 0\n",
+                "        \"line\": 26,\n",
+                "        \"character\": 19\n",
                 "      },\n",
                 "      \"end\": {\n",
-                "        \"line\": 0,\n",
-                "        \"character\": 0\n",
+                "        \"line\": 26,\n",
+                "        \"character\": 42\n",
                 "      }\n",
                 "    },\n",
                 "    \"severity\": 1,\n",
@@ -293,7 +610,8 @@ mod cli_integration_tests {
                 "  \"target\": \"https://httpbin.org/status/404\",\n",
                 "  \"http_version\": \"1.1\",\n",
                 "  \"headers\": [],\n",
-                "  \"body\": \"\"\n",
+                "  \"body\": \"\",\n",
+                "  \"body_file\": null\n",
                 "}\n"
             )),
             None::<String>
@@ -326,6 +644,125 @@ mod cli_integration_tests {
         );
     }
 
+    #[test]
+    fn export_body_file_to_curl() {
+        let assert = assert_command!("reqlang export ../examples/valid/body_file.reqlang -f curl");
+
+        assert_success!(
+            assert,
+            Some("curl -X POST https://httpbin.org/post --http1.1 -H \"content-type: application/json\" --data-binary '@../examples/valid/payloads/user.json' -v\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_multipart_to_curl() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/multipart.reqlang -f curl -P name=Ada"
+        );
+
+        assert_success!(
+            assert,
+            Some("curl -X POST https://httpbin.org/post --http1.1 -F 'name=Ada' -F 'profile=@../examples/valid/payloads/user.json;type=application/json' -v\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_with_auth() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/auth.reqlang -f curl -S token=abc123 -P api_key=xyz"
+        );
+
+        assert_success!(
+            assert,
+            Some("curl https://httpbin.org/bearer?api_key=xyz --http1.1 -H \"authorization: Bearer abc123\" -v\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_with_redacted_auth() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/auth.reqlang -f http --redact -S token=abc123 -P api_key=xyz"
+        );
+
+        assert_success!(
+            assert,
+            Some("GET https://httpbin.org/bearer?api_key=%3Credacted%3E HTTP/1.1\nauthorization: Bearer <redacted>\n\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_workspace_request_file() {
+        let assert = assert_command!(
+            "reqlang export ../examples/workspace/users/get_user.reqlang -f http -e dev"
+        );
+
+        assert_success!(
+            assert,
+            Some("GET https://httpbin.org/anything/users/1 HTTP/1.1\n\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_request_file_with_imports() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/imports.reqlang -f http -e prod -S api_key=abc123"
+        );
+
+        assert_success!(
+            assert,
+            Some("GET https://httpbin.org/anything HTTP/1.1\nx-api-key: abc123\n\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_first_named_request() {
+        let assert =
+            assert_command!("reqlang export ../examples/valid/named_requests.reqlang -f http");
+
+        assert_success!(
+            assert,
+            Some("GET https://httpbin.org/anything/users HTTP/1.1\n\n"),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_named_request() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/named_requests.reqlang -f http --request create-user -S api_key=abc123"
+        );
+
+        assert_success!(
+            assert,
+            Some(concat!(
+                "POST https://httpbin.org/anything/users HTTP/1.1\n",
+                "x-api-key: abc123\n",
+                "\n",
+                "{\"name\": \"Ada\"}\n\n\n"
+            )),
+            None::<String>
+        );
+    }
+
+    #[test]
+    fn export_undefined_named_request() {
+        let assert = assert_command!(
+            "reqlang export ../examples/valid/named_requests.reqlang -f http --request delete-user"
+        );
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(stdout.contains("'delete-user' is not a defined request in the request file"));
+    }
+
     #[test]
     fn export_to_invalid_format() {
         let assert =
@@ -530,13 +967,31 @@ mod cli_integration_tests {
             None::<String>,
             Some(concat!(
                 "error: invalid value 'invalid' for '--format <format>'\n",
-                "  [possible values: http, json, body]\n",
+                "  [possible values: http, json, body, captures]\n",
                 "\n",
                 "For more information, try '--help'.\n"
             ))
         );
     }
 
+    #[test]
+    fn run_with_captures_format() {
+        let assert = assert_command!("reqlang run ../examples/valid/captures.reqlang -f captures");
+
+        assert_success!(
+            assert,
+            Some(concat!(
+                "{\n",
+                "  \"author\": \"Yours Truly\",\n",
+                "  \"content_type\": \"application/json\",\n",
+                "  \"first_slide_title\": \"Wake up to WonderWidgets!\",\n",
+                "  \"status\": \"200\"\n",
+                "}\n"
+            )),
+            None::<String>
+        );
+    }
+
     #[test]
     fn run_with_body_format() {
         let assert = assert_command!("reqlang run ../examples/valid/base64decode.reqlang -f body");
@@ -544,6 +999,44 @@ mod cli_integration_tests {
         assert_success!(assert, Some("HTTPBIN is awesome\n"), None::<String>);
     }
 
+    #[test]
+    fn run_image_with_output() {
+        let output_path = std::env::temp_dir().join("reqlang-run-image.png");
+        let _ = fs::remove_file(&output_path);
+
+        let command = format!(
+            "reqlang run ../examples/valid/image.reqlang -P type=png --output {}",
+            output_path.display()
+        );
+
+        let assert = assert_command!(command);
+
+        assert_success!(assert, Some(""), None::<String>);
+
+        let image = fs::read(&output_path).unwrap();
+
+        assert!(image.starts_with(&[0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]));
+    }
+
+    #[test]
+    fn run_stream_conflicts_with_output() {
+        let assert = assert_command!(
+            "reqlang run ../examples/valid/stream.reqlang -P number=3 --stream --output stream.txt"
+        );
+
+        assert_failure!(
+            assert,
+            None::<String>,
+            Some(concat!(
+                "error: the argument '--stream' cannot be used with '--output <file>'\n",
+                "\n",
+                "Usage: reqlang run --prompt <prompts> --stream <path>\n",
+                "\n",
+                "For more information, try '--help'.\n"
+            ))
+        );
+    }
+
     #[test]
     fn run_mismatch_response_with_response_assertion() {
         let expected_stderr = textwrap::dedent(
@@ -554,32 +1047,9 @@ mod cli_integration_tests {
               +HTTP/1.1 200 OK
               -x-test-value: ...
               
-               {
-                 "slideshow": {
-              -    "author": "Yours Truly",
-              +    "author": "Yours Truly", 
-              +    "date": "date of publication", 
-                   "slides": [
-                     {
-                       "title": "Wake up to WonderWidgets!", 
-                       "type": "all"
-                     }, 
-                     {
-                       "items": [
-                         "Why <em>WonderWidgets</em> are great", 
-                         "Who <em>buys</em> WonderWidgets"
-                       ], 
-                       "title": "Overview", 
-                       "type": "all"
-                     }
-                   ], 
-              -    "title": "Test Slide Show"
-              -  },
-              -  "extra": true
-              +    "title": "Sample Slide Show"
-              +  }
-               }
-              -
+              $.extra: expected true got nothing
+              $.slideshow.title: expected "Test Slide Show" got "Sample Slide Show"
+              $.slideshow.date: expected nothing got "date of publication"
               
             "#,
         )
@@ -592,6 +1062,138 @@ mod cli_integration_tests {
         assert_failure!(assert, None::<String>, Some(expected_stderr));
     }
 
+    #[test]
+    fn run_all_workspace() {
+        let expected_stdout = textwrap::dedent(
+            "
+            RESULT  STATUS  REQUEST FILE
+            PASS    201     status_code.reqlang
+            PASS    200     users/get_user.reqlang
+
+            2 passed; 0 failed; 0 errored
+            ",
+        )
+        .trim_start()
+        .to_string();
+
+        let assert = assert_command!("reqlang run-all ../examples/workspace -e dev --test");
+
+        assert_success!(assert, Some(expected_stdout), None::<String>);
+    }
+
+    #[test]
+    fn run_all_provides_secrets_for_each_request() {
+        let dir = std::env::temp_dir().join("reqlang-run-all-secrets");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::copy(
+            "../examples/valid/named_requests.reqlang",
+            dir.join("named_requests.reqlang"),
+        )
+        .unwrap();
+
+        let command = format!("reqlang run-all {} --secret-command echo", dir.display());
+
+        let assert = assert_command!(command);
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+        let stderr = str::from_utf8(&output.stderr).unwrap();
+
+        // create-user references the secret, list-users doesn't
+        assert!(stdout.contains("named_requests.reqlang#create-user"));
+        assert!(!stderr.contains("Secret required but not passed"));
+        assert!(!stdout.contains("Secret required but not passed"));
+    }
+
+    #[test]
+    fn run_all_invalid_request_files() {
+        let assert = assert_command!("reqlang run-all ../examples/invalid");
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(stdout.starts_with("RESULT  STATUS  REQUEST FILE\nERROR   -       empty.reqlang\n"));
+        assert!(stdout.ends_with("\n0 passed; 0 failed; 16 errored\n"));
+    }
+
+    #[test]
+    fn run_all_tap_report() {
+        let assert = assert_command!("reqlang run-all ../examples/invalid --test --report tap");
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(stdout.starts_with("TAP version 13\n1..16\nnot ok 1 - empty.reqlang request\n"));
+        assert!(!stdout.contains("RESULT  STATUS  REQUEST FILE"));
+    }
+
+    #[test]
+    fn run_all_junit_report() {
+        let report_path = std::env::temp_dir().join("reqlang-run-all-junit-report.xml");
+        let _ = fs::remove_file(&report_path);
+
+        let command = format!(
+            "reqlang run-all ../examples/invalid --test --report junit={}",
+            report_path.display()
+        );
+
+        let assert = assert_command!(command);
+
+        let output = assert.get_output();
+        let stdout = str::from_utf8(&output.stdout).unwrap();
+
+        assert!(!output.status.success());
+        assert!(stdout.ends_with("\n0 passed; 0 failed; 16 errored\n"));
+
+        let report = fs::read_to_string(&report_path).unwrap();
+
+        assert!(report.contains(
+            r#"<testsuites name="reqlang" tests="16" failures="0" errors="16" time="0.000">"#
+        ));
+        assert!(report.contains(
+            r#"<testsuite name="empty.reqlang" tests="1" failures="0" errors="1" time="0.000">"#
+        ));
+    }
+
+    #[test]
+    fn run_report_requires_test() {
+        let assert =
+            assert_command!("reqlang run ../examples/valid/status_code.reqlang --report tap");
+
+        assert_failure!(
+            assert,
+            None::<String>,
+            Some(concat!(
+                "error: the following required arguments were not provided:\n",
+                "  --test\n",
+                "\n",
+                "Usage: reqlang run --test --report <FORMAT[=PATH]> <path>\n",
+                "\n",
+                "For more information, try '--help'.\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn run_with_invalid_report_format() {
+        let assert = assert_command!(
+            "reqlang run ../examples/valid/status_code.reqlang --test --report xml"
+        );
+
+        assert_failure!(
+            assert,
+            None::<String>,
+            Some(concat!(
+                "error: invalid value 'xml' for '--report <FORMAT[=PATH]>': Unknown report format: xml\n",
+                "\n",
+                "For more information, try '--help'.\n"
+            ))
+        );
+    }
+
     #[test]
     fn run_mismatch_response_without_response_assertion() {
         let assert = assert_command!("reqlang run ../examples/valid/mismatch_response.reqlang");
@@ -667,12 +1269,12 @@ mod cli_integration_tests {
                 {
                   "range": {
                     "start": {
-                      "line": 1,
-                      "character": 0
+                      "line": 2,
+                      "character": 8
                     },
                     "end": {
                       "line": 2,
-                      "character": 12
+                      "character": 11
                     }
                   },
                   "severity": 1,
@@ -701,12 +1303,12 @@ mod cli_integration_tests {
                 {
                   "range": {
                     "start": {
-                      "line": 1,
-                      "character": 0
+                      "line": 2,
+                      "character": 8
                     },
                     "end": {
-                      "line": 4,
-                      "character": 6
+                      "line": 2,
+                      "character": 11
                     }
                   },
                   "severity": 1,
@@ -735,12 +1337,12 @@ mod cli_integration_tests {
                 {
                   "range": {
                     "start": {
-                      "line": 1,
-                      "character": 0
+                      "line": 7,
+                      "character": 6
                     },
                     "end": {
                       "line": 7,
-                      "character": 12
+                      "character": 11
                     }
                   },
                   "severity": 1,
@@ -777,4 +1379,43 @@ mod cli_integration_tests {
 
         assert_success!(assert, Some("Foo\n"), None::<String>);
     }
+
+    #[test]
+    fn run_invalid_timeout() {
+        let assert =
+            assert_command!("reqlang run ../examples/valid/status_code.reqlang --timeout 30");
+
+        let expected_stderr = textwrap::dedent(
+            "
+            error: invalid value '30' for '--timeout <duration>': Invalid duration '30', expected a number followed by ms, s, or m (e.g. 30s)
+
+            For more information, try '--help'.
+            ",
+        )
+        .trim_start()
+        .to_string();
+
+        assert_failure!(assert, None::<String>, Some(expected_stderr));
+    }
+
+    #[test]
+    fn run_no_follow_redirects_with_max_redirects() {
+        let assert = assert_command!(
+            "reqlang run ../examples/valid/status_code.reqlang --no-follow-redirects --max-redirects 2"
+        );
+
+        let expected_stderr = textwrap::dedent(
+            "
+            error: the argument '--no-follow-redirects' cannot be used with '--max-redirects <count>'
+
+            Usage: reqlang run --no-follow-redirects <path>
+
+            For more information, try '--help'.
+            ",
+        )
+        .trim_start()
+        .to_string();
+
+        assert_failure!(assert, None::<String>, Some(expected_stderr));
+    }
 }
@@ -4,24 +4,43 @@ use std::ops::Deref;
 use anyhow::{Context, Result};
 use reqlang::prelude::*;
 use reqlang::{
+    auth::resolve_auth,
+    completion::{completions, CompletionKind},
+    dependencies::resolve_dependencies,
     diagnostics::{
-        get_diagnostics, Diagnosis, DiagnosisPosition, DiagnosisRange, DiagnosisSeverity,
+        get_diagnostics, get_lint_diagnostics, Diagnosis, DiagnosisPosition, DiagnosisRange,
+        DiagnosisSeverity,
     },
     errors::ReqlangError,
     export::{export, RequestFormat},
-    types::{ParseResult, RequestParamsFromClient},
+    extractor::{capture, CapturedValues},
+    hover::hover,
+    lint::lint,
+    parser::{parse_with_context, ParseContext},
+    str_idxpos::{index_to_position, position_to_index},
+    symbols::{definitions, references, rename, symbol_span},
+    templater::template_with_context,
+    types::{
+        client::ParsedConfigClient, http::HttpResponse, ParseResult, ParsedRequestFile,
+        RequestParamsFromClient, TemplatedRequestFile,
+    },
 };
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::Mutex;
-use tower_lsp::jsonrpc::Result as RpcResult;
+use tower_lsp::jsonrpc::{ErrorCode, Result as RpcResult};
 use tower_lsp::lsp_types::notification::Notification;
 use tower_lsp::lsp_types::{
-    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
-    DidSaveTextDocumentParams, ExecuteCommandOptions, ExecuteCommandParams, InitializeParams,
-    InitializeResult, MessageType, Position, Range, SaveOptions, ServerCapabilities, ServerInfo,
-    TextDocumentSyncKind, TextDocumentSyncOptions,
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, ExecuteCommandOptions,
+    ExecuteCommandParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, InitializeParams, InitializeResult, Location,
+    MarkupContent, MarkupKind, MessageType, NumberOrString, OneOf, Position, PrepareRenameResponse,
+    Range, ReferenceParams, RenameOptions, RenameParams, SaveOptions, ServerCapabilities,
+    ServerInfo, TextDocumentPositionParams, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextEdit, WorkspaceEdit,
 };
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
@@ -38,14 +57,43 @@ impl Backend {
         }
     }
 
+    /// The text of an open file, the file parsed, and the index of `position` in the text
+    ///
+    /// Files that don't parse return [None].
+    async fn parse_at(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Option<(String, ParsedRequestFile, usize)> {
+        let source = self.file_texts.lock().await.get(uri)?.clone();
+
+        let reqfile = parse_with_context(&Ast::from(&source), &parse_context(uri)).ok()?;
+
+        let index = position_to_index(
+            &source,
+            (position.line as usize, position.character as usize),
+        );
+
+        Some((source, reqfile, index))
+    }
+
     async fn parse_file_for_client(&self, uri: &Url, source: &str) {
         let ast = Ast::from(source);
 
-        let result = match parse(&ast) {
+        let result = match parse_with_context(&ast, &parse_context(uri)) {
             Ok(parsed_request_file) => {
-                // Clear diagnostics on the client
+                // Replace any error diagnostics with the lint findings
+                let findings = lint(&parsed_request_file, source, &HashMap::new());
+
                 self.client
-                    .publish_diagnostics(uri.clone(), vec![], None)
+                    .publish_diagnostics(
+                        uri.clone(),
+                        get_lint_diagnostics(&findings, source)
+                            .into_iter()
+                            .map(|x| LspDiagnosis(x).into())
+                            .collect(),
+                        None,
+                    )
                     .await;
 
                 Ok(parsed_request_file.into())
@@ -113,6 +161,22 @@ impl LanguageServer for Backend {
                     ],
                     work_done_progress_options: Default::default(),
                 }),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(
+                        [":", "?", "!", "@", ">", "[", "."]
+                            .into_iter()
+                            .map(String::from)
+                            .collect(),
+                    ),
+                    ..Default::default()
+                }),
                 text_document_sync: Some(
                     tower_lsp::lsp_types::TextDocumentSyncCapability::Options(
                         TextDocumentSyncOptions {
@@ -156,6 +220,9 @@ impl LanguageServer for Backend {
         let source = &params.content_changes.first().unwrap().text;
 
         self.parse_file_for_client(&uri, source).await;
+
+        let mut file_texts = self.file_texts.lock().await;
+        file_texts.insert(uri.clone(), source.clone());
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
@@ -168,6 +235,140 @@ impl LanguageServer for Backend {
         file_texts.insert(uri.clone(), source.clone());
     }
 
+    async fn hover(&self, params: HoverParams) -> RpcResult<Option<Hover>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        // Hover information is only available for request files that parse
+        let Some((source, reqfile, index)) = self.parse_at(&uri, position).await else {
+            return Ok(None);
+        };
+
+        Ok(hover(&reqfile, index).map(|hover| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: hover.contents,
+            }),
+            range: Some(span_to_range(&source, &hover.span)),
+        }))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> RpcResult<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+
+        let Some((source, reqfile, index)) = self.parse_at(&uri, position).await else {
+            return Ok(None);
+        };
+
+        let locations: Vec<Location> = definitions(&reqfile, &source, index)
+            .iter()
+            .map(|span| Location::new(uri.clone(), span_to_range(&source, span)))
+            .collect();
+
+        if locations.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(GotoDefinitionResponse::Array(locations)))
+    }
+
+    async fn references(&self, params: ReferenceParams) -> RpcResult<Option<Vec<Location>>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let Some((source, reqfile, index)) = self.parse_at(&uri, position).await else {
+            return Ok(None);
+        };
+
+        let include_declaration = params.context.include_declaration;
+
+        Ok(Some(
+            references(&reqfile, &source, index, include_declaration)
+                .iter()
+                .map(|span| Location::new(uri.clone(), span_to_range(&source, span)))
+                .collect(),
+        ))
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> RpcResult<Option<PrepareRenameResponse>> {
+        let Some((source, reqfile, index)) = self
+            .parse_at(&params.text_document.uri, params.position)
+            .await
+        else {
+            return Ok(None);
+        };
+
+        Ok(symbol_span(&reqfile, &source, index)
+            .map(|span| PrepareRenameResponse::Range(span_to_range(&source, &span))))
+    }
+
+    async fn rename(&self, params: RenameParams) -> RpcResult<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let Some((source, reqfile, index)) = self.parse_at(&uri, position).await else {
+            return Ok(None);
+        };
+
+        let edits = rename(&reqfile, &source, index, &params.new_name)
+            .map_err(|err| tower_lsp::jsonrpc::Error::invalid_params(err.to_string()))?
+            .into_iter()
+            .map(|(new_text, span)| TextEdit::new(span_to_range(&source, &span), new_text))
+            .collect();
+
+        // All of the edits are applied together
+        Ok(Some(WorkspaceEdit::new(HashMap::from([(uri, edits)]))))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> RpcResult<Option<CompletionResponse>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+
+        let file_texts = self.file_texts.lock().await;
+
+        let Some(source) = file_texts.get(&uri) else {
+            return Ok(None);
+        };
+
+        let index = position_to_index(
+            source,
+            (position.line as usize, position.character as usize),
+        );
+
+        let items: Vec<CompletionItem> = completions(source, index, &parse_context(&uri))
+            .into_iter()
+            .map(|completion| CompletionItem {
+                label: completion.label,
+                kind: Some(match completion.kind {
+                    CompletionKind::Variable => CompletionItemKind::VARIABLE,
+                    CompletionKind::Prompt => CompletionItemKind::VALUE,
+                    CompletionKind::Secret => CompletionItemKind::CONSTANT,
+                    CompletionKind::Provider | CompletionKind::Dependency => {
+                        CompletionItemKind::REFERENCE
+                    }
+                    CompletionKind::Header => CompletionItemKind::FIELD,
+                    CompletionKind::ConfigKey => CompletionItemKind::PROPERTY,
+                    CompletionKind::Environment => CompletionItemKind::ENUM_MEMBER,
+                }),
+                detail: completion.detail,
+                ..Default::default()
+            })
+            .collect();
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
     async fn execute_command(&self, params: ExecuteCommandParams) -> RpcResult<Option<Value>> {
         self.client
             .log_message(
@@ -177,7 +378,7 @@ impl LanguageServer for Backend {
             .await;
 
         if params.command.as_str() == "reqlang.executeRequest" {
-            let from_client_params_value = params.arguments.first().expect("Should be present");
+            let from_client_params_value = command_argument(&params)?;
 
             self.client
                 .log_message(MessageType::INFO, format!("{from_client_params_value:?}"))
@@ -187,27 +388,32 @@ impl LanguageServer for Backend {
             let from_client_params =
                 Into::<RequestParamsFromClient>::into(from_client_params_value.clone());
 
-            let mut provider_values: HashMap<String, String> = HashMap::new();
-
-            let env = from_client_params.env.as_deref();
+            let context = from_client_params.parse_context();
 
-            if let Some(env) = env {
-                provider_values.insert("env".to_string(), env.to_string());
-            }
-
-            let reqfile = template(
+            let reqfile = resolve_reqfile(
                 &from_client_params.reqfile,
                 from_client_params.env.as_deref(),
                 &from_client_params.prompts,
                 &from_client_params.secrets,
-                &provider_values,
+                from_client_params.provider_values.clone(),
+                &context,
             )
-            .expect("Should get templated request file");
+            .await
+            .map_err(resolve_error)?;
+
+            let (captures, assert_options) =
+                parse_with_context(&Ast::from(&from_client_params.reqfile), &context)
+                    .map(|reqfile| (reqfile.captures(), reqfile.assert_options()))
+                    .unwrap_or_default();
 
-            let response = Into::<HttpRequestFetcher>::into(from_client_params)
+            let response = HttpRequestFetcher::from(reqfile.clone())
                 .fetch()
                 .await
-                .expect("Request should have succeeded");
+                .map_err(|err| tower_lsp::jsonrpc::Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Request failed: {err}"),
+                    data: None,
+                })?;
 
             self.client
                 .log_message(
@@ -224,7 +430,9 @@ impl LanguageServer for Backend {
                 .await;
 
             if let Some(expected_response) = reqfile.response {
-                if let Err(diffs) = assert_response(&expected_response, &response) {
+                if let Err(diffs) =
+                    assert_response_with(&expected_response, &response, &assert_options)
+                {
                     self.client
                         .log_message(
                             MessageType::WARNING,
@@ -234,15 +442,20 @@ impl LanguageServer for Backend {
                 }
             };
 
+            let result = ExecuteRequestResult {
+                captures: capture(&captures, &response),
+                response,
+            };
+
             return Ok(Some(
-                serde_json::to_string(&response)
+                serde_json::to_string(&result)
                     .expect("Should serialize to json")
                     .into(),
             ));
         };
 
         if params.command.as_str() == "reqlang.exportRequest" {
-            let from_client_params_value = params.arguments.first().expect("Should be present");
+            let from_client_params_value = command_argument(&params)?;
 
             self.client
                 .log_message(MessageType::INFO, format!("{from_client_params_value:?}"))
@@ -252,28 +465,34 @@ impl LanguageServer for Backend {
             let from_client_params: FromClientExportRequestParams =
                 from_client_params_value.clone().into();
 
-            // Setup provider values
-            let env = from_client_params.env.as_deref();
-            let mut provider = HashMap::new();
-
-            provider.insert("env".to_string(), env.unwrap_or_default().to_string());
-
             // Get reqfile text content
-            let url = Url::parse(&from_client_params.uri).expect("Should be a valid url");
-            let file_texts = self.file_texts.lock().await;
-            let text = file_texts.get(&url).expect("Should be present");
-
-            // Template the reqfile
-            let templated_reqfile = template(
-                text,
-                env,
+            let url = Url::parse(&from_client_params.uri)
+                .map_err(|err| tower_lsp::jsonrpc::Error::invalid_params(err.to_string()))?;
+            let text = self
+                .file_texts
+                .lock()
+                .await
+                .get(&url)
+                .cloned()
+                .ok_or_else(|| {
+                    tower_lsp::jsonrpc::Error::invalid_params(format!("'{url}' isn't open"))
+                })?;
+
+            let templated_reqfile = resolve_reqfile(
+                &text,
+                from_client_params.env.as_deref(),
                 &from_client_params.prompts,
                 &from_client_params.secrets,
-                &provider,
+                HashMap::new(),
+                &parse_context(&url),
             )
-            .expect("Should have templated");
+            .await
+            .map_err(resolve_error)?;
 
-            let exported = export(&templated_reqfile.request, from_client_params.format);
+            let exported = export(
+                &templated_reqfile.authenticated_request(false),
+                from_client_params.format,
+            );
 
             return Ok(Some(exported.into()));
         }
@@ -286,6 +505,94 @@ impl LanguageServer for Backend {
     }
 }
 
+/// The LSP [Range] of a span in a source
+fn span_to_range(source: &str, span: &Span) -> Range {
+    let position = |index: usize| {
+        let (line, character) = index_to_position(source, index);
+
+        Position::new(line as u32, character as u32)
+    };
+
+    Range::new(position(span.start), position(span.end))
+}
+
+/// The first argument of a command
+fn command_argument(params: &ExecuteCommandParams) -> RpcResult<&Value> {
+    params.arguments.first().ok_or_else(|| {
+        tower_lsp::jsonrpc::Error::invalid_params(format!(
+            "The command '{}' requires an argument",
+            params.command
+        ))
+    })
+}
+
+/// Execute dependencies, obtain auth values, then template the request file
+async fn resolve_reqfile(
+    source: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    mut provider_values: HashMap<String, String>,
+    context: &ParseContext,
+) -> Result<TemplatedRequestFile, Vec<Spanned<ReqlangError>>> {
+    provider_values.insert("env".to_string(), env.unwrap_or_default().to_string());
+
+    // Execute any request files this one depends on
+    let dependency_values = resolve_dependencies(
+        context.path.clone().unwrap_or_default(),
+        source,
+        env,
+        prompts,
+        secrets,
+        &provider_values,
+        context.shared_config.as_ref(),
+    )
+    .await?;
+
+    provider_values.extend(dependency_values);
+
+    // Obtain any auth values (e.g. an OAuth2 access token)
+    let auth_values = resolve_auth(
+        source,
+        env,
+        prompts,
+        secrets,
+        &provider_values,
+        &ParsedConfigClient::default(),
+        context,
+    )
+    .await?;
+
+    provider_values.extend(auth_values);
+
+    template_with_context(source, env, prompts, secrets, &provider_values, context)
+}
+
+/// Error sent to the client when a request file can't be resolved
+fn resolve_error(errs: Vec<Spanned<ReqlangError>>) -> tower_lsp::jsonrpc::Error {
+    let messages: Vec<String> = errs.iter().map(|(err, _)| err.to_string()).collect();
+
+    tower_lsp::jsonrpc::Error::invalid_params(messages.join("\n"))
+}
+
+/// The [ParseContext] for a file, sharing the declarations of its workspace
+fn parse_context(uri: &Url) -> ParseContext {
+    uri.to_file_path()
+        .ok()
+        .and_then(|path| ParseContext::for_path(path).ok())
+        .unwrap_or_default()
+}
+
+/// Result sent to the client after executing a request
+///
+/// This is the response with the values captured from it
+#[derive(Debug, Serialize)]
+struct ExecuteRequestResult {
+    #[serde(flatten)]
+    response: HttpResponse,
+    captures: CapturedValues,
+}
+
 /// Command parameters from client to export request
 ///
 /// This is useful for language server clients
@@ -408,10 +715,27 @@ impl From<LspDiagnosis> for Diagnostic {
     fn from(value: LspDiagnosis) -> Self {
         let range: LspDiagnosisRange = value.range.into();
 
+        // Locations in other files, like imported files
+        let related_information: Vec<DiagnosticRelatedInformation> = value
+            .related_information
+            .iter()
+            .filter_map(|related| {
+                let path = std::fs::canonicalize(&related.path).ok()?;
+                let range: LspDiagnosisRange = related.range.into();
+
+                Some(DiagnosticRelatedInformation {
+                    location: Location::new(Url::from_file_path(path).ok()?, range.into()),
+                    message: related.message.clone(),
+                })
+            })
+            .collect();
+
         Diagnostic {
             range: range.into(),
             severity: value.severity.map(|x| LspDiagnosisSeverity(x).into()),
+            code: value.code.clone().map(NumberOrString::String),
             message: value.message.clone(),
+            related_information: (!related_information.is_empty()).then_some(related_information),
             ..Default::default()
         }
     }
@@ -1,10 +1,142 @@
 use std::fmt::{self, Display};
 
-use crate::types::http::{HttpResponse, HttpStatusCode, HttpVersion};
+use crate::types::{
+    ParsedConfigAssert,
+    client::ClientDuration,
+    http::{HttpResponse, HttpStatusCode, HttpVersion},
+};
 use console::Style;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use similar::{ChangeTag, TextDiff};
 
+/// Matchers usable in the expected response: `{{*}}` or `{{/pattern/}}`
+pub const RESPONSE_MATCHER_PATTERN: &str = r"\{\{(\*|/.+?/)\}\}";
+
+/// Matcher in an expected response value
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResponseMatcher {
+    /// `{{*}}` matches any text, including none
+    Wildcard,
+    /// `{{/pattern/}}` matches text using a regular expression
+    Regex(String),
+}
+
+impl Display for ResponseMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseMatcher::Wildcard => write!(f, "{{{{*}}}}"),
+            ResponseMatcher::Regex(pattern) => write!(f, "{{{{/{pattern}/}}}}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ExpectedSegment {
+    Literal(String),
+    Matcher(ResponseMatcher),
+}
+
+impl ExpectedSegment {
+    fn to_regex(&self) -> String {
+        match self {
+            ExpectedSegment::Literal(text) => regex::escape(text),
+            ExpectedSegment::Matcher(ResponseMatcher::Wildcard) => "(?s:.*?)".to_string(),
+            ExpectedSegment::Matcher(ResponseMatcher::Regex(pattern)) => format!("(?:{pattern})"),
+        }
+    }
+}
+
+impl Display for ExpectedSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpectedSegment::Literal(text) => write!(f, "{text}"),
+            ExpectedSegment::Matcher(matcher) => write!(f, "{matcher}"),
+        }
+    }
+}
+
+/// Expected value from a response (status text, header value or body) split in to literal text and matchers
+///
+/// ```text
+/// x-request-id: {{/[a-f0-9-]+/}}
+/// date: {{*}}
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpectedValue(Vec<ExpectedSegment>);
+
+impl ExpectedValue {
+    /// Split an expected value in to literal text and matchers
+    pub fn parse(expected: &str) -> Result<Self, String> {
+        let re = Regex::new(RESPONSE_MATCHER_PATTERN).unwrap();
+
+        let mut segments = vec![];
+        let mut last_end = 0;
+
+        for captures in re.captures_iter(expected) {
+            let (whole, inner) = (captures.get(0).unwrap(), &captures[1]);
+
+            if whole.start() > last_end {
+                segments.push(ExpectedSegment::Literal(
+                    expected[last_end..whole.start()].to_string(),
+                ));
+            }
+
+            let matcher = match inner.strip_prefix('/').and_then(|x| x.strip_suffix('/')) {
+                Some(pattern) => {
+                    Regex::new(pattern)
+                        .map_err(|err| format!("Invalid regex matcher '{inner}': {err}"))?;
+
+                    ResponseMatcher::Regex(pattern.to_string())
+                }
+                None => ResponseMatcher::Wildcard,
+            };
+
+            segments.push(ExpectedSegment::Matcher(matcher));
+            last_end = whole.end();
+        }
+
+        if last_end < expected.len() {
+            segments.push(ExpectedSegment::Literal(expected[last_end..].to_string()));
+        }
+
+        Ok(ExpectedValue(segments))
+    }
+
+    /// If the expected value contains any matchers
+    pub fn has_matchers(&self) -> bool {
+        self.0
+            .iter()
+            .any(|segment| matches!(segment, ExpectedSegment::Matcher(_)))
+    }
+
+    /// If the actual value satisfies the expected value
+    pub fn is_match(&self, actual: &str) -> bool {
+        self.regex(self.0.len(), true).is_match(actual)
+    }
+
+    /// Find the first part of the expected value that the actual value doesn't satisfy
+    pub fn first_unmatched(&self, actual: &str) -> Option<String> {
+        if self.is_match(actual) {
+            return None;
+        }
+
+        let index = (1..=self.0.len())
+            .find(|end| !self.regex(*end, false).is_match(actual))
+            .unwrap_or(self.0.len());
+
+        self.0.get(index.saturating_sub(1)).map(|x| x.to_string())
+    }
+
+    fn regex(&self, end: usize, anchor_end: bool) -> Regex {
+        let pattern: String = self.0[..end].iter().map(|x| x.to_regex()).collect();
+        let anchor = if anchor_end { "$" } else { "" };
+
+        Regex::new(&format!("^{pattern}{anchor}")).unwrap()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ResponseDiff {
     StatusCode {
@@ -29,6 +161,125 @@ pub enum ResponseDiff {
         expected: Option<String>,
         actual: Option<String>,
     },
+    /// Status text didn't satisfy the matchers in the expected status text
+    UnmatchedStatusText {
+        expected: String,
+        actual: String,
+        unmatched: String,
+    },
+    /// Header value didn't satisfy the matchers in the expected header value
+    UnmatchedHeaderValue {
+        header: String,
+        expected: String,
+        actual: String,
+        unmatched: String,
+    },
+    /// Body didn't satisfy the matchers in the expected body
+    UnmatchedBody {
+        expected: String,
+        actual: Option<String>,
+        unmatched: String,
+    },
+    /// JSON body is structurally different from the expected JSON body
+    JsonBody(Vec<JsonBodyDiff>),
+    /// Response took longer than the `max_latency` assert option
+    Latency {
+        max: ClientDuration,
+        actual_ms: f64,
+    },
+}
+
+/// Difference between the expected and actual JSON bodies at a JSONPath
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum JsonBodyDiff {
+    /// Expected value is missing from the actual body
+    Missing { path: String, expected: Value },
+    /// Actual value isn't in the expected body
+    Unexpected { path: String, actual: Value },
+    /// Actual value is different from the expected value
+    Mismatch {
+        path: String,
+        expected: Value,
+        actual: Value,
+    },
+}
+
+impl Display for JsonBodyDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonBodyDiff::Missing { path, expected } => {
+                write!(f, "{path}: expected {expected} got nothing")
+            }
+            JsonBodyDiff::Unexpected { path, actual } => {
+                write!(f, "{path}: expected nothing got {actual}")
+            }
+            JsonBodyDiff::Mismatch {
+                path,
+                expected,
+                actual,
+            } => write!(f, "{path}: expected {expected} got {actual}"),
+        }
+    }
+}
+
+impl Display for ResponseDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseDiff::StatusCode { expected, actual } => {
+                write!(f, "Expected status code {expected} got {actual}")
+            }
+            ResponseDiff::StatusText { expected, actual } => {
+                write!(f, "Expected status text '{expected}' got '{actual}'")
+            }
+            ResponseDiff::HttpVersion { expected, actual } => {
+                write!(f, "Expected HTTP version {expected} got {actual}")
+            }
+            ResponseDiff::MissingHeader(header) => {
+                write!(f, "Expected header '{header}' got nothing")
+            }
+            ResponseDiff::MismatchHeaderValue {
+                header,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected header '{header}' to be '{expected}' got '{actual}'"
+            ),
+            ResponseDiff::Body { expected, actual } => write!(
+                f,
+                "Body is different from the expected body\nExpected:\n{}\nGot:\n{}",
+                expected.as_deref().unwrap_or_default().trim_end(),
+                actual.as_deref().unwrap_or_default().trim_end()
+            ),
+            ResponseDiff::UnmatchedStatusText {
+                actual, unmatched, ..
+            } => write!(f, "Status text '{actual}' did not match: {unmatched}"),
+            ResponseDiff::UnmatchedHeaderValue {
+                header,
+                actual,
+                unmatched,
+                ..
+            } => write!(
+                f,
+                "Header '{header}' value '{actual}' did not match: {unmatched}"
+            ),
+            ResponseDiff::UnmatchedBody { unmatched, .. } => {
+                write!(f, "Body did not match: {unmatched}")
+            }
+            ResponseDiff::JsonBody(diffs) => {
+                write!(f, "JSON body is different:")?;
+
+                for diff in diffs {
+                    write!(f, "\n{diff}")?;
+                }
+
+                Ok(())
+            }
+            ResponseDiff::Latency { max, actual_ms } => {
+                write!(f, "Expected a response within {max} got {actual_ms:.1} ms")
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -53,6 +304,9 @@ impl ResponseDiffs {
         let mut header_diffs: Vec<(String, String)> = vec![];
         let mut body_diff: Option<(String, String)> = None;
 
+        let mut unmatched_notes: Vec<String> = vec![];
+        let mut json_body_diffs: Vec<String> = vec![];
+
         for response_diff in self.0.iter() {
             match response_diff {
                 ResponseDiff::StatusCode { expected, actual } => {
@@ -83,6 +337,43 @@ impl ResponseDiffs {
                         actual.as_ref().cloned().unwrap_or_default(),
                     ))
                 }
+                ResponseDiff::UnmatchedStatusText {
+                    expected,
+                    actual,
+                    unmatched,
+                } => {
+                    status_text_diff = Some((expected.to_string(), actual.to_string()));
+                    unmatched_notes.push(format!("Status text did not match: {unmatched}"));
+                }
+                ResponseDiff::UnmatchedHeaderValue {
+                    header,
+                    expected,
+                    actual,
+                    unmatched,
+                } => {
+                    header_diffs.push((
+                        format!("{header}: {expected}"),
+                        format!("{header}: {actual}"),
+                    ));
+                    unmatched_notes.push(format!("Header '{header}' did not match: {unmatched}"));
+                }
+                ResponseDiff::UnmatchedBody {
+                    expected,
+                    actual,
+                    unmatched,
+                } => {
+                    body_diff = Some((
+                        expected.clone(),
+                        actual.as_ref().cloned().unwrap_or_default(),
+                    ));
+                    unmatched_notes.push(format!("Body did not match: {unmatched}"));
+                }
+                ResponseDiff::JsonBody(diffs) => {
+                    json_body_diffs.extend(diffs.iter().map(|diff| diff.to_string()));
+                }
+                ResponseDiff::Latency { .. } => {
+                    unmatched_notes.push(response_diff.to_string());
+                }
             };
         }
 
@@ -239,6 +530,25 @@ impl ResponseDiffs {
             }
         }
 
+        if !json_body_diffs.is_empty() {
+            output.push('\n');
+
+            for json_body_diff in json_body_diffs.iter() {
+                output.push_str(&format!(
+                    "{}\n",
+                    Style::new().red().apply_to(json_body_diff)
+                ));
+            }
+        }
+
+        if !unmatched_notes.is_empty() {
+            output.push('\n');
+
+            for note in unmatched_notes.iter() {
+                output.push_str(&format!("{}\n", Style::new().yellow().apply_to(note)));
+            }
+        }
+
         output
     }
 }
@@ -247,6 +557,17 @@ impl ResponseDiffs {
 pub fn assert_response(
     expected: &HttpResponse,
     actual: &HttpResponse,
+) -> Result<(), Box<ResponseDiffs>> {
+    assert_response_with(expected, actual, &ParsedConfigAssert::default())
+}
+
+/// Asserts that the `actual` response matches the `expected` response using the assert options from the config.
+///
+/// JSON object and array bodies are compared structurally, ignoring key order and whitespace.
+pub fn assert_response_with(
+    expected: &HttpResponse,
+    actual: &HttpResponse,
+    options: &ParsedConfigAssert,
 ) -> Result<(), Box<ResponseDiffs>> {
     let mut differences: Vec<ResponseDiff> = vec![];
 
@@ -257,39 +578,119 @@ pub fn assert_response(
         });
     }
 
-    if expected.status_text != actual.status_text {
-        differences.push(ResponseDiff::StatusText {
-            expected: expected.status_text.clone(),
-            actual: actual.status_text.clone(),
-        });
+    match expected_value(&expected.status_text) {
+        Some(expected_status_text) => {
+            if let Some(unmatched) = expected_status_text.first_unmatched(&actual.status_text) {
+                differences.push(ResponseDiff::UnmatchedStatusText {
+                    expected: expected.status_text.clone(),
+                    actual: actual.status_text.clone(),
+                    unmatched,
+                });
+            }
+        }
+        None => {
+            if expected.status_text != actual.status_text {
+                differences.push(ResponseDiff::StatusText {
+                    expected: expected.status_text.clone(),
+                    actual: actual.status_text.clone(),
+                });
+            }
+        }
     }
 
-    for (expected_key, expected_value) in expected.headers.iter() {
-        let maybe_actual_header = actual.headers.iter().find(|x| x.0 == *expected_key);
+    for (expected_key, expected_value_str) in expected.headers.iter() {
+        // Header names are case insensitive
+        let actual_values: Vec<&String> = actual
+            .headers
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case(expected_key))
+            .map(|(_, value)| value)
+            .collect();
 
-        match maybe_actual_header {
-            Some((_, actual_value)) => {
-                if actual_value != expected_value {
+        let Some(first_actual_value) = actual_values.first() else {
+            differences.push(ResponseDiff::MissingHeader(expected_key.clone()));
+            continue;
+        };
+
+        match expected_value(expected_value_str) {
+            Some(expected_header_value) => {
+                let is_match = actual_values
+                    .iter()
+                    .any(|actual_value| expected_header_value.is_match(actual_value));
+
+                if !is_match {
+                    differences.push(ResponseDiff::UnmatchedHeaderValue {
+                        header: expected_key.clone(),
+                        expected: expected_value_str.clone(),
+                        actual: first_actual_value.to_string(),
+                        unmatched: expected_header_value
+                            .first_unmatched(first_actual_value)
+                            .unwrap_or_default(),
+                    });
+                }
+            }
+            None => {
+                if !actual_values.contains(&expected_value_str) {
                     differences.push(ResponseDiff::MismatchHeaderValue {
                         header: expected_key.clone(),
-                        expected: expected_value.clone(),
-                        actual: actual_value.clone(),
+                        expected: expected_value_str.clone(),
+                        actual: first_actual_value.to_string(),
                     })
                 }
             }
-            None => {
-                differences.push(ResponseDiff::MissingHeader(expected_key.clone()));
+        }
+    }
+
+    if let Some(expected_body) = &expected.body {
+        let actual_body = actual.body.as_ref().map(|body| body.trim_end());
+
+        let json_bodies = json_body(expected_body).zip(actual_body.and_then(json_body));
+
+        match (json_bodies, expected_value(expected_body.trim_end())) {
+            (Some((expected_json, actual_json)), _) => {
+                let mut json_body_diffs = vec![];
+
+                compare_json(
+                    &expected_json,
+                    &actual_json,
+                    "$".to_string(),
+                    options.allow_extra_fields.unwrap_or_default(),
+                    &mut json_body_diffs,
+                );
+
+                if !json_body_diffs.is_empty() {
+                    differences.push(ResponseDiff::JsonBody(json_body_diffs));
+                }
+            }
+            (None, Some(expected_body_value)) => {
+                if let Some(unmatched) =
+                    expected_body_value.first_unmatched(actual_body.unwrap_or_default())
+                {
+                    differences.push(ResponseDiff::UnmatchedBody {
+                        expected: expected_body.clone(),
+                        actual: actual.body.clone(),
+                        unmatched,
+                    });
+                }
+            }
+            (None, None) => {
+                if Some(expected_body.trim_end()) != actual_body {
+                    differences.push(ResponseDiff::Body {
+                        expected: expected.body.clone(),
+                        actual: actual.body.clone(),
+                    });
+                }
             }
         }
     }
 
-    if expected.body.is_some()
-        && expected.body.as_ref().map(|body| body.trim_end())
-            != actual.body.as_ref().map(|body| body.trim_end())
+    // Only fetched responses have metrics
+    if let Some((max, metrics)) = options.max_latency.zip(actual.metrics.as_ref())
+        && metrics.total_ms > max.0.as_secs_f64() * 1000.0
     {
-        differences.push(ResponseDiff::Body {
-            expected: expected.body.clone(),
-            actual: actual.body.clone(),
+        differences.push(ResponseDiff::Latency {
+            max,
+            actual_ms: metrics.total_ms,
         });
     }
 
@@ -300,9 +701,130 @@ pub fn assert_response(
     Ok(())
 }
 
+/// Parse an expected value if it contains matchers, otherwise it's compared exactly
+fn expected_value(expected: &str) -> Option<ExpectedValue> {
+    ExpectedValue::parse(expected)
+        .ok()
+        .filter(ExpectedValue::has_matchers)
+}
+
+/// Parse a body as JSON if it's an object or array
+fn json_body(body: &str) -> Option<Value> {
+    serde_json::from_str(body)
+        .ok()
+        .filter(|json: &Value| json.is_object() || json.is_array())
+}
+
+fn compare_json(
+    expected: &Value,
+    actual: &Value,
+    path: String,
+    allow_extra_fields: bool,
+    diffs: &mut Vec<JsonBodyDiff>,
+) {
+    match (expected, actual) {
+        (Value::Object(expected_object), Value::Object(actual_object)) => {
+            for (key, expected_value) in expected_object {
+                let key_path = json_path_key(&path, key);
+
+                match actual_object.get(key) {
+                    Some(actual_value) => compare_json(
+                        expected_value,
+                        actual_value,
+                        key_path,
+                        allow_extra_fields,
+                        diffs,
+                    ),
+                    None => diffs.push(JsonBodyDiff::Missing {
+                        path: key_path,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+
+            if !allow_extra_fields {
+                for (key, actual_value) in actual_object {
+                    if !expected_object.contains_key(key) {
+                        diffs.push(JsonBodyDiff::Unexpected {
+                            path: json_path_key(&path, key),
+                            actual: actual_value.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        (Value::Array(expected_array), Value::Array(actual_array)) => {
+            for (index, expected_value) in expected_array.iter().enumerate() {
+                let index_path = format!("{path}[{index}]");
+
+                match actual_array.get(index) {
+                    Some(actual_value) => compare_json(
+                        expected_value,
+                        actual_value,
+                        index_path,
+                        allow_extra_fields,
+                        diffs,
+                    ),
+                    None => diffs.push(JsonBodyDiff::Missing {
+                        path: index_path,
+                        expected: expected_value.clone(),
+                    }),
+                }
+            }
+
+            for (index, actual_value) in actual_array.iter().enumerate().skip(expected_array.len())
+            {
+                diffs.push(JsonBodyDiff::Unexpected {
+                    path: format!("{path}[{index}]"),
+                    actual: actual_value.clone(),
+                });
+            }
+        }
+        // Strings in the expected body can contain matchers
+        (Value::String(expected_string), actual_value) => {
+            let is_match = match expected_value(expected_string) {
+                Some(expected_string_value) => match actual_value {
+                    Value::String(actual_string) => expected_string_value.is_match(actual_string),
+                    actual_value => expected_string_value.is_match(&actual_value.to_string()),
+                },
+                None => expected == actual,
+            };
+
+            if !is_match {
+                diffs.push(JsonBodyDiff::Mismatch {
+                    path,
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
+        }
+        _ => {
+            if expected != actual {
+                diffs.push(JsonBodyDiff::Mismatch {
+                    path,
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Append an object key to a JSONPath, using bracket notation when the key isn't an identifier
+fn json_path_key(path: &str, key: &str) -> String {
+    let is_identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_identifier {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}['{}']", key.replace('\'', "\\'"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::types::http::{HttpStatusCode, HttpVersion};
+    use crate::types::http::{HttpResponseMetrics, HttpStatusCode, HttpVersion};
 
     use super::*;
 
@@ -316,6 +838,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -324,6 +848,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(Ok(()), assert_response(&expected, &actual))
@@ -337,6 +863,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -345,6 +873,8 @@ mod tests {
             status_text: "CREATED".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(
@@ -377,6 +907,8 @@ mod tests {
                 ("X-Custom-Header".to_string(), "custom-value".to_string()),
             ],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -385,6 +917,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(
@@ -405,6 +939,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -416,6 +952,8 @@ mod tests {
                 ("X-Custom-Header".to_string(), "custom-value".to_string()),
             ],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(Ok(()), assert_response(&expected, &actual))
@@ -429,6 +967,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "application/json".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -437,6 +977,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
             body: Some(r#"{"key": "value"}"#.to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(
@@ -461,6 +1003,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
             body: Some("Hello World!".to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -469,6 +1013,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
             body: Some("Greetings World!".to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(
@@ -492,6 +1038,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
             body: Some("Hello World!".to_string()),
+            binary_body: None,
+            metrics: None,
         };
 
         let actual = HttpResponse {
@@ -500,6 +1048,8 @@ mod tests {
             status_text: "OK".to_string(),
             headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
             body: None,
+            binary_body: None,
+            metrics: None,
         };
 
         assert_eq!(
@@ -514,4 +1064,309 @@ mod tests {
             assert_response(&expected, &actual)
         )
     }
+
+    fn response(status_text: &str, headers: &[(&str, &str)], body: Option<&str>) -> HttpResponse {
+        HttpResponse {
+            http_version: HttpVersion::one_point_one(),
+            status_code: HttpStatusCode::new(200),
+            status_text: status_text.to_string(),
+            headers: headers
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            body: body.map(str::to_string),
+            binary_body: None,
+            metrics: None,
+        }
+    }
+
+    #[test]
+    fn test_header_names_are_case_insensitive() {
+        let expected = response("OK", &[("content-type", "application/json")], None);
+        let actual = response("OK", &[("Content-Type", "application/json")], None);
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_wildcard_matchers() {
+        let expected = response(
+            "{{*}}",
+            &[("date", "{{*}}"), ("content-type", "application/{{*}}")],
+            Some(r#"{"id": {{*}}, "name": "reqlang"}"#),
+        );
+        let actual = response(
+            "Created",
+            &[
+                ("date", "Tue, 15 Nov 1994 08:12:31 GMT"),
+                ("content-type", "application/json; charset=utf-8"),
+            ],
+            Some(r#"{"id": 42, "name": "reqlang"}"#),
+        );
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_regex_matchers() {
+        let expected = response(
+            "OK",
+            &[("x-request-id", "{{/[a-f0-9]{8}/}}")],
+            Some(r#"{"created_at": "{{/\d{4}-\d{2}-\d{2}/}}"}"#),
+        );
+        let actual = response(
+            "OK",
+            &[("X-Request-Id", "deadbeef")],
+            Some(r#"{"created_at": "2024-01-31"}"#),
+        );
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_body_contains() {
+        let expected = response("OK", &[], Some("{{*}}\"name\": \"reqlang\"{{*}}"));
+        let actual = response(
+            "OK",
+            &[],
+            Some("{\n  \"id\": 1,\n  \"name\": \"reqlang\",\n  \"version\": 2\n}"),
+        );
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_unmatched_header_value() {
+        let expected = response("OK", &[("x-request-id", "req-{{/[0-9]+/}}")], None);
+        let actual = response("OK", &[("x-request-id", "req-abc")], None);
+
+        assert_eq!(
+            Err(ResponseDiffs(
+                vec![ResponseDiff::UnmatchedHeaderValue {
+                    header: "x-request-id".to_string(),
+                    expected: "req-{{/[0-9]+/}}".to_string(),
+                    actual: "req-abc".to_string(),
+                    unmatched: "{{/[0-9]+/}}".to_string(),
+                }],
+                expected.clone()
+            )
+            .into()),
+            assert_response(&expected, &actual)
+        )
+    }
+
+    #[test]
+    fn test_unmatched_status_text() {
+        let expected = response("{{/[A-Z]+/}}", &[], None);
+        let actual = response("Not Found", &[], None);
+
+        assert_eq!(
+            Err(ResponseDiffs(
+                vec![ResponseDiff::UnmatchedStatusText {
+                    expected: "{{/[A-Z]+/}}".to_string(),
+                    actual: "Not Found".to_string(),
+                    unmatched: "{{/[A-Z]+/}}".to_string(),
+                }],
+                expected.clone()
+            )
+            .into()),
+            assert_response(&expected, &actual)
+        )
+    }
+
+    #[test]
+    fn test_unmatched_body() {
+        let expected = response("OK", &[], Some("{{*}}\"name\": \"reqlang\"{{*}}"));
+        let actual = response("OK", &[], Some(r#"{"name": "other"}"#));
+
+        assert_eq!(
+            Err(ResponseDiffs(
+                vec![ResponseDiff::UnmatchedBody {
+                    expected: "{{*}}\"name\": \"reqlang\"{{*}}".to_string(),
+                    actual: Some(r#"{"name": "other"}"#.to_string()),
+                    unmatched: "\"name\": \"reqlang\"".to_string(),
+                }],
+                expected.clone()
+            )
+            .into()),
+            assert_response(&expected, &actual)
+        )
+    }
+
+    #[test]
+    fn test_unmatched_trailing_text() {
+        let expected = ExpectedValue::parse("{{/[0-9]+/}} items").unwrap();
+
+        assert_eq!(
+            Some(" items".to_string()),
+            expected.first_unmatched("42 items left")
+        );
+    }
+
+    #[test]
+    fn test_invalid_regex_matcher() {
+        assert!(ExpectedValue::parse("{{/(/}}").is_err());
+    }
+
+    #[test]
+    fn test_json_body_ignores_key_order_and_whitespace() {
+        let expected = response("OK", &[], Some(r#"{"a": 1, "b": [true, null]}"#));
+        let actual = response(
+            "OK",
+            &[],
+            Some("{\n  \"b\": [ true, null ],\n  \"a\": 1\n}"),
+        );
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_json_body_differences() {
+        let expected = response(
+            "OK",
+            &[],
+            Some(r#"{"slideshow": {"author": "Yours Truly", "slides": [1, 2]}, "the key": 1}"#),
+        );
+        let actual = response(
+            "OK",
+            &[],
+            Some(r#"{"slideshow": {"author": "Someone", "slides": [1], "title": "Sample"}}"#),
+        );
+
+        assert_eq!(
+            Err(ResponseDiffs(
+                vec![ResponseDiff::JsonBody(vec![
+                    JsonBodyDiff::Mismatch {
+                        path: "$.slideshow.author".to_string(),
+                        expected: serde_json::json!("Yours Truly"),
+                        actual: serde_json::json!("Someone"),
+                    },
+                    JsonBodyDiff::Missing {
+                        path: "$.slideshow.slides[1]".to_string(),
+                        expected: serde_json::json!(2),
+                    },
+                    JsonBodyDiff::Unexpected {
+                        path: "$.slideshow.title".to_string(),
+                        actual: serde_json::json!("Sample"),
+                    },
+                    JsonBodyDiff::Missing {
+                        path: "$['the key']".to_string(),
+                        expected: serde_json::json!(1),
+                    },
+                ])],
+                expected.clone()
+            )
+            .into()),
+            assert_response(&expected, &actual)
+        )
+    }
+
+    #[test]
+    fn test_json_body_allow_extra_fields() {
+        let expected = response("OK", &[], Some(r#"{"user": {"id": 1}}"#));
+        let actual = response(
+            "OK",
+            &[],
+            Some(r#"{"user": {"id": 1, "name": "reqlang"}, "total": 1}"#),
+        );
+
+        let options = ParsedConfigAssert {
+            allow_extra_fields: Some(true),
+            max_latency: None,
+        };
+
+        assert_eq!(Ok(()), assert_response_with(&expected, &actual, &options))
+    }
+
+    fn response_with_total_ms(total_ms: f64) -> HttpResponse {
+        HttpResponse {
+            metrics: Some(HttpResponseMetrics {
+                total_ms,
+                ..Default::default()
+            }),
+            ..response("OK", &[], None)
+        }
+    }
+
+    fn max_latency(max_latency: &str) -> ParsedConfigAssert {
+        ParsedConfigAssert {
+            max_latency: Some(max_latency.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_max_latency() {
+        let expected = response("OK", &[], None);
+        let actual = response_with_total_ms(120.0);
+
+        assert_eq!(
+            Ok(()),
+            assert_response_with(&expected, &actual, &max_latency("500ms"))
+        );
+    }
+
+    #[test]
+    fn test_max_latency_exceeded() {
+        let expected = response("OK", &[], None);
+        let actual = response_with_total_ms(612.5);
+
+        let diffs = assert_response_with(&expected, &actual, &max_latency("500ms")).unwrap_err();
+
+        assert_eq!(
+            vec![ResponseDiff::Latency {
+                max: "500ms".parse().unwrap(),
+                actual_ms: 612.5
+            }],
+            diffs.diffs()
+        );
+        assert_eq!(
+            "Expected a response within 500ms got 612.5 ms",
+            diffs.diffs()[0].to_string()
+        );
+    }
+
+    #[test]
+    fn test_max_latency_without_metrics() {
+        let expected = response("OK", &[], None);
+        let actual = response("OK", &[], None);
+
+        assert_eq!(
+            Ok(()),
+            assert_response_with(&expected, &actual, &max_latency("1ms"))
+        );
+    }
+
+    #[test]
+    fn test_json_body_string_matchers() {
+        let expected = response(
+            "OK",
+            &[],
+            Some(r#"{"id": "{{/[0-9]+/}}", "created_at": "{{*}}"}"#),
+        );
+        let actual = response(
+            "OK",
+            &[],
+            Some(r#"{"created_at": "2024-01-31T00:00:00Z", "id": 42}"#),
+        );
+
+        assert_eq!(Ok(()), assert_response(&expected, &actual))
+    }
+
+    #[test]
+    fn test_json_body_diff_string() {
+        let expected = response(
+            "OK",
+            &[],
+            Some(r#"{"slideshow": {"author": "Yours Truly"}}"#),
+        );
+        let actual = response("OK", &[], Some(r#"{"slideshow": {"author": "Someone"}}"#));
+
+        let diffs = assert_response(&expected, &actual).unwrap_err();
+
+        assert_eq!(
+            "\n\n$.slideshow.author: expected \"Yours Truly\" got \"Someone\"\n",
+            console::strip_ansi_codes(&diffs.to_diff_string())
+        );
+    }
 }
@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{extract_codeblocks::extract_codeblocks, span::Spanned};
+use crate::{
+    errors::{ParseError, ReqlangError},
+    extract_codeblocks::{extract_codeblock_langs, extract_codeblocks, extract_named_codeblocks},
+    span::Spanned,
+};
+
+/// Languages of the code blocks in a request file
+pub const BLOCK_LANGS: [&str; 3] = ["%config", "%request", "%response"];
 
 /// Abstract syntax tree for a request file
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -16,20 +23,26 @@ impl Ast {
     pub fn from(input: impl AsRef<str>) -> Self {
         let mut nodes: Vec<Spanned<AstNode>> = vec![];
 
-        for (text, span) in extract_codeblocks(&input, "%request").iter() {
-            nodes.push((AstNode::RequestBlock(text.clone()), span.clone()));
+        for (name, (text, span)) in extract_named_codeblocks(&input, "%request") {
+            nodes.push((AstNode::RequestBlock(name, text), span));
         }
 
         for (text, span) in extract_codeblocks(&input, "%config").iter() {
             nodes.push((AstNode::ConfigBlock(text.clone()), span.clone()));
         }
 
-        for (text, span) in extract_codeblocks(&input, "%response").iter() {
-            nodes.push((AstNode::ResponseBlock(text.clone()), span.clone()));
+        for (name, (text, span)) in extract_named_codeblocks(&input, "%response") {
+            nodes.push((AstNode::ResponseBlock(name, text), span));
+        }
+
+        for (lang, span) in extract_codeblock_langs(&input) {
+            if !BLOCK_LANGS.contains(&lang.as_str()) && suggest_block_lang(&lang).is_some() {
+                nodes.push((AstNode::MisspelledBlock(lang), span));
+            }
         }
 
         // Sort AST nodes by their positions
-        nodes.sort_by(|a, b| a.1.start.cmp(&b.1.start));
+        nodes.sort_by_key(|a| a.1.start);
 
         let mut index = 0usize;
 
@@ -49,7 +62,7 @@ impl Ast {
         }
 
         // Sort AST nodes by their positions
-        nodes.sort_by(|a, b| a.1.start.cmp(&b.1.start));
+        nodes.sort_by_key(|a| a.1.start);
 
         Self::new(nodes)
     }
@@ -67,20 +80,147 @@ impl Ast {
         })
     }
 
-    /// Get the [AstNode::RequestBlock]
+    /// Get the first [AstNode::RequestBlock]
     pub fn request(&self) -> Option<&Spanned<String>> {
-        self.iter().find_map(|(node, _)| match &node {
-            AstNode::RequestBlock(request) => Some(request),
-            _ => None,
-        })
+        self.requests()
+            .into_iter()
+            .next()
+            .map(|(_, request)| request)
     }
 
-    /// Get the [AstNode::ResponseBlock], if present
+    /// Get the first [AstNode::ResponseBlock], if present
     pub fn response(&self) -> Option<&Spanned<String>> {
-        self.iter().find_map(|(node, _)| match &node {
-            AstNode::ResponseBlock(response) => Some(response),
+        self.responses()
+            .into_iter()
+            .next()
+            .map(|(_, response)| response)
+    }
+
+    /// Get all [AstNode::RequestBlock] with their names
+    pub fn requests(&self) -> Vec<(Option<&String>, &Spanned<String>)> {
+        self.iter()
+            .filter_map(|(node, _)| match &node {
+                AstNode::RequestBlock(name, request) => Some((name.as_ref(), request)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get all [AstNode::ResponseBlock] with their names
+    pub fn responses(&self) -> Vec<(Option<&String>, &Spanned<String>)> {
+        self.iter()
+            .filter_map(|(node, _)| match &node {
+                AstNode::ResponseBlock(name, response) => Some((name.as_ref(), response)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Get the [AstNode::RequestBlock] named `name` with its name
+    ///
+    /// Without a `name` the unnamed request block is used, or the first request block if they're all named.
+    pub fn select_request(
+        &self,
+        name: Option<&str>,
+    ) -> Option<(Option<&String>, &Spanned<String>)> {
+        let requests = self.requests();
+
+        match name {
+            Some(name) => requests
+                .into_iter()
+                .find(|(request_name, _)| request_name.is_some_and(|x| x == name)),
+            None => requests
+                .iter()
+                .find(|(request_name, _)| request_name.is_none())
+                .or(requests.first())
+                .copied(),
+        }
+    }
+
+    /// Get the [AstNode::ResponseBlock] with the same name as the request, if present
+    pub fn response_for(&self, request_name: Option<&str>) -> Option<&Spanned<String>> {
+        self.responses()
+            .into_iter()
+            .find(|(name, _)| name.map(String::as_str) == request_name)
+            .map(|(_, response)| response)
+    }
+
+    /// Validate the code blocks of the request file
+    ///
+    /// Reports duplicate blocks, responses without a request, and [AstNode::MisspelledBlock].
+    /// Duplicate blocks are reported on the whole code block, since an empty block's text has no width.
+    pub fn validate(&self) -> Vec<Spanned<ReqlangError>> {
+        let mut errs: Vec<Spanned<ReqlangError>> = vec![];
+
+        let configs = self.iter().filter_map(|(node, block_span)| match node {
+            AstNode::ConfigBlock(_) => Some(block_span),
             _ => None,
-        })
+        });
+
+        for span in configs.skip(1) {
+            errs.push((
+                ParseError::DuplicateBlock("%config".to_string()).into(),
+                span.clone(),
+            ));
+        }
+
+        let requests = self.iter().filter_map(|(node, block_span)| match node {
+            AstNode::RequestBlock(name, _) => Some((name.as_ref(), block_span)),
+            _ => None,
+        });
+        let mut request_names: Vec<Option<&String>> = vec![];
+
+        for (name, span) in requests {
+            if request_names.contains(&name) {
+                let err = match name {
+                    Some(name) => ParseError::DuplicateRequestName(name.to_string()),
+                    None => ParseError::DuplicateBlock("%request".to_string()),
+                };
+
+                errs.push((err.into(), span.clone()));
+            }
+
+            request_names.push(name);
+        }
+
+        let responses = self.iter().filter_map(|(node, block_span)| match node {
+            AstNode::ResponseBlock(name, (_, span)) => Some((name.as_ref(), span, block_span)),
+            _ => None,
+        });
+        let mut response_names: Vec<Option<&String>> = vec![];
+
+        for (name, span, block_span) in responses {
+            if response_names.contains(&name) {
+                let lang = match name {
+                    Some(name) => format!("%response {name}"),
+                    None => "%response".to_string(),
+                };
+
+                errs.push((ParseError::DuplicateBlock(lang).into(), block_span.clone()));
+            } else if !request_names.contains(&name) {
+                errs.push((
+                    ParseError::ResponseWithoutRequest(name.cloned()).into(),
+                    span.clone(),
+                ));
+            }
+
+            response_names.push(name);
+        }
+
+        for (node, span) in self.iter() {
+            if let AstNode::MisspelledBlock(lang) = node
+                && let Some(suggestion) = suggest_block_lang(lang)
+            {
+                errs.push((
+                    ParseError::MisspelledBlock(lang.clone(), suggestion.to_string()).into(),
+                    span.clone(),
+                ));
+            }
+        }
+
+        errs.sort_by_key(|(_, span)| span.start);
+
+        errs
     }
 
     /// Get all [AstNode::Comment]
@@ -97,16 +237,56 @@ impl Ast {
     }
 }
 
+/// The [block language](BLOCK_LANGS) a code block language is likely a typo of
+///
+/// Only languages starting with `%` are suggested for, so plain code blocks like ```` ```request ```` stay comments.
+fn suggest_block_lang(lang: &str) -> Option<&'static str> {
+    if !lang.starts_with('%') {
+        return None;
+    }
+
+    let lang = lang.to_lowercase();
+
+    BLOCK_LANGS
+        .iter()
+        .map(|block_lang| (edit_distance(&lang, block_lang), *block_lang))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, block_lang)| block_lang)
+}
+
+/// The number of single character insertions, deletions, or substitutions to change `a` in to `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum AstNode {
-    /// Any text that isn't a [AstNode::RequestBlock], [AstNode::ResponseBlock], or [AstNode::ConfigBlock].
+    /// Any text that isn't a [AstNode::RequestBlock], [AstNode::ResponseBlock], [AstNode::ConfigBlock], or [AstNode::MisspelledBlock].
     Comment(String),
     /// A code block delimited configuration
     ConfigBlock(Spanned<String>),
-    /// A code block delimited request
-    RequestBlock(Spanned<String>),
-    /// A code block delimited response
-    ResponseBlock(Spanned<String>),
+    /// A code block delimited request, optionally named: ```` ```%request name ````
+    RequestBlock(Option<String>, Spanned<String>),
+    /// A code block delimited response, optionally named for the request it's paired with
+    ResponseBlock(Option<String>, Spanned<String>),
+    /// A code block with a language that's likely a typo of a block language: ```` ```%reqeust ````
+    MisspelledBlock(String),
 }
 
 #[cfg(test)]
@@ -144,7 +324,7 @@ REQUEST
             Ast(vec![
                 (AstNode::Comment("\n".to_string()), 0..1),
                 (
-                    AstNode::RequestBlock(("REQUEST".to_string(), 13..20)),
+                    AstNode::RequestBlock(None, ("REQUEST".to_string(), 13..20)),
                     1..24
                 )
             ]),
@@ -170,12 +350,12 @@ RESPONSE
             Ast(vec![
                 (AstNode::Comment("\n".to_string()), 0..1),
                 (
-                    AstNode::RequestBlock(("REQUEST".to_string(), 13..20)),
+                    AstNode::RequestBlock(None, ("REQUEST".to_string(), 13..20)),
                     1..24
                 ),
                 (AstNode::Comment("\n".to_string()), 24..25),
                 (
-                    AstNode::ResponseBlock(("RESPONSE".to_string(), 38..46)),
+                    AstNode::ResponseBlock(None, ("RESPONSE".to_string(), 38..46)),
                     25..50
                 ),
             ]),
@@ -206,12 +386,12 @@ RESPONSE
                 (AstNode::ConfigBlock(("CONFIG".to_string(), 12..18)), 1..22),
                 (AstNode::Comment("\n".to_string()), 22..23),
                 (
-                    AstNode::RequestBlock(("REQUEST".to_string(), 35..42)),
+                    AstNode::RequestBlock(None, ("REQUEST".to_string(), 35..42)),
                     23..46
                 ),
                 (AstNode::Comment("\n".to_string()), 46..47),
                 (
-                    AstNode::ResponseBlock(("RESPONSE".to_string(), 60..68)),
+                    AstNode::ResponseBlock(None, ("RESPONSE".to_string(), 60..68)),
                     47..72
                 ),
             ]),
@@ -272,27 +452,30 @@ D
                 ),
                 (AstNode::Comment("\n\nB\n\n".to_string()), 60..65),
                 (
-                    AstNode::RequestBlock((
-                        "GET https://example.com HTTP/1.1".to_string(),
-                        77..109
-                    )),
+                    AstNode::RequestBlock(
+                        None,
+                        ("GET https://example.com HTTP/1.1".to_string(), 77..109)
+                    ),
                     65..113
                 ),
                 (AstNode::Comment("\n\nC\n\n".to_string()), 113..118),
                 (
-                    AstNode::ResponseBlock((
-                        textwrap::dedent(
-                            r#"
+                    AstNode::ResponseBlock(
+                        None,
+                        (
+                            textwrap::dedent(
+                                r#"
                             HTTP/1.1 200 OK
                             content-type: application/html
 
                             <html></html>
                             "#
+                            )
+                            .trim()
+                            .to_string(),
+                            131..192
                         )
-                        .trim()
-                        .to_string(),
-                        131..192
-                    )),
+                    ),
                     118..196
                 ),
             ]),
@@ -323,11 +506,111 @@ GET https://example.com HTTP/1.1
                 ),
                 (AstNode::Comment("\n\n".to_string()), 40..42),
                 (
-                    AstNode::RequestBlock(("GET https://example.com HTTP/1.1".to_string(), 54..86)),
+                    AstNode::RequestBlock(
+                        None,
+                        ("GET https://example.com HTTP/1.1".to_string(), 54..86)
+                    ),
                     42..90
                 )
             ]),
             ast_result
         );
     }
+
+    #[test]
+    fn edit_distance_between_langs() {
+        assert_eq!(0, edit_distance("%request", "%request"));
+        assert_eq!(2, edit_distance("%reqeust", "%request"));
+        assert_eq!(1, edit_distance("request", "%request"));
+        assert_eq!(1, edit_distance("%configs", "%config"));
+    }
+
+    #[test]
+    fn validate_duplicate_blocks() {
+        let input = textwrap::dedent(
+            "
+            ```%config
+            ```
+
+            ```%config
+            ```
+
+            ```%request
+            GET / HTTP/1.1
+            ```
+
+            ```%request
+            GET / HTTP/1.1
+            ```
+
+            ```%response
+            HTTP/1.1 200 OK
+            ```
+
+            ```%response
+            HTTP/1.1 200 OK
+            ```
+            ",
+        );
+
+        assert_eq!(
+            vec![
+                (
+                    ParseError::DuplicateBlock("%config".to_string()).into(),
+                    17..31
+                ),
+                (
+                    ParseError::DuplicateBlock("%request".to_string()).into(),
+                    65..95
+                ),
+                (
+                    ParseError::DuplicateBlock("%response".to_string()).into(),
+                    131..163
+                ),
+            ],
+            Ast::from(input).validate()
+        );
+    }
+
+    #[test]
+    fn validate_misspelled_blocks() {
+        let input = textwrap::dedent(
+            "
+            ```%reqeust
+            GET / HTTP/1.1
+            ```
+
+            ```%Response
+            HTTP/1.1 200 OK
+            ```
+
+            ```json
+            {}
+            ```
+
+            ```request
+            GET / HTTP/1.1
+            ```
+
+            ```config
+            ```
+            ",
+        );
+
+        assert_eq!(
+            vec![
+                (
+                    ParseError::MisspelledBlock("%reqeust".to_string(), "%request".to_string())
+                        .into(),
+                    1..31
+                ),
+                (
+                    ParseError::MisspelledBlock("%Response".to_string(), "%response".to_string())
+                        .into(),
+                    33..65
+                ),
+            ],
+            Ast::from(input).validate()
+        );
+    }
 }
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    ast::Ast,
+    errors::{FetchError, ReqlangError},
+    fetch::{Fetch, HttpRequestFetcher},
+    parser::{ParseContext, parse_with_context},
+    span::Spanned,
+    templater::{resolve_client, template_config},
+    types::{
+        auth::{OAUTH2_ACCESS_TOKEN, OAuth2Grant, ParsedOAuth2Config},
+        client::ParsedConfigClient,
+        http::{HttpRequest, HttpVerb, HttpVersion},
+    },
+};
+
+type AuthValuesResult = Result<HashMap<String, String>, Vec<Spanned<ReqlangError>>>;
+
+/// Execute the authentication declared in a request file's config and return the provider values it sets.
+///
+/// The returned map uses names like `auth.oauth2.access_token` as keys and
+/// should be passed to [crate::templater::template] as provider values.
+///
+/// The token endpoint is requested with the client options of the request file and its
+/// workspace. Options in `client` (e.g. passed to the CLI) take precedence over them.
+///
+/// ```ignore
+/// let auth_values = resolve_auth(&source, env, &prompts, &secrets, &provider_values, &client, &context).await?;
+/// provider_values.extend(auth_values);
+/// let reqfile = template(&source, env, &prompts, &secrets, &provider_values)?;
+/// ```
+pub async fn resolve_auth(
+    source: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    provider_values: &HashMap<String, String>,
+    client: &ParsedConfigClient,
+    context: &ParseContext,
+) -> AuthValuesResult {
+    let Some((_, config_span)) = parse_with_context(&Ast::from(source), context)?.config else {
+        return Ok(HashMap::new());
+    };
+
+    let Some(config) = template_config(source, env, prompts, secrets, provider_values, context)?
+    else {
+        return Ok(HashMap::new());
+    };
+
+    let mut values = HashMap::new();
+
+    if let Some(oauth2) = config.auth.and_then(|auth| auth.oauth2) {
+        // Auth errors are reported on the config declaring the auth
+        let file_client = resolve_client(config.client, context).map_err(|errs| {
+            errs.into_iter()
+                .map(|err| (err, config_span.clone()))
+                .collect::<Vec<_>>()
+        })?;
+
+        let client = client.merge(&file_client.unwrap_or_default());
+
+        let access_token = fetch_oauth2_access_token(&oauth2, client)
+            .await
+            .map_err(|err| vec![(err.into(), config_span.clone())])?;
+
+        values.insert(OAUTH2_ACCESS_TOKEN.to_string(), access_token);
+    }
+
+    Ok(values)
+}
+
+/// Request an access token from the token endpoint using the configured grant
+async fn fetch_oauth2_access_token(
+    oauth2: &ParsedOAuth2Config,
+    client: ParsedConfigClient,
+) -> Result<String, FetchError> {
+    let grant = oauth2.grant();
+
+    let mut params: Vec<(&str, &str)> = vec![("grant_type", grant.grant_type())];
+
+    let optional_params = match grant {
+        OAuth2Grant::ClientCredentials => vec![],
+        OAuth2Grant::Password => vec![
+            ("username", &oauth2.username),
+            ("password", &oauth2.password),
+        ],
+        OAuth2Grant::RefreshToken => vec![("refresh_token", &oauth2.refresh_token)],
+    };
+
+    for (name, value) in optional_params.into_iter().chain([
+        ("client_id", &oauth2.client_id),
+        ("client_secret", &oauth2.client_secret),
+        ("scope", &oauth2.scopes),
+    ]) {
+        if let Some(value) = value {
+            params.push((name, value));
+        }
+    }
+
+    let body = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish();
+
+    let request = HttpRequest::new(
+        HttpVerb::post(),
+        oauth2.access_token_url.clone().unwrap_or_default(),
+        HttpVersion::one_point_one(),
+        vec![
+            (
+                "content-type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            ),
+            ("accept".to_string(), "application/json".to_string()),
+        ],
+        Some(body),
+    );
+
+    let fetcher = HttpRequestFetcher(request, None, Some(client));
+    let response = fetcher
+        .fetch()
+        .await
+        .map_err(|err| FetchError::OAuth2TokenError(err.to_string()))?;
+
+    if !response.status_code.is_success() {
+        return Err(FetchError::OAuth2TokenError(format!(
+            "Token endpoint responded with {} {}",
+            response.status_code, response.status_text
+        )));
+    }
+
+    response
+        .body
+        .as_deref()
+        .and_then(|body| serde_json::from_str::<Value>(body).ok())
+        .and_then(|json| json.get("access_token")?.as_str().map(str::to_string))
+        .ok_or_else(|| {
+            FetchError::OAuth2TokenError(
+                "Token endpoint response did not include an access_token".to_string(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use httptest::{
+        Expectation, Server,
+        matchers::{all_of, contains, request, url_decoded},
+        responders::{json_encoded, status_code},
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        errors::{FetchError, ReqlangError},
+        parser::parse,
+    };
+
+    use crate::{
+        parser::ParseContext,
+        types::{ParsedConfig, client::ParsedConfigClient},
+    };
+
+    use super::resolve_auth;
+
+    fn reqfile(token_url: &str, oauth2: &str) -> String {
+        format!(
+            "```%config\nsecrets = [\"client_secret\"]\n\n[auth.oauth2]\naccess_token_url = \"{token_url}\"\nclient_id = \"reqlang\"\nclient_secret = \"{{{{!client_secret}}}}\"\n{oauth2}\n```\n\n```%request\nGET https://example.com HTTP/1.1\nauthorization: Bearer {{{{@auth.oauth2.access_token}}}}\n```\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn client_credentials_grant() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains(("grant_type", "client_credentials")))),
+                request::body(url_decoded(contains(("client_id", "reqlang")))),
+                request::body(url_decoded(contains(("client_secret", "shh")))),
+                request::body(url_decoded(contains(("scope", "read write")))),
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "access_token": "abc123",
+                "token_type": "Bearer"
+            }))),
+        );
+
+        let source = reqfile(&server.url_str("/token"), "scopes = \"read write\"");
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "auth.oauth2.access_token".to_string(),
+                "abc123".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn password_grant() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains(("grant_type", "password")))),
+                request::body(url_decoded(contains(("username", "user")))),
+                request::body(url_decoded(contains(("password", "pass")))),
+            ])
+            .respond_with(json_encoded(
+                serde_json::json!({ "access_token": "def456" }),
+            )),
+        );
+
+        let source = reqfile(
+            &server.url_str("/token"),
+            "grant = \"password\"\nusername = \"user\"\npassword = \"pass\"",
+        );
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "auth.oauth2.access_token".to_string(),
+                "def456".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_token_grant() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::body(url_decoded(contains(("grant_type", "refresh_token")))),
+                request::body(url_decoded(contains(("refresh_token", "refresh")))),
+            ])
+            .respond_with(json_encoded(
+                serde_json::json!({ "access_token": "ghi789" }),
+            )),
+        );
+
+        let source = reqfile(
+            &server.url_str("/token"),
+            "grant = \"refresh_token\"\nrefresh_token = \"refresh\"",
+        );
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "auth.oauth2.access_token".to_string(),
+                "ghi789".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn token_request_uses_client_config() {
+        let proxy = Server::run();
+
+        proxy.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/token"),
+                request::headers(contains(("host", "auth.invalid"))),
+            ])
+            .respond_with(json_encoded(
+                serde_json::json!({ "access_token": "proxied" }),
+            )),
+        );
+
+        let source = reqfile(
+            "http://auth.invalid/token",
+            &format!("\n[client]\nproxy = \"{}\"", proxy.url_str("")),
+        );
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "auth.oauth2.access_token".to_string(),
+                "proxied".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn token_request_uses_workspace_client_config() {
+        let proxy = Server::run();
+
+        proxy.expect(
+            Expectation::matching(request::method_path("POST", "/token")).respond_with(
+                json_encoded(serde_json::json!({ "access_token": "proxied" })),
+            ),
+        );
+
+        let source = reqfile("http://auth.invalid/token", "");
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext {
+                shared_config: Some(ParsedConfig {
+                    client: Some(ParsedConfigClient {
+                        proxy: Some(proxy.url_str("")),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "auth.oauth2.access_token".to_string(),
+                "proxied".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn token_endpoint_error() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/token"))
+                .respond_with(status_code(401)),
+        );
+
+        let source = reqfile(&server.url_str("/token"), "");
+        let config_span = parse(&Ast::from(&source)).unwrap().config.unwrap().1;
+
+        let values = resolve_auth(
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::FetchError(FetchError::OAuth2TokenError(
+                    "Token endpoint responded with 401 Unauthorized".to_string()
+                )),
+                config_span
+            )]),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn no_auth() {
+        let values = resolve_auth(
+            "```%request\nGET https://example.com HTTP/1.1\n```\n",
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &ParsedConfigClient::default(),
+            &ParseContext::default(),
+        )
+        .await;
+
+        assert_eq!(Ok(HashMap::new()), values);
+    }
+}
@@ -0,0 +1,478 @@
+use regex::Regex;
+
+use crate::{
+    ast::Ast,
+    parser::{FORBIDDEN_REQUEST_HEADER_NAMES, ParseContext, parse_config},
+    span::Span,
+    types::{ParsedConfig, ReferenceType},
+};
+
+/// Request header names offered in the headers of a `%request` block
+///
+/// Names in [FORBIDDEN_REQUEST_HEADER_NAMES] aren't offered.
+const REQUEST_HEADER_NAMES: &[&str] = &[
+    "accept",
+    "accept-encoding",
+    "accept-language",
+    "authorization",
+    "cache-control",
+    "connection",
+    "content-encoding",
+    "content-language",
+    "content-length",
+    "content-type",
+    "cookie",
+    "forwarded",
+    "from",
+    "if-match",
+    "if-modified-since",
+    "if-none-match",
+    "if-range",
+    "if-unmodified-since",
+    "pragma",
+    "prefer",
+    "range",
+    "referer",
+    "user-agent",
+    "x-api-key",
+    "x-correlation-id",
+    "x-forwarded-for",
+    "x-request-id",
+    "x-requested-with",
+];
+
+/// Top level keys of the `%config` with a description
+const CONFIG_KEYS: &[(&str, &str)] = &[
+    ("vars", "Variables, referenced with {{:name}}"),
+    ("envs", "Environments with variable values"),
+    ("prompts", "Prompts, referenced with {{?name}}"),
+    ("secrets", "Secrets, referenced with {{!name}}"),
+    ("auth", "Authentication for the request"),
+    ("depends", "Request files to execute before this one"),
+    ("captures", "Values to capture from the response"),
+    ("assert", "Options for the response assertion"),
+    ("client", "Options for the HTTP client"),
+    ("imports", "Config files to import declarations from"),
+    ("lints", "Levels for lints, e.g. unused_value = \"allow\""),
+];
+
+/// Kind of value a [Completion] inserts
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionKind {
+    Variable,
+    Prompt,
+    Secret,
+    Provider,
+    Dependency,
+    Header,
+    ConfigKey,
+    Environment,
+}
+
+/// A value that can be inserted at a position in a request file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: Option<String>,
+}
+
+impl Completion {
+    fn new(label: impl Into<String>, kind: CompletionKind, detail: Option<String>) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            detail,
+        }
+    }
+}
+
+/// Get the completions at `index` in `source`
+///
+/// Only the config is parsed, so completions are available while the rest of
+/// the request file is invalid. Declarations shared by a workspace are included.
+pub fn completions(source: &str, index: usize, context: &ParseContext) -> Vec<Completion> {
+    let Some(line_prefix) = source
+        .get(..index)
+        .map(|x| x.rsplit('\n').next().unwrap_or(x))
+    else {
+        return vec![];
+    };
+
+    let ast = Ast::from(source);
+    let config = declared_config(&ast, context);
+
+    if let Some(completions) = reference_completions(&config, line_prefix) {
+        return completions;
+    }
+
+    if let Some((text, span)) = ast.config()
+        && in_block(span, index)
+    {
+        return config_completions(&config, text, &text[..index - span.start], line_prefix);
+    }
+
+    let in_request_headers = ast.requests().iter().any(|(_, (text, span))| {
+        in_block(span, index) && is_header_position(&text[..index - span.start])
+    });
+
+    if in_request_headers {
+        return header_completions();
+    }
+
+    vec![]
+}
+
+/// The config with the declarations shared by the workspace
+fn declared_config(ast: &Ast, context: &ParseContext) -> ParsedConfig {
+    let mut config = parse_config(&ast.config().cloned(), context.path.as_deref())
+        .and_then(Result::ok)
+        .map(|(config, _)| config)
+        .unwrap_or_default();
+
+    if let Some(shared) = &context.shared_config {
+        // Offer every shared declaration, not just those already referenced
+        let shared_refs: Vec<ReferenceType> = shared
+            .vars()
+            .into_iter()
+            .map(ReferenceType::Variable)
+            .chain(shared.prompts().into_iter().map(ReferenceType::Prompt))
+            .chain(shared.secrets().into_iter().map(ReferenceType::Secret))
+            .collect();
+
+        config.merge_shared(shared, &shared_refs);
+    }
+
+    config
+}
+
+/// If `index` is in a block's content, including the end of its last line
+fn in_block(span: &Span, index: usize) -> bool {
+    span.start <= index && index <= span.end
+}
+
+/// Completions after an unclosed `{{:`, `{{?`, `{{!`, `{{@`, or `{{>`
+fn reference_completions(config: &ParsedConfig, line_prefix: &str) -> Option<Vec<Completion>> {
+    let re = Regex::new(r"\{\{([:?!@>])[_a-zA-Z0-9.]*$").unwrap();
+
+    let captures = re.captures(line_prefix)?;
+
+    let completions = match &captures[1] {
+        ":" => config
+            .vars
+            .iter()
+            .flatten()
+            .map(|var| {
+                let detail = var.default.as_ref().map(|x| format!("Default: {x}"));
+
+                Completion::new(&var.name, CompletionKind::Variable, detail)
+            })
+            .collect(),
+        "?" => config
+            .prompts
+            .iter()
+            .flatten()
+            .map(|prompt| {
+                Completion::new(
+                    &prompt.name,
+                    CompletionKind::Prompt,
+                    prompt.description.clone(),
+                )
+            })
+            .collect(),
+        "!" => config
+            .secrets()
+            .into_iter()
+            .map(|secret| Completion::new(secret, CompletionKind::Secret, None))
+            .collect(),
+        "@" => {
+            let auth_values = config
+                .auth
+                .as_ref()
+                .map(|auth| auth.provider_values())
+                .unwrap_or_default();
+
+            std::iter::once("env".to_string())
+                .chain(auth_values)
+                .map(|name| Completion::new(name, CompletionKind::Provider, None))
+                .collect()
+        }
+        _ => config
+            .dependency_values()
+            .into_iter()
+            .map(|name| Completion::new(name, CompletionKind::Dependency, None))
+            .collect(),
+    };
+
+    Some(completions)
+}
+
+/// Completions for top level keys and environment names in the config
+fn config_completions(
+    config: &ParsedConfig,
+    config_text: &str,
+    config_prefix: &str,
+    line_prefix: &str,
+) -> Vec<Completion> {
+    if Regex::new(r"^\s*\[envs\.[_a-zA-Z0-9-]*$")
+        .unwrap()
+        .is_match(line_prefix)
+    {
+        // The config doesn't parse while an environment table header is being written
+        let declared_envs = Regex::new(r"(?m)^\s*\[envs\.([_a-zA-Z0-9-]+)\]")
+            .unwrap()
+            .captures_iter(config_text)
+            .map(|captures| captures[1].to_string())
+            .collect::<Vec<String>>();
+
+        let mut envs = config.envs();
+        envs.extend(declared_envs);
+        envs.sort();
+        envs.dedup();
+
+        return envs
+            .into_iter()
+            .map(|env| Completion::new(env, CompletionKind::Environment, None))
+            .collect();
+    }
+
+    let is_table_header = Regex::new(r"^\s*\[{1,2}[_a-z]*$")
+        .unwrap()
+        .is_match(line_prefix);
+
+    // Keys outside of tables are top level keys
+    let is_top_level_key = Regex::new(r"^\s*[_a-z]*$").unwrap().is_match(line_prefix)
+        && !config_prefix
+            .lines()
+            .any(|line| line.trim_start().starts_with('['));
+
+    if !is_table_header && !is_top_level_key {
+        return vec![];
+    }
+
+    CONFIG_KEYS
+        .iter()
+        .map(|(key, detail)| {
+            Completion::new(*key, CompletionKind::ConfigKey, Some(detail.to_string()))
+        })
+        .collect()
+}
+
+/// If the end of `request_prefix` is the name of a header in a request
+fn is_header_position(request_prefix: &str) -> bool {
+    let lines: Vec<&str> = request_prefix.split('\n').collect();
+
+    let Some((line, previous_lines)) = lines.split_last() else {
+        return false;
+    };
+
+    // Headers are after the request line and before the blank line starting the body
+    !previous_lines.is_empty()
+        && previous_lines[1..]
+            .iter()
+            .all(|line| !line.trim().is_empty())
+        && !line.contains(':')
+}
+
+fn header_completions() -> Vec<Completion> {
+    REQUEST_HEADER_NAMES
+        .iter()
+        .filter(|name| !FORBIDDEN_REQUEST_HEADER_NAMES.contains(name))
+        .map(|name| Completion::new(*name, CompletionKind::Header, None))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        parser::{FORBIDDEN_REQUEST_HEADER_NAMES, ParseContext},
+        types::{ParsedConfig, ParsedConfigVariable},
+    };
+
+    use super::{CompletionKind, completions};
+
+    const CONFIG: &str = r#"```%config
+secrets = ["api_key"]
+
+[[vars]]
+name = "base_url"
+default = "http://localhost:3000"
+
+[[prompts]]
+name = "user_id"
+description = "Id of the user to get"
+
+[envs.dev]
+base_url = "https://dev.example.com"
+
+[envs.prod]
+base_url = "https://example.com"
+```
+"#;
+
+    /// Completions at the `|` in the source
+    fn labels_at(source: &str) -> Vec<String> {
+        labels_at_with_context(source, &ParseContext::default())
+    }
+
+    fn labels_at_with_context(source: &str, context: &ParseContext) -> Vec<String> {
+        let index = source.find('|').unwrap();
+        let source = source.replacen('|', "", 1);
+
+        completions(&source, index, context)
+            .into_iter()
+            .map(|completion| completion.label)
+            .collect()
+    }
+
+    fn request(request: &str) -> String {
+        format!("{CONFIG}\n```%request\n{request}\n```\n")
+    }
+
+    #[test]
+    fn complete_variables() {
+        let source = request("GET {{:|");
+
+        assert_eq!(vec!["base_url"], labels_at(&source));
+
+        let index = source.find('|').unwrap();
+        let completion = &completions(&source.replacen('|', "", 1), index, &Default::default())[0];
+
+        assert_eq!(CompletionKind::Variable, completion.kind);
+        assert_eq!(
+            Some("Default: http://localhost:3000".to_string()),
+            completion.detail
+        );
+    }
+
+    #[test]
+    fn complete_partial_variable() {
+        assert_eq!(vec!["base_url"], labels_at(&request("GET {{:ba| HTTP/1.1")));
+    }
+
+    #[test]
+    fn complete_prompts() {
+        assert_eq!(
+            vec!["user_id"],
+            labels_at(&request("GET https://example.com/{{?|"))
+        );
+    }
+
+    #[test]
+    fn complete_secrets() {
+        assert_eq!(
+            vec!["api_key"],
+            labels_at(&request(
+                "GET https://example.com HTTP/1.1\nx-api-key: {{!|"
+            ))
+        );
+    }
+
+    #[test]
+    fn complete_provider_values() {
+        assert_eq!(
+            vec!["env"],
+            labels_at(&request("GET https://example.com HTTP/1.1\nx-env: {{@|"))
+        );
+    }
+
+    #[test]
+    fn complete_shared_variables() {
+        let context = ParseContext {
+            shared_config: Some(ParsedConfig {
+                vars: Some(vec![ParsedConfigVariable {
+                    name: "token".to_string(),
+                    default: None,
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec!["base_url", "token"],
+            labels_at_with_context(&request("GET {{:|"), &context)
+        );
+    }
+
+    #[test]
+    fn complete_without_closed_references() {
+        assert_eq!(
+            Vec::<String>::new(),
+            labels_at(&request("GET {{:base_url}}|"))
+        );
+    }
+
+    #[test]
+    fn complete_header_names() {
+        let labels = labels_at(&request(
+            "GET https://example.com HTTP/1.1\naccept: */*\ncont|",
+        ));
+
+        assert!(labels.contains(&"content-type".to_string()));
+        assert!(labels.contains(&"authorization".to_string()));
+        assert!(
+            labels
+                .iter()
+                .all(|label| !FORBIDDEN_REQUEST_HEADER_NAMES.contains(&label.as_str()))
+        );
+    }
+
+    #[test]
+    fn complete_no_header_names_in_request_line_or_body() {
+        assert_eq!(Vec::<String>::new(), labels_at(&request("GET |")));
+        assert_eq!(
+            Vec::<String>::new(),
+            labels_at(&request(
+                "POST https://example.com HTTP/1.1\ncontent-type: text/plain\n\nbo|"
+            ))
+        );
+        assert_eq!(
+            Vec::<String>::new(),
+            labels_at(&request("GET https://example.com HTTP/1.1\naccept: |"))
+        );
+    }
+
+    #[test]
+    fn complete_config_keys() {
+        let source = "```%config\n|\n```\n\n```%request\nGET https://example.com HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![
+                "vars", "envs", "prompts", "secrets", "auth", "depends", "captures", "assert",
+                "client", "imports", "lints"
+            ],
+            labels_at(source)
+        );
+    }
+
+    #[test]
+    fn complete_config_table_headers() {
+        let source = CONFIG.replacen("[envs.prod]", "[[pro|", 1);
+
+        assert!(labels_at(&source).contains(&"prompts".to_string()));
+    }
+
+    #[test]
+    fn complete_no_config_keys_in_tables() {
+        let source = CONFIG.replacen("default = \"http", "|default = \"http", 1);
+
+        assert_eq!(Vec::<String>::new(), labels_at(&source));
+    }
+
+    #[test]
+    fn complete_environment_names() {
+        let source = CONFIG.replacen("[envs.prod]", "[envs.|", 1);
+
+        assert_eq!(vec!["dev"], labels_at(&source));
+
+        let source = format!(
+            "{}\n[envs.|\n```\n",
+            CONFIG.trim_end().trim_end_matches("```")
+        );
+
+        assert_eq!(vec!["dev", "prod"], labels_at(&source));
+    }
+}
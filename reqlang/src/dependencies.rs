@@ -0,0 +1,490 @@
+use std::{
+    collections::HashMap,
+    fs,
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
+};
+
+use crate::{
+    ast::Ast,
+    errors::{FetchError, ParseError, ReqlangError, ResolverError},
+    fetch::{Fetch, HttpRequestFetcher},
+    parser::{ParseContext, parse_with_context},
+    span::Spanned,
+    templater::template_with_context,
+    types::{ParsedConfig, ParsedRequestFile},
+};
+
+type DependencyValuesResult = Result<HashMap<String, String>, Vec<Spanned<ReqlangError>>>;
+
+/// Execute the dependencies declared in a request file's config and extract their values.
+///
+/// Dependencies are resolved relative to `path` and executed depth first. The
+/// returned map uses `dependency.value` names as keys and should be passed to
+/// [template] as provider values to resolve `{{>dependency.value}}` references.
+///
+/// Declarations shared by a workspace are used for the request file and its dependencies.
+///
+/// ```ignore
+/// let dependency_values = resolve_dependencies(path, &source, env, &prompts, &secrets, &provider_values, None).await?;
+/// provider_values.extend(dependency_values);
+/// let reqfile = template(&source, env, &prompts, &secrets, &provider_values)?;
+/// ```
+pub async fn resolve_dependencies(
+    path: impl AsRef<Path>,
+    source: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    provider_values: &HashMap<String, String>,
+    shared_config: Option<&ParsedConfig>,
+) -> DependencyValuesResult {
+    let path = path.as_ref();
+    let visited = vec![fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())];
+
+    let inputs = ResolveInputs {
+        prompts,
+        secrets,
+        provider_values,
+        shared_config,
+    };
+
+    resolve(
+        path.to_path_buf(),
+        source.to_string(),
+        env.map(str::to_string),
+        &inputs,
+        visited,
+    )
+    .await
+}
+
+/// Inputs used to resolve the request file and each of its dependencies
+struct ResolveInputs<'a> {
+    prompts: &'a HashMap<String, String>,
+    secrets: &'a HashMap<String, String>,
+    provider_values: &'a HashMap<String, String>,
+    shared_config: Option<&'a ParsedConfig>,
+}
+
+fn resolve<'a>(
+    path: PathBuf,
+    source: String,
+    env: Option<String>,
+    inputs: &'a ResolveInputs<'a>,
+    visited: Vec<PathBuf>,
+) -> Pin<Box<dyn Future<Output = DependencyValuesResult> + Send + 'a>> {
+    let ResolveInputs {
+        prompts,
+        secrets,
+        provider_values,
+        shared_config,
+    } = *inputs;
+
+    Box::pin(async move {
+        let context = ParseContext {
+            path: Some(path.clone()),
+            shared_config: shared_config.cloned(),
+            request: None,
+        };
+
+        let reqfile = parse_with_context(&Ast::from(&source), &context)?;
+
+        let Some((config, config_span)) = reqfile.config else {
+            return Ok(HashMap::new());
+        };
+
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let mut values = HashMap::new();
+
+        for dependency in config.depends.unwrap_or_default() {
+            let dependency_path = base_dir.join(&dependency.path);
+
+            let Ok(canonical_dependency_path) = fs::canonicalize(&dependency_path) else {
+                return Err(vec![(
+                    ParseError::MissingDependency(dependency.path.clone()).into(),
+                    config_span,
+                )]);
+            };
+
+            if visited.contains(&canonical_dependency_path) {
+                return Err(vec![(
+                    ParseError::CircularDependency(dependency.path.clone()).into(),
+                    config_span,
+                )]);
+            }
+
+            let Ok(dependency_source) = fs::read_to_string(&dependency_path) else {
+                return Err(vec![(
+                    ParseError::MissingDependency(dependency.path.clone()).into(),
+                    config_span,
+                )]);
+            };
+
+            // Errors in the dependency refer to spans in a different file
+            // so they are reported on the config declaring the dependency
+            let respan = |errs: Vec<Spanned<ReqlangError>>| -> Vec<Spanned<ReqlangError>> {
+                errs.into_iter()
+                    .map(|(err, _)| (err, config_span.clone()))
+                    .collect()
+            };
+
+            let dependency_context = ParseContext {
+                path: Some(dependency_path.clone()),
+                shared_config: shared_config.cloned(),
+                request: None,
+            };
+
+            // Only pass the environment along if the dependency defines it
+            let dependency_env = env.clone().filter(|env| {
+                parse_with_context(&Ast::from(&dependency_source), &dependency_context)
+                    .map(|reqfile| reqfile.envs().contains(env))
+                    .unwrap_or_default()
+            });
+
+            let mut dependency_visited = visited.clone();
+            dependency_visited.push(canonical_dependency_path);
+
+            let mut dependency_provider_values = provider_values.clone();
+            dependency_provider_values.extend(
+                resolve(
+                    dependency_path.clone(),
+                    dependency_source.clone(),
+                    dependency_env.clone(),
+                    inputs,
+                    dependency_visited,
+                )
+                .await
+                .map_err(respan)?,
+            );
+
+            if let Some(env) = &dependency_env {
+                dependency_provider_values.insert("env".to_string(), env.clone());
+            }
+
+            let dependency_reqfile = template_with_context(
+                &dependency_source,
+                dependency_env.as_deref(),
+                prompts,
+                secrets,
+                &dependency_provider_values,
+                &dependency_context,
+            )
+            .map_err(respan)?;
+
+            let fetcher: HttpRequestFetcher = dependency_reqfile.into();
+            let response = fetcher.fetch().await.map_err(|err| {
+                vec![(
+                    FetchError::RequestError(format!("{}: {err}", dependency.path)).into(),
+                    config_span.clone(),
+                )]
+            })?;
+
+            for value in dependency.values.unwrap_or_default() {
+                let name = format!("{}.{}", dependency.name, value.name);
+
+                match value.extractor.extract(&response) {
+                    Some(extracted) => {
+                        values.insert(name, extracted);
+                    }
+                    None => {
+                        return Err(vec![(
+                            ResolverError::DependencyValueNotExtracted(name).into(),
+                            config_span,
+                        )]);
+                    }
+                }
+            }
+        }
+
+        Ok(values)
+    })
+}
+
+/// Parse a request file and the request files it depends on
+///
+/// Request files that can't be read or parsed are skipped, they're reported when resolving the request file.
+/// The request selected by `context` is only selected in the request file at `path`.
+pub fn parse_with_dependencies(
+    path: impl AsRef<Path>,
+    source: &str,
+    context: &ParseContext,
+) -> Vec<ParsedRequestFile> {
+    let mut reqfiles = vec![];
+    let mut visited: Vec<PathBuf> = vec![];
+    let mut files = vec![(path.as_ref().to_path_buf(), source.to_string())];
+
+    while let Some((path, source)) = files.pop() {
+        let is_root = visited.is_empty();
+
+        visited.push(fs::canonicalize(&path).unwrap_or_else(|_| path.clone()));
+
+        let context = ParseContext {
+            path: Some(path.clone()),
+            shared_config: context.shared_config.clone(),
+            request: context.request.clone().filter(|_| is_root),
+        };
+
+        let Ok(reqfile) = parse_with_context(&Ast::from(&source), &context) else {
+            continue;
+        };
+
+        let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let depends = reqfile
+            .config
+            .as_ref()
+            .and_then(|(config, _)| config.depends.clone())
+            .unwrap_or_default();
+
+        for dependency in depends {
+            let dependency_path = base_dir.join(&dependency.path);
+
+            let is_visited = fs::canonicalize(&dependency_path)
+                .is_ok_and(|canonical_path| visited.contains(&canonical_path));
+
+            if is_visited {
+                continue;
+            }
+
+            if let Ok(dependency_source) = fs::read_to_string(&dependency_path) {
+                files.push((dependency_path, dependency_source));
+            }
+        }
+
+        reqfiles.push(reqfile);
+    }
+
+    reqfiles
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use httptest::{
+        Expectation, Server,
+        matchers::request,
+        responders::{json_encoded, status_code},
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        errors::{ParseError, ReqlangError},
+        test_utils::write_files,
+    };
+
+    use super::resolve_dependencies;
+
+    #[tokio::test]
+    async fn resolve_dependency_values() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/login")).respond_with(
+                json_encoded(serde_json::json!({ "access_token": "abc123", "user": { "id": 7 } }))
+                    .insert_header("x-session", "session-id"),
+            ),
+        );
+
+        let login_url = server.url("/login");
+
+        let dir = write_files(
+            "dependencies-resolve_dependency_values",
+            &[
+                (
+                    "login.reqlang",
+                    format!("```%request\nPOST {login_url} HTTP/1.1\n```\n"),
+                ),
+                (
+                    "main.reqlang",
+                    textwrap::dedent(
+                        r#"
+                        ```%config
+                        [[depends]]
+                        name = "login"
+                        path = "./login.reqlang"
+
+                        [[depends.values]]
+                        name = "token"
+                        json = "/access_token"
+
+                        [[depends.values]]
+                        name = "user_id"
+                        json = "/user/id"
+
+                        [[depends.values]]
+                        name = "session"
+                        header = "X-Session"
+                        ```
+
+                        ```%request
+                        GET https://example.com/users/{{>login.user_id}} HTTP/1.1
+                        authorization: Bearer {{>login.token}}
+                        x-session: {{>login.session}}
+                        ```
+                        "#,
+                    ),
+                ),
+            ],
+        );
+
+        let path = dir.join("main.reqlang");
+        let source = fs::read_to_string(&path).unwrap();
+
+        let values = resolve_dependencies(
+            &path,
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([
+                ("login.token".to_string(), "abc123".to_string()),
+                ("login.user_id".to_string(), "7".to_string()),
+                ("login.session".to_string(), "session-id".to_string()),
+            ])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_chained_dependencies() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/a"))
+                .respond_with(status_code(200).body("token=first")),
+        );
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/b/first"))
+                .respond_with(status_code(200).body("token=second")),
+        );
+
+        let a_url = server.url("/a");
+        let b_url = server.url_str("/b");
+
+        let dir = write_files(
+            "dependencies-resolve_chained_dependencies",
+            &[
+                (
+                    "a.reqlang",
+                    format!("```%request\nGET {a_url} HTTP/1.1\n```\n"),
+                ),
+                (
+                    "b.reqlang",
+                    format!(
+                        "```%config\n[[depends]]\nname = \"a\"\npath = \"a.reqlang\"\n\n[[depends.values]]\nname = \"token\"\nregex = \"token=(.+)\"\n```\n\n```%request\nGET {b_url}/{{{{>a.token}}}} HTTP/1.1\n```\n"
+                    ),
+                ),
+                (
+                    "main.reqlang",
+                    "```%config\n[[depends]]\nname = \"b\"\npath = \"b.reqlang\"\n\n[[depends.values]]\nname = \"token\"\nregex = \"token=(.+)\"\n```\n\n```%request\nGET https://example.com/{{>b.token}} HTTP/1.1\n```\n".to_string(),
+                ),
+            ],
+        );
+
+        let path = dir.join("main.reqlang");
+        let source = fs::read_to_string(&path).unwrap();
+
+        let values = resolve_dependencies(
+            &path,
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "b.token".to_string(),
+                "second".to_string()
+            )])),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_dependency() {
+        let source = textwrap::dedent(
+            r#"
+            ```%config
+            [[depends]]
+            name = "login"
+            path = "./does_not_exist.reqlang"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            ```
+            "#,
+        );
+
+        let dir = write_files(
+            "dependencies-missing_dependency",
+            &[("main.reqlang", source.clone())],
+        );
+
+        let values = resolve_dependencies(
+            dir.join("main.reqlang"),
+            &source,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::MissingDependency(
+                    "./does_not_exist.reqlang".to_string()
+                )),
+                12..72
+            )]),
+            values
+        );
+    }
+
+    #[tokio::test]
+    async fn circular_dependency() {
+        let a = "```%config\n[[depends]]\nname = \"b\"\npath = \"b.reqlang\"\n```\n\n```%request\nGET https://example.com/a HTTP/1.1\n```\n";
+        let b = "```%config\n[[depends]]\nname = \"a\"\npath = \"a.reqlang\"\n```\n\n```%request\nGET https://example.com/b HTTP/1.1\n```\n";
+
+        let dir = write_files(
+            "dependencies-circular_dependency",
+            &[("a.reqlang", a.to_string()), ("b.reqlang", b.to_string())],
+        );
+
+        let values = resolve_dependencies(
+            dir.join("a.reqlang"),
+            a,
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            None,
+        )
+        .await;
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::CircularDependency("a.reqlang".to_string())),
+                11..52
+            )]),
+            values
+        );
+    }
+}
@@ -1,10 +1,14 @@
+use std::fs;
+
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     errors::{FetchError, ParseError, ReqlangError, ResolverError},
+    lint::LintFinding,
     span::{Span, Spanned},
     str_idxpos::index_to_position,
+    types::lint::LintLevel,
 };
 
 /// Get a list of diagnostics from a list of errors
@@ -13,11 +17,63 @@ pub fn get_diagnostics(errs: &[Spanned<ReqlangError>], source: &str) -> Vec<Diag
         .map(|(err, span)| Diagnosis {
             range: get_range(source, span),
             severity: Some(DiagnosisSeverity::ERROR),
+            code: None,
             message: err.to_string(),
+            related_information: get_related_information(err),
         })
         .collect()
 }
 
+/// Get a list of diagnostics from a list of lint findings
+///
+/// The severity follows the level of the lint and the code is the lint's code.
+/// Findings from allowed lints are left out.
+pub fn get_lint_diagnostics(findings: &[LintFinding], source: &str) -> Vec<Diagnosis> {
+    findings
+        .iter()
+        .filter_map(|finding| {
+            let severity = match finding.level {
+                LintLevel::Allow => return None,
+                LintLevel::Hint => DiagnosisSeverity::HINT,
+                LintLevel::Info => DiagnosisSeverity::INFORMATION,
+                LintLevel::Warn => DiagnosisSeverity::WARNING,
+                LintLevel::Deny => DiagnosisSeverity::ERROR,
+            };
+
+            Some(Diagnosis {
+                range: get_range(source, &finding.span),
+                severity: Some(severity),
+                code: Some(finding.lint.code().to_string()),
+                message: finding.message.clone(),
+                related_information: vec![],
+            })
+        })
+        .collect()
+}
+
+/// Get the locations in other files related to an error (e.g. an imported file)
+fn get_related_information(err: &ReqlangError) -> Vec<DiagnosisRelatedInformation> {
+    let (path, span, message) = match err {
+        ReqlangError::ParseError(ParseError::InvalidImport {
+            path,
+            message,
+            span,
+        }) => (path, span, message.clone()),
+        ReqlangError::ParseError(ParseError::ConflictingImport { name, path, span }) => {
+            (path, span, format!("'{name}' is defined here"))
+        }
+        _ => return vec![],
+    };
+
+    let source = fs::read_to_string(path).unwrap_or_default();
+
+    vec![DiagnosisRelatedInformation {
+        path: path.clone(),
+        range: get_range(&source, span),
+        message,
+    }]
+}
+
 fn get_range(source: &str, span: &Span) -> DiagnosisRange {
     DiagnosisRange {
         start: get_position(source, span.start),
@@ -42,6 +98,23 @@ pub struct Diagnosis {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub severity: Option<DiagnosisSeverity>,
 
+    /// Code of the lint reporting the diagnosis, e.g. `unused_value`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
+    pub message: String,
+
+    /// Locations in other files related to the diagnosis
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosisRelatedInformation>,
+}
+
+/// A location in another file related to a [Diagnosis]
+#[derive(Debug, Eq, PartialEq, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosisRelatedInformation {
+    pub path: String,
+    pub range: DiagnosisRange,
     pub message: String,
 }
 
@@ -113,13 +186,17 @@ impl AsDiagnostic for ReqlangError {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
 
     use crate::{
         ast::Ast,
         diagnostics::{
             Diagnosis, DiagnosisPosition, DiagnosisRange, DiagnosisSeverity, get_diagnostics,
+            get_lint_diagnostics,
         },
+        lint::{LintFinding, lint},
         parser::parse,
+        types::lint::{Lint, LintLevel},
     };
 
     #[test]
@@ -143,9 +220,105 @@ mod tests {
                     },
                 },
                 severity: Some(DiagnosisSeverity::ERROR),
-                message: String::from("ParseError: Request file requires a request be defined")
+                code: None,
+                message: String::from("ParseError: Request file requires a request be defined"),
+                related_information: vec![],
             }],
             get_diagnostics(&errs, &source)
         );
     }
+
+    #[test]
+    fn misspelled_request_block() {
+        let source = String::from("```%reqeust\nGET / HTTP/1.1\n```\n");
+
+        let ast = Ast::from(&source);
+
+        let errs = parse(&ast).unwrap_err();
+
+        assert_eq!(
+            vec![
+                Diagnosis {
+                    range: DiagnosisRange {
+                        start: DiagnosisPosition {
+                            line: 0,
+                            character: 0,
+                        },
+                        end: DiagnosisPosition {
+                            line: 2,
+                            character: 3,
+                        },
+                    },
+                    severity: Some(DiagnosisSeverity::ERROR),
+                    code: None,
+                    message: String::from(
+                        "ParseError: Unknown code block `%reqeust`, did you mean `%request`?"
+                    ),
+                    related_information: vec![],
+                },
+                Diagnosis {
+                    range: DiagnosisRange {
+                        start: DiagnosisPosition {
+                            line: 0,
+                            character: 0,
+                        },
+                        end: DiagnosisPosition {
+                            line: 0,
+                            character: 0,
+                        },
+                    },
+                    severity: Some(DiagnosisSeverity::ERROR),
+                    code: None,
+                    message: String::from("ParseError: Request file requires a request be defined"),
+                    related_information: vec![],
+                }
+            ],
+            get_diagnostics(&errs, &source)
+        );
+    }
+
+    #[test]
+    fn lint_findings() {
+        let source = String::from(
+            "```%config\nsecrets = [\"api_key\"]\n```\n\n```%request\nGET https://example.com/?key={{!api_key}} HTTP/1.1\n```\n",
+        );
+
+        let reqfile = parse(&Ast::from(&source)).unwrap();
+
+        let findings = lint(&reqfile, &source, &HashMap::new());
+
+        assert_eq!(
+            vec![Diagnosis {
+                range: DiagnosisRange {
+                    start: DiagnosisPosition {
+                        line: 5,
+                        character: 29,
+                    },
+                    end: DiagnosisPosition {
+                        line: 5,
+                        character: 41,
+                    },
+                },
+                severity: Some(DiagnosisSeverity::WARNING),
+                code: Some(String::from("secret_in_url")),
+                message: String::from(
+                    "Secret {{!api_key}} is in the request target where it may be logged. Try sending it in a header instead."
+                ),
+                related_information: vec![],
+            }],
+            get_lint_diagnostics(&findings, &source)
+        );
+    }
+
+    #[test]
+    fn allowed_lint_findings() {
+        let findings = vec![LintFinding {
+            lint: Lint::UnusedValue,
+            level: LintLevel::Allow,
+            message: String::from("Value was declared but not used"),
+            span: 0..0,
+        }];
+
+        assert_eq!(Vec::<Diagnosis>::new(), get_lint_diagnostics(&findings, ""));
+    }
 }
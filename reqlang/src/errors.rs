@@ -1,4 +1,4 @@
-use crate::types::ReferenceType;
+use crate::{span::Span, types::ReferenceType};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
@@ -26,10 +26,6 @@ pub enum ParseError {
     InvalidConfigError { message: String },
     #[error("Undefined template reference: {0}")]
     UndefinedReferenceError(ReferenceType),
-    #[error(
-        "Value was declared but not used. Try adding the template reference {0} to the request or response."
-    )]
-    UnusedValueError(ReferenceType),
     #[error(
         "This request header is calculated at request time and can not be specified by user: {0}"
     )]
@@ -38,6 +34,47 @@ pub enum ParseError {
     VariableUndefinedInEnvironment(String, String),
     #[error("Variable '{0}' is not defined in any environment or no environments are defined")]
     VariableNotDefinedInAnyEnvironment(String),
+    #[error("Dependency request file does not exist: '{0}'")]
+    MissingDependency(String),
+    #[error("Circular dependency on request file: '{0}'")]
+    CircularDependency(String),
+    #[error("Response matcher is invalid: {0}")]
+    InvalidResponseMatcher(String),
+    #[error("OAuth2 config is missing the required field: '{0}'")]
+    MissingOAuth2ConfigField(String),
+    /// The `span` is the location in the imported file
+    #[error("Unable to import '{path}': {message}")]
+    InvalidImport {
+        path: String,
+        message: String,
+        span: Span,
+    },
+    #[error("Only one `{0}` block can be defined")]
+    DuplicateBlock(String),
+    #[error("Unknown code block `{0}`, did you mean `{1}`?")]
+    MisspelledBlock(String, String),
+    #[error("Request '{0}' is defined more than once")]
+    DuplicateRequestName(String),
+    #[error("{}", response_without_request(.0))]
+    ResponseWithoutRequest(Option<String>),
+    #[error("Unable to read the request body file '{path}': {message}")]
+    InvalidBodyFile { path: String, message: String },
+    #[error("Multipart body is invalid: {0}")]
+    InvalidMultipartPart(String),
+    /// The `span` is the location of the conflicting definition in the imported file
+    #[error("Conflicting definitions of '{name}' imported from '{path}'")]
+    ConflictingImport {
+        name: String,
+        path: String,
+        span: Span,
+    },
+}
+
+fn response_without_request(name: &Option<String>) -> String {
+    match name {
+        Some(name) => format!("Response '{name}' does not have a request named '{name}'"),
+        None => "Unnamed response does not have an unnamed request".to_string(),
+    }
 }
 
 #[derive(Debug, Clone, Error, PartialEq, Serialize, Deserialize, TS)]
@@ -45,6 +82,8 @@ pub enum ParseError {
 pub enum ResolverError {
     #[error("'{0}' is not a defined environment in the request file")]
     InvalidEnvError(String),
+    #[error("'{0}' is not a defined request in the request file")]
+    InvalidRequestName(String),
     #[error(
         "Trying to resolve the environment '{0}' but no environments are defined in the request file"
     )]
@@ -53,6 +92,12 @@ pub enum ResolverError {
     PromptValueNotPassed(String),
     #[error("Secret required but not passed: {0}")]
     SecretValueNotPassed(String),
+    #[error("Dependency value required but not passed: {0}")]
+    DependencyValueNotPassed(String),
+    #[error("Unable to extract dependency value from response: {0}")]
+    DependencyValueNotExtracted(String),
+    #[error("Auth value required but not passed: {0}")]
+    AuthValueNotPassed(String),
     #[error("There was an error evaluating the expression: '{0}'; Error: {1}")]
     ExpressionEvaluationError(String, String),
 }
@@ -62,6 +107,62 @@ pub enum ResolverError {
 pub enum FetchError {
     #[error("An error occurred when making request: '{0}'")]
     RequestError(String),
+    #[error("Invalid request method: '{0}'")]
+    InvalidRequestMethod(String),
+    #[error("Unsupported HTTP version: 'HTTP/{0}'")]
+    UnsupportedHttpVersion(String),
+    #[error("Invalid client config: {0}")]
+    InvalidClientConfig(String),
+    #[error("Unable to obtain an OAuth2 access token: {0}")]
+    OAuth2TokenError(String),
+    #[error("Unable to read the request body file '{0}': {1}")]
+    BodyFileError(String, String),
+    #[error("Multipart body is invalid: {0}")]
+    InvalidMultipartBody(String),
+    #[error("HTTP/2 wasn't negotiated with the server for '{0}'")]
+    Http2NotNegotiated(String),
+}
+
+/// Error loading a workspace manifest (`reqlang.toml`)
+#[derive(Debug, Clone, Error, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum WorkspaceError {
+    #[error("Unable to read the workspace manifest '{0}': {1}")]
+    ReadError(String, String),
+    #[error("Workspace manifest is invalid: {0}")]
+    InvalidManifest(String),
+    #[error("Workspace variable '{0}' is undefined in the environment '{1}'")]
+    VariableUndefinedInEnvironment(String, String),
+}
+
+/// Error getting secret values from a [crate::secrets::SecretProvider]
+#[derive(Debug, Clone, Error, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum SecretProviderError {
+    #[error("Unable to read the dotenv file '{0}': {1}")]
+    ReadError(String, String),
+    #[error("Dotenv file '{0}' is invalid on line {1}")]
+    InvalidDotenv(String, usize),
+    #[error("Unable to get the secret '{0}' from the command: {1}")]
+    CommandError(String, String),
+    #[error(
+        "Secret name '{0}' can't be passed to a command, only letters, digits, '_', '.', and '-' are allowed"
+    )]
+    InvalidSecretName(String),
+}
+
+/// Error renaming a value declared in the config
+#[derive(Debug, Clone, Error, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum RenameError {
+    #[error("Only variables, prompts, and secrets can be renamed")]
+    NotRenameable,
+    #[error("'{0}' isn't declared in the request file")]
+    NotDeclared(String),
+    #[error("'{0}' isn't a valid name")]
+    InvalidName(String),
+    #[error("'{0}' is already declared")]
+    AlreadyDeclared(String),
 }
 
 macro_rules! impl_from_error {
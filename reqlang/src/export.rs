@@ -49,34 +49,47 @@ pub fn export(request: &HttpRequest, format: RequestFormat) -> String {
         RequestFormat::CurlCommand => {
             let request_verb_flag = match request.verb.0.as_str() {
                 "GET" => String::new(),
+                "HEAD" => String::from("-I "),
                 verb => format!("-X {verb} "),
             };
 
             let request_url = &request.target;
 
-            let header_args = if request.headers.is_empty() {
+            let multipart_parts = request.multipart_parts().and_then(Result::ok);
+
+            // curl sets the multipart content type with its boundary
+            let headers = request
+                .headers
+                .iter()
+                .filter(|x| multipart_parts.is_none() || !x.0.eq_ignore_ascii_case("content-type"))
+                .map(|x| format!(r#"-H "{}: {}""#, x.0, x.1))
+                .collect::<Vec<String>>();
+
+            let header_args = if headers.is_empty() {
                 None
             } else {
-                Some(
-                    request
-                        .headers
-                        .clone()
-                        .into_iter()
-                        .map(|x| format!(r#"-H "{}: {}""#, x.0, x.1))
+                Some(headers.join(" "))
+            };
+
+            let body_arg = match (&multipart_parts, request.body_file.as_deref()) {
+                (Some(parts), _) => Some(
+                    parts
+                        .iter()
+                        .map(|part| format!("-F '{part}'"))
                         .collect::<Vec<String>>()
-                        .join(" ")
-                        .to_string(),
+                        .join(" "),
                 )
+                .filter(|x| !x.is_empty()),
+                (None, Some(path)) => Some(format!("--data-binary '@{path}'")),
+                (None, None) => request.body.clone().and_then(|x| {
+                    if x.is_empty() {
+                        None
+                    } else {
+                        Some(format!("-d '{x}'"))
+                    }
+                }),
             };
 
-            let body_arg = request.body.clone().and_then(|x| {
-                if x.is_empty() {
-                    None
-                } else {
-                    Some(format!("-d '{x}'"))
-                }
-            });
-
             let headers_and_body_args = match (&header_args, &body_arg) {
                 (Some(headers), Some(body)) => format!(" {headers} {body}"),
                 (Some(headers), None) => format!(" {headers}"),
@@ -84,9 +97,17 @@ pub fn export(request: &HttpRequest, format: RequestFormat) -> String {
                 (None, None) => String::new(),
             };
 
+            // Cleartext HTTP/2 (h2c) requires prior knowledge
+            let http_version_flag =
+                if request.http_version.is_two() && request_url.starts_with("http://") {
+                    String::from("--http2-prior-knowledge")
+                } else {
+                    format!("--http{}", request.http_version)
+                };
+
             format!(
-                "curl {}{} --http{}{} -v",
-                request_verb_flag, request_url, request.http_version, headers_and_body_args
+                "curl {}{} {}{} -v",
+                request_verb_flag, request_url, http_version_flag, headers_and_body_args
             )
         }
         RequestFormat::Json => serde_json::to_string_pretty(request).unwrap(),
@@ -138,7 +159,7 @@ pub fn export_response(response: &HttpResponse, format: ResponseFormat) -> Strin
 
 #[cfg(test)]
 mod test {
-    use crate::types::http::{HttpRequest, HttpResponse, HttpStatusCode, HttpVersion};
+    use crate::types::http::{HttpRequest, HttpResponse, HttpStatusCode, HttpVerb, HttpVersion};
 
     use super::{RequestFormat, ResponseFormat, export, export_response};
 
@@ -207,6 +228,81 @@ mod test {
         "curl -X POST / --http1.1 -H \"test: value\" -d 'testing' -v"
     );
 
+    export_test!(
+        format_to_curl_post_request_with_body_file,
+        HttpRequest {
+            body_file: Some("/tmp/image.png".to_string()),
+            ..HttpRequest::post(
+                "/",
+                "1.1",
+                vec![("content-type".to_string(), "image/png".to_string())],
+                None
+            )
+        },
+        RequestFormat::CurlCommand,
+        "curl -X POST / --http1.1 -H \"content-type: image/png\" --data-binary '@/tmp/image.png' -v"
+    );
+
+    export_test!(
+        format_to_curl_multipart_request,
+        HttpRequest::post(
+            "/",
+            "1.1",
+            vec![
+                ("x-test".to_string(), "value".to_string()),
+                (
+                    "content-type".to_string(),
+                    "multipart/form-data".to_string()
+                )
+            ],
+            Some("name=Ada\navatar=@/tmp/ada.png;type=image/png\n\n")
+        ),
+        RequestFormat::CurlCommand,
+        "curl -X POST / --http1.1 -H \"x-test: value\" -F 'name=Ada' -F 'avatar=@/tmp/ada.png;type=image/png' -v"
+    );
+
+    export_test!(
+        format_to_curl_http_1_0_request,
+        HttpRequest::get("https://example.com", "1.0", vec![]),
+        RequestFormat::CurlCommand,
+        "curl https://example.com --http1.0 -v"
+    );
+
+    export_test!(
+        format_to_curl_http_2_request,
+        HttpRequest::get("https://example.com", "2", vec![]),
+        RequestFormat::CurlCommand,
+        "curl https://example.com --http2 -v"
+    );
+
+    export_test!(
+        format_to_curl_h2c_request,
+        HttpRequest::get("http://localhost:8080", "2", vec![]),
+        RequestFormat::CurlCommand,
+        "curl http://localhost:8080 --http2-prior-knowledge -v"
+    );
+
+    export_test!(
+        format_to_http_2_request,
+        HttpRequest::get("https://example.com", "2", vec![]),
+        RequestFormat::HttpMessage,
+        "GET https://example.com HTTP/2\n"
+    );
+
+    export_test!(
+        format_to_curl_head_request,
+        HttpRequest::new(HttpVerb::head(), "/", "1.1", vec![], None),
+        RequestFormat::CurlCommand,
+        "curl -I / --http1.1 -v"
+    );
+
+    export_test!(
+        format_to_curl_delete_request,
+        HttpRequest::new(HttpVerb::delete(), "/", "1.1", vec![], None),
+        RequestFormat::CurlCommand,
+        "curl -X DELETE / --http1.1 -v"
+    );
+
     export_test!(
         format_to_http_get_request,
         HttpRequest::get("/", "1.1", vec![]),
@@ -228,7 +324,9 @@ mod test {
             status_code: HttpStatusCode::new(200),
             status_text: "OK".into(),
             headers: vec![],
-            body: Some("".to_owned())
+            body: Some("".to_owned()),
+            binary_body: None,
+            metrics: None
         },
         ResponseFormat::HttpMessage,
         "HTTP/1.1 200 OK\n"
@@ -244,7 +342,9 @@ mod test {
                 ("x-value".to_string(), "123".to_string()),
                 ("content-type".to_string(), "application/json".to_string())
             ],
-            body: Some("".to_owned())
+            body: Some("".to_owned()),
+            binary_body: None,
+            metrics: None
         },
         ResponseFormat::HttpMessage,
         "HTTP/1.1 200 OK\nx-value: 123\ncontent-type: application/json\n"
@@ -257,7 +357,9 @@ mod test {
             status_code: HttpStatusCode::new(200),
             status_text: "OK".into(),
             headers: vec![],
-            body: Some("response body\n".to_owned())
+            body: Some("response body\n".to_owned()),
+            binary_body: None,
+            metrics: None
         },
         ResponseFormat::Body,
         "response body\n"
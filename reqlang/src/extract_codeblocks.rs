@@ -10,9 +10,23 @@ pub fn extract_codeblocks(
     input: impl AsRef<str>,
     target_lang: impl AsRef<str>,
 ) -> Vec<SpannedCodeBlock> {
+    extract_named_codeblocks(input, target_lang)
+        .into_iter()
+        .map(|(_, codeblock)| codeblock)
+        .collect()
+}
+
+/// Extract matching lang code blocks from a markdown string with their names.
+///
+/// The name is the text following the lang: ```` ```%request create-user ````
+pub fn extract_named_codeblocks(
+    input: impl AsRef<str>,
+    target_lang: impl AsRef<str>,
+) -> Vec<(Option<String>, SpannedCodeBlock)> {
+    let input = input.as_ref();
     let mut results = vec![];
 
-    let md_nodes: Vec<Node> = to_mdast(input.as_ref(), &markdown::ParseOptions::default())
+    let md_nodes: Vec<Node> = to_mdast(input, &markdown::ParseOptions::default())
         .unwrap()
         .children()
         .cloned()
@@ -24,14 +38,24 @@ pub fn extract_codeblocks(
             let start = position.start.offset;
             let end = position.end.offset;
 
+            // The text starts on the line after the opening fence
             let text = codeblock.value.clone();
-            let text_start = (3 + 1) + target_lang.as_ref().len() + start;
+            let text_start = input[start..end]
+                .find('\n')
+                .map(|index| start + index + 1)
+                .unwrap_or(end);
             let text_end = text_start + text.len();
 
             if let Some(lang) = &codeblock.lang
                 && lang == target_lang.as_ref()
             {
-                results.push(((text, text_start..text_end), start..end));
+                let name = codeblock
+                    .meta
+                    .as_ref()
+                    .map(|meta| meta.trim().to_string())
+                    .filter(|meta| !meta.is_empty());
+
+                results.push((name, ((text, text_start..text_end), start..end)));
             }
         }
     }
@@ -39,6 +63,27 @@ pub fn extract_codeblocks(
     results
 }
 
+/// Extract the lang of every code block in a markdown string with the span of the code block.
+pub fn extract_codeblock_langs(input: impl AsRef<str>) -> Vec<Spanned<String>> {
+    to_mdast(input.as_ref(), &markdown::ParseOptions::default())
+        .unwrap()
+        .children()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|md_node| match md_node {
+            Node::Code(codeblock) => {
+                let position = codeblock.position.as_ref().unwrap();
+
+                codeblock
+                    .lang
+                    .map(|lang| (lang, position.start.offset..position.end.offset))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +166,43 @@ TEST TEST
 
         assert_eq!(expected, extract_codeblocks(input, "nonmatching_lang"));
     }
+
+    #[test]
+    fn extract_named_codeblocks_matching_lang() {
+        let input = textwrap::dedent(
+            "
+```test_lang create-user
+TEST
+```
+
+```test_lang
+TEST TEST
+```
+            ",
+        );
+
+        assert_eq!(
+            vec![
+                (
+                    Some(String::from("create-user")),
+                    ((String::from("TEST"), 26..30), 1..34)
+                ),
+                (None, ((String::from("TEST TEST"), 49..58), 36..62))
+            ],
+            extract_named_codeblocks(input, "test_lang")
+        );
+    }
+
+    #[test]
+    fn extract_codeblock_langs_with_spans() {
+        let input = "```javascript\nconst foo = 123;\n```\n\n```\nno lang\n```\n\n```%reqeust\nGET / HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![
+                ("javascript".to_string(), 0..34),
+                ("%reqeust".to_string(), 53..83)
+            ],
+            extract_codeblock_langs(input)
+        );
+    }
 }
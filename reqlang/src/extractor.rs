@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+
+use crate::types::{ParsedConfigCapture, http::HttpResponse};
+
+/// Rule for extracting a value from an [HttpResponse]
+///
+/// ```toml
+/// header = "x-request-id"
+/// json = "/data/access_token"
+/// jsonpath = "$.data.access_token"
+/// regex = "id=([0-9]+)"
+/// status = "code"
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ResponseExtractor {
+    /// Value of a response header. Header names are case insensitive.
+    Header(String),
+    /// Value at a [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901) in the response body
+    Json(String),
+    /// Value at a [JSONPath](https://datatracker.ietf.org/doc/html/rfc9535) in the response body
+    ///
+    /// Only singular paths using names and indexes are supported (e.g. `$.data.items[0]['id']`).
+    #[serde(rename = "jsonpath")]
+    JsonPath(String),
+    /// First capture group (or the whole match) of a regular expression applied to the response body
+    Regex(String),
+    /// The response status `code` or `text`
+    Status(String),
+}
+
+impl ResponseExtractor {
+    /// Extract the value from the response, if present
+    pub fn extract(&self, response: &HttpResponse) -> Option<String> {
+        match self {
+            ResponseExtractor::Header(name) => response
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone()),
+            ResponseExtractor::Json(pointer) => extract_json_pointer(response, pointer),
+            ResponseExtractor::JsonPath(path) => {
+                extract_json_pointer(response, &json_path_to_pointer(path).ok()?)
+            }
+            ResponseExtractor::Regex(pattern) => {
+                let body = response.body.as_ref()?;
+                let captures = Regex::new(pattern).ok()?.captures(body)?;
+
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|value| value.as_str().to_string())
+            }
+            ResponseExtractor::Status(part) => match part.as_str() {
+                "code" => Some(response.status_code.to_string()),
+                "text" => Some(response.status_text.clone()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Validate the extractor, returning a message describing the problem if invalid
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ResponseExtractor::Json(pointer)
+                if !pointer.is_empty() && !pointer.starts_with('/') =>
+            {
+                Err(format!("JSON pointer must start with '/': '{pointer}'"))
+            }
+            ResponseExtractor::JsonPath(path) => json_path_to_pointer(path).map(|_| ()),
+            ResponseExtractor::Regex(pattern) => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|err| err.to_string()),
+            ResponseExtractor::Status(part) if part != "code" && part != "text" => {
+                Err(format!("Status must be either 'code' or 'text': '{part}'"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Values captured from a response by name
+///
+/// Captures that couldn't be extracted from the response are `None`.
+pub type CapturedValues = BTreeMap<String, Option<String>>;
+
+/// Capture values from a response using the captures declared in a request file's config
+pub fn capture(captures: &[ParsedConfigCapture], response: &HttpResponse) -> CapturedValues {
+    captures
+        .iter()
+        .map(|capture| (capture.name.clone(), capture.extractor.extract(response)))
+        .collect()
+}
+
+fn extract_json_pointer(response: &HttpResponse, pointer: &str) -> Option<String> {
+    let body = response.body.as_ref()?;
+    let json: Value = serde_json::from_str(body).ok()?;
+
+    json.pointer(pointer).map(|value| match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    })
+}
+
+/// Convert a singular JSONPath (e.g. `$.data.items[0]['id']`) in to a JSON pointer
+fn json_path_to_pointer(path: &str) -> Result<String, String> {
+    let invalid = || format!("Unsupported JSONPath: '{path}'");
+
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut pointer = String::new();
+
+    while !rest.is_empty() {
+        let segment = if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            let (name, after_name) = after_dot.split_at(end);
+
+            rest = after_name;
+            name
+        } else if let Some(after_bracket) = rest.strip_prefix("['") {
+            let end = after_bracket.find("']").ok_or_else(invalid)?;
+
+            rest = &after_bracket[end + 2..];
+            &after_bracket[..end]
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let end = after_bracket.find(']').ok_or_else(invalid)?;
+            let index = &after_bracket[..end];
+
+            if index.parse::<usize>().is_err() {
+                return Err(invalid());
+            }
+
+            rest = &after_bracket[end + 1..];
+            index
+        } else {
+            return Err(invalid());
+        };
+
+        if segment.is_empty() {
+            return Err(invalid());
+        }
+
+        pointer.push('/');
+        pointer.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    }
+
+    Ok(pointer)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::types::{
+        ParsedConfigCapture,
+        http::{HttpResponse, HttpStatusCode, HttpVersion},
+    };
+
+    use super::{ResponseExtractor, capture};
+
+    fn response() -> HttpResponse {
+        HttpResponse {
+            http_version: HttpVersion::one_point_one(),
+            status_code: HttpStatusCode::new(200),
+            status_text: "OK".to_string(),
+            headers: vec![("X-Request-Id".to_string(), "abc123".to_string())],
+            body: Some(
+                r#"{"data": {"token": "secret", "id": 42}, "message": "id=99"}"#.to_string(),
+            ),
+            binary_body: None,
+            metrics: None,
+        }
+    }
+
+    #[test]
+    fn extract_header() {
+        assert_eq!(
+            Some("abc123".to_string()),
+            ResponseExtractor::Header("x-request-id".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_missing_header() {
+        assert_eq!(
+            None,
+            ResponseExtractor::Header("x-missing".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_json_string() {
+        assert_eq!(
+            Some("secret".to_string()),
+            ResponseExtractor::Json("/data/token".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_json_number() {
+        assert_eq!(
+            Some("42".to_string()),
+            ResponseExtractor::Json("/data/id".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_json_missing() {
+        assert_eq!(
+            None,
+            ResponseExtractor::Json("/data/missing".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_regex_capture_group() {
+        assert_eq!(
+            Some("99".to_string()),
+            ResponseExtractor::Regex("id=([0-9]+)".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_regex_whole_match() {
+        assert_eq!(
+            Some("id=99".to_string()),
+            ResponseExtractor::Regex("id=[0-9]+".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn validate_invalid_regex() {
+        assert!(
+            ResponseExtractor::Regex("(".to_string())
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn validate_invalid_json_pointer() {
+        assert_eq!(
+            Err("JSON pointer must start with '/': 'data'".to_string()),
+            ResponseExtractor::Json("data".to_string()).validate()
+        );
+    }
+
+    #[test]
+    fn extract_json_path() {
+        assert_eq!(
+            Some("secret".to_string()),
+            ResponseExtractor::JsonPath("$.data.token".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn extract_json_path_with_brackets() {
+        let mut response = response();
+        response.body = Some(r#"{"items": [{"the id": 1}, {"the id": 2}]}"#.to_string());
+
+        assert_eq!(
+            Some("2".to_string()),
+            ResponseExtractor::JsonPath("$.items[1]['the id']".to_string()).extract(&response)
+        );
+    }
+
+    #[test]
+    fn validate_invalid_json_path() {
+        assert_eq!(
+            Err("Unsupported JSONPath: '$.items[*]'".to_string()),
+            ResponseExtractor::JsonPath("$.items[*]".to_string()).validate()
+        );
+    }
+
+    #[test]
+    fn extract_status() {
+        assert_eq!(
+            Some("200".to_string()),
+            ResponseExtractor::Status("code".to_string()).extract(&response())
+        );
+
+        assert_eq!(
+            Some("OK".to_string()),
+            ResponseExtractor::Status("text".to_string()).extract(&response())
+        );
+    }
+
+    #[test]
+    fn validate_invalid_status() {
+        assert_eq!(
+            Err("Status must be either 'code' or 'text': 'reason'".to_string()),
+            ResponseExtractor::Status("reason".to_string()).validate()
+        );
+    }
+
+    #[test]
+    fn capture_values() {
+        let captures = vec![
+            ParsedConfigCapture {
+                name: "token".to_string(),
+                extractor: ResponseExtractor::Json("/data/token".to_string()),
+            },
+            ParsedConfigCapture {
+                name: "missing".to_string(),
+                extractor: ResponseExtractor::Header("x-missing".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            BTreeMap::from([
+                ("missing".to_string(), None),
+                ("token".to_string(), Some("secret".to_string())),
+            ]),
+            capture(&captures, &response())
+        );
+    }
+}
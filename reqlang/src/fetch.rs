@@ -1,15 +1,38 @@
-use std::{collections::HashMap, future::Future};
+use std::{
+    collections::HashMap,
+    fs,
+    future::Future,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
 
 use crate::{
     errors::{FetchError, ReqlangError},
+    span::Spanned,
     types::{
-        RequestParamsFromClient,
-        http::{HttpRequest, HttpResponse, HttpStatusCode, HttpVersion},
+        RequestParamsFromClient, TemplatedRequestFile,
+        auth::ParsedConfigAuth,
+        client::ParsedConfigClient,
+        http::{
+            HttpRequest, HttpResponse, HttpResponseMetrics, HttpStatusCode, HttpVersion,
+            MultipartValue,
+        },
     },
 };
-use reqwest::{Method, Response, Version};
+use reqwest::{
+    Certificate, Client, ClientBuilder, Identity, Method, Proxy, Response, Version,
+    multipart::{Form, Part},
+    redirect::Policy,
+};
+
+use crate::templater::template_with_context;
 
-use crate::templater::template;
+/// The most redirects followed when `max_redirects` isn't set
+const DEFAULT_MAX_REDIRECTS: usize = 10;
 
 /// Implement a fetch that returns an [HttpResponse]. See [HttpRequestFetcher].
 pub trait Fetch {
@@ -20,43 +43,192 @@ pub trait Fetch {
 
 /// Fetch using an [HttpRequest] that returns an [HttpResponse]
 ///
+/// Auth from a [TemplatedRequestFile] is applied to the request when it's fetched, and its
+/// client options configure the HTTP client.
+///
 /// ## Usage
 ///
 /// ```ignore
 /// let fetcher: HttpRequestFetcher = http_request.into();
 /// let response: HttpResponse = fetcher.fetch().await?;
 /// ```
-pub struct HttpRequestFetcher(HttpRequest);
+pub struct HttpRequestFetcher(
+    pub(crate) HttpRequest,
+    pub(crate) Option<ParsedConfigAuth>,
+    pub(crate) Option<ParsedConfigClient>,
+);
 
 impl HttpRequestFetcher {
-    fn request_method(&self) -> Method {
-        match self.0.verb.0.as_str() {
-            "GET" => Method::GET,
-            "POST" => Method::POST,
-            _ => todo!(),
+    /// The request with any auth applied
+    fn request(&self) -> HttpRequest {
+        match &self.1 {
+            Some(auth) => auth.apply(&self.0, false),
+            None => self.0.clone(),
         }
     }
 
-    fn request_url(&self) -> &str {
-        &self.0.target
+    fn request_method(&self) -> Result<Method, FetchError> {
+        Method::from_bytes(self.0.verb.0.as_bytes())
+            .map_err(|_| FetchError::InvalidRequestMethod(self.0.verb.to_string()))
     }
 
-    fn body(&self) -> String {
-        self.0.body.clone().unwrap_or_default()
+    /// The body bytes, read from the body file if there is one
+    fn body(&self) -> Result<Vec<u8>, FetchError> {
+        match self.0.body_file.as_deref() {
+            Some(path) => fs::read(path)
+                .map_err(|err| FetchError::BodyFileError(path.to_string(), err.to_string())),
+            None => Ok(self.0.body.clone().unwrap_or_default().into_bytes()),
+        }
+    }
+
+    /// The multipart form, if the body is written as [crate::types::http::MultipartPart]s
+    fn multipart_form(&self) -> Option<Result<Form, FetchError>> {
+        let parts = match self.0.multipart_parts()? {
+            Ok(parts) => parts,
+            Err(message) => return Some(Err(FetchError::InvalidMultipartBody(message))),
+        };
+
+        let mut form = Form::new();
+
+        for part in parts {
+            let form_part = match &part.value {
+                MultipartValue::Text(value) => Part::text(value.clone()),
+                MultipartValue::File(path) => match fs::read(path) {
+                    Ok(bytes) => {
+                        let file_name = Path::new(path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+
+                        Part::bytes(bytes).file_name(file_name)
+                    }
+                    Err(err) => {
+                        return Some(Err(FetchError::BodyFileError(
+                            path.clone(),
+                            err.to_string(),
+                        )));
+                    }
+                },
+            };
+
+            let content_type = match (&part.content_type, &part.value) {
+                (Some(content_type), _) => Some(content_type.as_str()),
+                (None, MultipartValue::File(_)) => Some("application/octet-stream"),
+                (None, MultipartValue::Text(_)) => None,
+            };
+
+            let form_part = match content_type {
+                Some(content_type) => match form_part.mime_str(content_type) {
+                    Ok(form_part) => form_part,
+                    Err(err) => {
+                        return Some(Err(FetchError::InvalidMultipartBody(err.to_string())));
+                    }
+                },
+                None => form_part,
+            };
+
+            form = form.part(part.name, form_part);
+        }
+
+        Some(Ok(form))
     }
 
-    fn request_headers(&self) -> Vec<(&str, &str)> {
-        self.0
-            .headers
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect()
+    /// The version to send the request with
+    fn request_version(&self) -> Result<Version, FetchError> {
+        let http_version = &self.0.http_version;
+
+        if http_version.is_two() {
+            return Ok(Version::HTTP_2);
+        }
+
+        match http_version.to_string().as_str() {
+            "1.0" => Ok(Version::HTTP_10),
+            "1.1" => Ok(Version::HTTP_11),
+            _ => Err(FetchError::UnsupportedHttpVersion(http_version.to_string())),
+        }
+    }
+
+    /// An HTTP client for the request's version and client options
+    ///
+    /// The number of redirects followed is stored in `redirects`.
+    fn client(&self, version: Version, redirects: Arc<AtomicUsize>) -> Result<Client, FetchError> {
+        let options = self.2.clone().unwrap_or_default();
+
+        let invalid =
+            |err: &dyn std::fmt::Display| FetchError::InvalidClientConfig(err.to_string());
+
+        let read = |path: &str| {
+            fs::read(path).map_err(|err| {
+                FetchError::InvalidClientConfig(format!("Unable to read '{path}': {err}"))
+            })
+        };
+
+        let mut builder = match version {
+            // Cleartext HTTP/2 (h2c) requires prior knowledge, HTTPS negotiates it with ALPN
+            Version::HTTP_2 if self.0.target.starts_with("http://") => {
+                ClientBuilder::new().http2_prior_knowledge()
+            }
+            Version::HTTP_2 => ClientBuilder::new(),
+            _ => ClientBuilder::new().http1_only(),
+        };
+
+        if let Some(timeout) = options.timeout {
+            builder = builder.timeout(timeout.0);
+        }
+
+        if let Some(connect_timeout) = options.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout.0);
+        }
+
+        builder = match options.follow_redirects {
+            Some(false) => builder.redirect(Policy::none()),
+            _ => {
+                let max_redirects = options.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS);
+
+                builder.redirect(Policy::custom(move |attempt| {
+                    let count = attempt.previous().len();
+
+                    if count > max_redirects {
+                        attempt.error(format!("too many redirects (max: {max_redirects})"))
+                    } else {
+                        redirects.store(count, Ordering::Relaxed);
+                        attempt.follow()
+                    }
+                }))
+            }
+        };
+
+        if let Some(proxy) = &options.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(|err| invalid(&err))?);
+        }
+
+        if let Some(ca_cert) = &options.ca_cert {
+            let certs =
+                Certificate::from_pem_bundle(&read(ca_cert)?).map_err(|err| invalid(&err))?;
+
+            builder = builder.tls_certs_merge(certs);
+        }
+
+        if let Some(client_cert) = &options.client_cert {
+            builder = builder
+                .identity(Identity::from_pem(&read(client_cert)?).map_err(|err| invalid(&err))?);
+        }
+
+        if options.insecure == Some(true) {
+            builder = builder.tls_danger_accept_invalid_certs(true);
+        }
+
+        builder.build().map_err(|err| invalid(&err))
     }
 
     fn map_response_http_version(response: &Response) -> HttpVersion {
         match response.version() {
+            Version::HTTP_09 => "0.9".into(),
+            Version::HTTP_10 => HttpVersion::one_point_zero(),
             Version::HTTP_11 => HttpVersion::one_point_one(),
-            _ => todo!(),
+            Version::HTTP_2 => HttpVersion::two(),
+            Version::HTTP_3 => "3".into(),
+            version => format!("{version:?}").trim_start_matches("HTTP/").into(),
         }
     }
 
@@ -87,78 +259,169 @@ impl HttpRequestFetcher {
     }
 }
 
-impl Fetch for HttpRequestFetcher {
-    async fn fetch(&self) -> std::result::Result<HttpResponse, Box<dyn std::error::Error + Send>> {
-        let client = reqwest::ClientBuilder::new()
-            .http1_only()
-            .build()
-            .expect("should build reqwest HTTP client");
+impl HttpRequestFetcher {
+    /// Fetch the response, passing each chunk of the body to `on_chunk` as it arrives
+    ///
+    /// The returned [HttpResponse] still has the whole body.
+    pub async fn fetch_streaming(
+        &self,
+        mut on_chunk: impl FnMut(&[u8]) + Send,
+    ) -> Result<HttpResponse, Box<dyn std::error::Error + Send>> {
+        let method = match self.request_method() {
+            Ok(method) => method,
+            Err(err) => return Err(Box::new(ReqlangError::FetchError(err))),
+        };
+
+        let version = match self.request_version() {
+            Ok(version) => version,
+            Err(err) => return Err(Box::new(ReqlangError::FetchError(err))),
+        };
+
+        let redirects = Arc::new(AtomicUsize::new(0));
+
+        let client = match self.client(version, redirects.clone()) {
+            Ok(client) => client,
+            Err(err) => return Err(Box::new(ReqlangError::FetchError(err))),
+        };
+
+        let is_head_request = method == Method::HEAD;
+
+        let http_request = self.request();
+
+        // HTTP/2 over HTTPS is negotiated with ALPN instead of being required by the request
+        let is_negotiated =
+            version == Version::HTTP_2 && !http_request.target.starts_with("http://");
+
+        let mut request = client.request(method, &http_request.target);
+
+        if !is_negotiated {
+            request = request.version(version);
+        }
+
+        // The multipart content type is set with its boundary by the form
+        let is_multipart = http_request.is_multipart();
+
+        for (key, value) in http_request.headers.iter() {
+            if is_multipart && key.eq_ignore_ascii_case("content-type") {
+                continue;
+            }
+
+            request = request.header(key, value);
+        }
+
+        let body = match self.multipart_form() {
+            Some(form) => form.map(|form| request.multipart(form)),
+            None => self.body().map(|body| request.body(body)),
+        };
+
+        request = match body {
+            Ok(request) => request,
+            Err(err) => return Err(Box::new(ReqlangError::FetchError(err))),
+        };
+
+        let request_error = |err: reqwest::Error| -> Box<dyn std::error::Error + Send> {
+            Box::new(ReqlangError::FetchError(FetchError::RequestError(
+                err.to_string(),
+            )))
+        };
+
+        let started = Instant::now();
 
-        let mut request = client.request(self.request_method(), self.request_url());
+        let mut response = request.send().await.map_err(request_error)?;
 
-        for header in self.request_headers().into_iter() {
-            request = request.header(header.0, header.1);
+        if is_negotiated && response.version() != Version::HTTP_2 {
+            return Err(Box::new(ReqlangError::FetchError(
+                FetchError::Http2NotNegotiated(http_request.target.clone()),
+            )));
         }
 
-        request = request.body(self.body());
+        let time_to_first_byte = started.elapsed();
 
-        let response = request.send().await;
+        let (status_code, status_text) = Self::map_response_status_code_and_text(&response);
 
-        match response {
-            Ok(response) => {
-                let http_version = Self::map_response_http_version(&response);
-                let headers = Self::map_response_headers(&response);
-                let (status_code, status_text) = Self::map_response_status_code_and_text(&response);
-                let body = response.text().await.ok();
+        let mut http_response = HttpResponse {
+            http_version: Self::map_response_http_version(&response),
+            status_code,
+            status_text,
+            headers: Self::map_response_headers(&response),
+            body: None,
+            binary_body: None,
+            metrics: None,
+        };
+
+        let mut size = 0;
 
-                Ok(HttpResponse {
-                    http_version,
-                    status_code,
-                    status_text,
-                    headers,
-                    body,
-                })
+        // Responses to HEAD requests never include a body
+        if !is_head_request {
+            let mut body = vec![];
+
+            while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+                on_chunk(&chunk);
+                body.extend_from_slice(&chunk);
             }
-            Err(err) => Err(Box::new(ReqlangError::FetchError(
-                FetchError::RequestError(err.to_string()),
-            ))),
+
+            size = body.len();
+
+            http_response.set_body_bytes(body);
         }
+
+        http_response.metrics = Some(HttpResponseMetrics {
+            total_ms: started.elapsed().as_secs_f64() * 1000.0,
+            time_to_first_byte_ms: time_to_first_byte.as_secs_f64() * 1000.0,
+            size,
+            redirects: redirects.load(Ordering::Relaxed),
+        });
+
+        Ok(http_response)
+    }
+}
+
+impl Fetch for HttpRequestFetcher {
+    async fn fetch(&self) -> std::result::Result<HttpResponse, Box<dyn std::error::Error + Send>> {
+        self.fetch_streaming(|_| {}).await
     }
 }
 
 impl From<HttpRequest> for HttpRequestFetcher {
     fn from(value: HttpRequest) -> Self {
-        Self(value)
+        Self(value, None, None)
+    }
+}
+
+impl From<TemplatedRequestFile> for HttpRequestFetcher {
+    fn from(value: TemplatedRequestFile) -> Self {
+        Self(value.request, value.auth, value.client)
     }
 }
 
 /// Executes requests from an [`RequestParamsFromClient`].
 ///
+/// The request file is templated with its [crate::parser::ParseContext], see
+/// [RequestParamsFromClient::parse_context].
+///
 /// ```ignore
-/// let response: HttpResponse = Into::<RequestParamsFromClient>::into(params).fetch().await?;
+/// let response: HttpResponse = HttpRequestFetcher::try_from(params)?.fetch().await?;
 /// ```
-impl From<RequestParamsFromClient> for HttpRequestFetcher {
-    fn from(params: RequestParamsFromClient) -> Self {
-        let mut params_provider_values: HashMap<String, String> = params.provider_values.clone();
+impl TryFrom<RequestParamsFromClient> for HttpRequestFetcher {
+    type Error = Vec<Spanned<ReqlangError>>;
 
-        let mut provider_values: HashMap<String, String> = HashMap::new();
+    fn try_from(params: RequestParamsFromClient) -> Result<Self, Self::Error> {
+        let mut provider_values: HashMap<String, String> = params.provider_values.clone();
 
         if let Some(env) = &params.env {
             provider_values.insert("env".to_string(), env.clone());
         }
 
-        params_provider_values.extend(provider_values);
-
-        let reqfile = template(
+        let reqfile = template_with_context(
             &params.reqfile,
             params.env.as_deref(),
             &params.prompts,
             &params.secrets,
-            &params_provider_values,
-        )
-        .unwrap();
+            &provider_values,
+            &params.parse_context(),
+        )?;
 
-        Self(reqfile.request)
+        Ok(reqfile.into())
     }
 }
 
@@ -166,13 +429,30 @@ impl From<RequestParamsFromClient> for HttpRequestFetcher {
 mod test {
     use super::*;
 
-    use crate::types::http::{HttpStatusCode, HttpVerb};
+    use std::time::Duration;
+
+    use crate::{
+        test_utils::write_files,
+        types::{
+            auth::{ApiKeyPlacement, ParsedApiKeyAuthConfig, ParsedBearerAuthConfig},
+            client::ClientDuration,
+            http::{HttpStatusCode, HttpVerb},
+        },
+    };
     use httptest::{
         Expectation, Server,
-        matchers::{all_of, contains, request},
-        responders::status_code,
+        matchers::{all_of, contains, matches, request, url_decoded},
+        responders::{delay_and_then, status_code},
     };
     use pretty_assertions::assert_eq;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tokio_rustls::{
+        TlsAcceptor,
+        rustls::{ServerConfig, pki_types::PrivateKeyDer},
+    };
 
     #[tokio::test]
     async fn test_real_http_request_fetch() {
@@ -200,6 +480,7 @@ mod test {
                 ("x-test".to_string(), "foo".to_string()),
             ],
             body: Some("test body".to_string()),
+            body_file: None,
         };
 
         let fetcher: HttpRequestFetcher = http_request.into();
@@ -216,6 +497,517 @@ mod test {
         assert_eq!(Some("test response!".to_string()), response.body);
     }
 
+    #[rstest::rstest]
+    #[case(HttpVerb::get())]
+    #[case(HttpVerb::post())]
+    #[case(HttpVerb::put())]
+    #[case(HttpVerb::patch())]
+    #[case(HttpVerb::delete())]
+    #[case(HttpVerb::options())]
+    #[case(HttpVerb::trace())]
+    #[case(HttpVerb("PURGE".to_owned()))]
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_method(#[case] verb: HttpVerb) {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method(verb.to_string()),
+                request::path("/test"),
+            ])
+            .respond_with(status_code(200).body("test response!")),
+        );
+
+        let url = server.url("/test");
+
+        let http_request = HttpRequest::new(verb, url.to_string(), "1.1", vec![], None);
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(200), response.status_code);
+        assert_eq!(Some("test response!".to_string()), response.body);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_head_has_no_body() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![request::method("HEAD"), request::path("/test")])
+                .respond_with(status_code(200).insert_header("x-test", "foo")),
+        );
+
+        let url = server.url("/test");
+
+        let http_request = HttpRequest::new(HttpVerb::head(), url.to_string(), "1.1", vec![], None);
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(200), response.status_code);
+        assert!(
+            response
+                .headers
+                .contains(&("x-test".to_string(), "foo".to_string()))
+        );
+        assert_eq!(None, response.body);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_binary_body_streaming() {
+        let server = Server::run();
+
+        let png_signature: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/image"))
+                .respond_with(status_code(200).body(png_signature.clone())),
+        );
+
+        let http_request = HttpRequest::get(server.url_str("/image"), "1.1", vec![]);
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let mut chunks: Vec<u8> = vec![];
+
+        let response = fetcher
+            .fetch_streaming(|chunk| chunks.extend_from_slice(chunk))
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(None, response.body);
+        assert_eq!(png_signature, response.body_bytes());
+        assert_eq!(png_signature, chunks);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_body_file() {
+        let server = Server::run();
+
+        let png_signature: Vec<u8> = vec![0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+        let path = write_files("fetch-body_file", &[("body.png", &png_signature)]).join("body.png");
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/upload"),
+                request::body(png_signature.clone())
+            ])
+            .respond_with(status_code(201)),
+        );
+
+        let mut http_request = HttpRequest::post(server.url_str("/upload"), "1.1", vec![], None);
+        http_request.body_file = Some(path.display().to_string());
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(201), response.status_code);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_multipart() {
+        let server = Server::run();
+
+        let path =
+            write_files("fetch-multipart", &[("notes.txt", "file contents")]).join("notes.txt");
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/upload"),
+                request::headers(contains((
+                    "content-type",
+                    matches("^multipart/form-data; boundary=")
+                ))),
+                request::body(matches("name=\"name\"\r\n\r\nAda\r\n")),
+                request::body(matches(
+                    "name=\"notes\"; filename=\"notes.txt\"\r\nContent-Type: text/plain\r\n\r\nfile contents\r\n"
+                ))
+            ])
+            .respond_with(status_code(201)),
+        );
+
+        let body = format!("name=Ada\nnotes=@{};type=text/plain", path.display());
+        let http_request = HttpRequest::post(
+            server.url_str("/upload"),
+            "1.1",
+            vec![(
+                "content-type".to_string(),
+                "multipart/form-data".to_string(),
+            )],
+            Some(&body),
+        );
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(201), response.status_code);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_missing_body_file() {
+        let mut http_request = HttpRequest::post("http://localhost:9999", "1.1", vec![], None);
+        http_request.body_file = Some("/reqlang/missing.json".to_string());
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let err = fetcher.fetch().await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ReqlangError>(),
+            Some(ReqlangError::FetchError(FetchError::BodyFileError(path, _))) if path == "/reqlang/missing.json"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_h2c() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/http2"))
+                .respond_with(status_code(200).body("http2")),
+        );
+
+        let http_request = HttpRequest::get(server.url_str("/http2"), HttpVersion::two(), vec![]);
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpVersion::two(), response.http_version);
+        assert_eq!(Some("http2".to_string()), response.body);
+    }
+
+    /// An HTTPS server with a self signed certificate, offering `alpn_protocols`
+    ///
+    /// It responds with the body `http2` over HTTP/2, or `http1` otherwise.
+    async fn https_server(alpn_protocols: &[&[u8]]) -> String {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let key = PrivateKeyDer::Pkcs8(cert.key_pair.serialize_der().into());
+
+        let mut config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.cert.der().clone()], key)
+            .unwrap();
+        config.alpn_protocols = alpn_protocols.iter().map(|x| x.to_vec()).collect();
+
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = acceptor.accept(stream).await.unwrap();
+
+            if stream.get_ref().1.alpn_protocol() == Some(b"h2") {
+                let mut connection = h2::server::handshake(stream).await.unwrap();
+
+                while let Some(Ok((_, mut respond))) = connection.accept().await {
+                    let mut body = respond
+                        .send_response(http::Response::new(()), false)
+                        .unwrap();
+                    body.send_data(bytes::Bytes::from("http2"), true).unwrap();
+                }
+            } else {
+                let _ = stream.read(&mut [0; 1024]).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhttp1")
+                    .await;
+            }
+        });
+
+        format!("https://localhost:{port}/")
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_http2_over_https() {
+        let url = https_server(&[b"h2", b"http/1.1"]).await;
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get(url, HttpVersion::two(), vec![]),
+            ParsedConfigClient {
+                insecure: Some(true),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpVersion::two(), response.http_version);
+        assert_eq!(Some("http2".to_string()), response.body);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_http2_not_negotiated() {
+        let url = https_server(&[b"http/1.1"]).await;
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get(url, HttpVersion::two(), vec![]),
+            ParsedConfigClient {
+                insecure: Some(true),
+                ..Default::default()
+            },
+        );
+
+        let err = fetcher.fetch().await.unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ReqlangError>(),
+            Some(ReqlangError::FetchError(FetchError::Http2NotNegotiated(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_http_1_0() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/http1.0"))
+                .respond_with(status_code(200).body("http1.0")),
+        );
+
+        let http_request = HttpRequest::get(
+            server.url_str("/http1.0"),
+            HttpVersion::one_point_zero(),
+            vec![],
+        );
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpVersion::one_point_zero(), response.http_version);
+        assert_eq!(Some("http1.0".to_string()), response.body);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_unsupported_http_version() {
+        let http_request = HttpRequest::get("http://localhost:9999", "3", vec![]);
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+        let response = fetcher.fetch().await;
+
+        assert_eq!(
+            "FetchError: Unsupported HTTP version: 'HTTP/3'",
+            response.err().unwrap().to_string()
+        );
+    }
+
+    fn fetcher_with_client(request: HttpRequest, client: ParsedConfigClient) -> HttpRequestFetcher {
+        HttpRequestFetcher(request, None, Some(client))
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_without_following_redirects() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/redirect")).respond_with(
+                status_code(302).insert_header("location", server.url_str("/target")),
+            ),
+        );
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get(server.url_str("/redirect"), "1.1", vec![]),
+            ParsedConfigClient {
+                follow_redirects: Some(false),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(302), response.status_code);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_max_redirects() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/a"))
+                .respond_with(status_code(302).insert_header("location", server.url_str("/b"))),
+        );
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/b"))
+                .respond_with(status_code(302).insert_header("location", server.url_str("/c"))),
+        );
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get(server.url_str("/a"), "1.1", vec![]),
+            ParsedConfigClient {
+                max_redirects: Some(1),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher.fetch().await;
+
+        // The redirect to /b is followed, the redirect to /c isn't
+        assert!(response.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_metrics() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/a"))
+                .respond_with(status_code(302).insert_header("location", server.url_str("/b"))),
+        );
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/b"))
+                .respond_with(status_code(302).insert_header("location", server.url_str("/c"))),
+        );
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/c")).respond_with(delay_and_then(
+                Duration::from_millis(50),
+                status_code(200).body("test response!"),
+            )),
+        );
+
+        let fetcher: HttpRequestFetcher =
+            HttpRequest::get(server.url_str("/a"), "1.1", vec![]).into();
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        let metrics = response.metrics.expect("Should have metrics");
+
+        assert_eq!(14, metrics.size);
+        assert_eq!(2, metrics.redirects);
+        assert!(metrics.time_to_first_byte_ms >= 50.0);
+        assert!(metrics.total_ms >= metrics.time_to_first_byte_ms);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_timeout() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/slow"))
+                .respond_with(delay_and_then(Duration::from_secs(2), status_code(200))),
+        );
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get(server.url_str("/slow"), "1.1", vec![]),
+            ParsedConfigClient {
+                timeout: Some(ClientDuration(Duration::from_millis(100))),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher.fetch().await;
+
+        assert!(response.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_through_proxy() {
+        let proxy = Server::run();
+
+        proxy.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/proxied"),
+                request::headers(contains(("host", "example.invalid")))
+            ])
+            .respond_with(status_code(200).body("proxied")),
+        );
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get("http://example.invalid/proxied", "1.1", vec![]),
+            ParsedConfigClient {
+                proxy: Some(proxy.url_str("")),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(Some("proxied".to_string()), response.body);
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_invalid_client_cert() {
+        let path = write_files(
+            "fetch-invalid_client_cert",
+            &[("client.pem", "not a certificate")],
+        )
+        .join("client.pem");
+
+        let fetcher = fetcher_with_client(
+            HttpRequest::get("http://localhost:9999", "1.1", vec![]),
+            ParsedConfigClient {
+                client_cert: Some(path.display().to_string()),
+                ..Default::default()
+            },
+        );
+
+        let response = fetcher.fetch().await;
+
+        assert!(
+            response
+                .err()
+                .unwrap()
+                .to_string()
+                .starts_with("FetchError: Invalid client config: ")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_real_http_request_fetch_with_invalid_method() {
+        let http_request = HttpRequest::new(
+            HttpVerb("NOT A METHOD".to_owned()),
+            "http://localhost:9999",
+            "1.1",
+            vec![],
+            None,
+        );
+
+        let fetcher: HttpRequestFetcher = http_request.into();
+        let response = fetcher.fetch().await;
+
+        assert_eq!(
+            "FetchError: Invalid request method: 'NOT A METHOD'",
+            response.err().unwrap().to_string()
+        );
+    }
+
     #[tokio::test]
     async fn test_real_http_request_fetch_when_errors() {
         let url: httptest::http::Uri = httptest::http::Uri::from_static("http://localhost:9999");
@@ -229,6 +1021,7 @@ mod test {
                 ("x-test".to_string(), "foo".to_string()),
             ],
             body: Some("test body".to_string()),
+            body_file: None,
         };
 
         let fetcher: HttpRequestFetcher = http_request.into();
@@ -279,9 +1072,11 @@ test body
             prompts: HashMap::new(),
             secrets: HashMap::new(),
             provider_values: HashMap::from([("foo".to_string(), "bar".to_string())]),
+            uri: None,
+            request: None,
         };
 
-        let fetcher: HttpRequestFetcher = params.into();
+        let fetcher = HttpRequestFetcher::try_from(params).unwrap();
         let response = fetcher
             .fetch()
             .await
@@ -294,4 +1089,75 @@ test body
 
         assert_eq!(Some("test response!".to_string()), response.body);
     }
+
+    #[test]
+    fn request_params_select_named_request() {
+        let params = RequestParamsFromClient {
+            reqfile: "```%request first\nGET https://example.com/first HTTP/1.1\n```\n\n```%request second\nGET https://example.com/second HTTP/1.1\n```\n".to_string(),
+            request: Some("second".to_string()),
+            ..Default::default()
+        };
+
+        let fetcher = HttpRequestFetcher::try_from(params).unwrap();
+
+        assert_eq!("https://example.com/second", fetcher.0.target);
+    }
+
+    #[test]
+    fn request_params_with_errors() {
+        let params = RequestParamsFromClient {
+            reqfile: "```%request\nGET https://example.com/{{?id}} HTTP/1.1\n```\n".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Err(vec![(
+                crate::errors::ParseError::UndefinedReferenceError(
+                    crate::types::ReferenceType::Prompt("id".to_string())
+                )
+                .into(),
+                36..43
+            )]),
+            HttpRequestFetcher::try_from(params).map(|fetcher| fetcher.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_applies_auth() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/test"),
+                request::query(url_decoded(contains(("api_key", "xyz")))),
+                request::headers(contains(("authorization", "Bearer abc123"))),
+            ])
+            .respond_with(status_code(200)),
+        );
+
+        let templated_reqfile = TemplatedRequestFile {
+            request: HttpRequest::get(server.url_str("/test"), "1.1", vec![]),
+            response: None,
+            auth: Some(ParsedConfigAuth {
+                bearer: Some(ParsedBearerAuthConfig {
+                    token: "abc123".to_string(),
+                }),
+                api_key: Some(ParsedApiKeyAuthConfig {
+                    name: "api_key".to_string(),
+                    value: "xyz".to_string(),
+                    placement: Some(ApiKeyPlacement::Query),
+                }),
+                ..Default::default()
+            }),
+            client: None,
+        };
+
+        let fetcher: HttpRequestFetcher = templated_reqfile.into();
+        let response = fetcher
+            .fetch()
+            .await
+            .expect("Should be able to make real HTTP request");
+
+        assert_eq!(HttpStatusCode::new(200), response.status_code);
+    }
 }
@@ -0,0 +1,269 @@
+use reqlang_expr::prelude::*;
+
+use crate::{
+    span::Span,
+    types::{
+        ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequestFile, ReferenceType,
+    },
+};
+
+/// Information about the template reference or expression at a position in a request file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hover {
+    /// Markdown describing the reference or expression
+    pub contents: String,
+    /// Span of the reference or expression, including its braces
+    pub span: Span,
+}
+
+/// Get the hover information for the template reference or expression at `index` in the request file
+///
+/// Only references and expressions recorded in [ParsedRequestFile::refs] and
+/// [ParsedRequestFile::exprs] are described.
+pub fn hover(reqfile: &ParsedRequestFile, index: usize) -> Option<Hover> {
+    hover_expr(reqfile, index).or_else(|| hover_ref(reqfile, index))
+}
+
+fn contains(span: &Span, index: usize) -> bool {
+    span.start <= index && index < span.end
+}
+
+fn hover_expr(reqfile: &ParsedRequestFile, index: usize) -> Option<Hover> {
+    let (expr, span) = reqfile
+        .exprs
+        .iter()
+        .find(|(_, span)| contains(span, index))?;
+
+    Some(Hover {
+        contents: format!(
+            "`{{({expr})}}` expression\n\nType: `{}`",
+            expr_type(reqfile, expr)
+        ),
+        span: span.clone(),
+    })
+}
+
+/// The type of the value an expression evaluates to
+fn expr_type(reqfile: &ParsedRequestFile, expr: &str) -> Type {
+    let env = CompileTimeEnv::new(
+        reqfile.vars(),
+        reqfile.prompts(),
+        reqfile.secrets(),
+        vec!["env".to_string()],
+    );
+
+    let Ok(mut expr) = reqlang_expr::parser::parse(&format!("({expr})")) else {
+        return Type::Unknown;
+    };
+
+    add_type_to_expr(&mut expr, &env);
+
+    match &expr {
+        Expr::Call(call) => match &call.callee.0 {
+            Expr::Identifier(identifier) => env
+                .get_builtin_index(identifier.lookup_name())
+                .map(|(builtin, _)| builtin.return_type.clone())
+                .unwrap_or(Type::Unknown),
+            _ => Type::Unknown,
+        },
+        expr => expr.get_type(),
+    }
+}
+
+fn hover_ref(reqfile: &ParsedRequestFile, index: usize) -> Option<Hover> {
+    let (reference, span) = reqfile
+        .refs
+        .iter()
+        .find(|(_, span)| contains(span, index))?;
+
+    Some(Hover {
+        contents: describe_ref(reqfile, reference),
+        span: span.clone(),
+    })
+}
+
+fn describe_ref(reqfile: &ParsedRequestFile, reference: &ReferenceType) -> String {
+    let config = reqfile.config.as_ref().map(|(config, _)| config);
+
+    match reference {
+        ReferenceType::Variable(name) => {
+            let declaration = config
+                .and_then(|config| config.vars.as_ref())
+                .and_then(|vars| vars.iter().find(|var| &var.name == name));
+
+            describe_var(config, reference, declaration)
+        }
+        ReferenceType::Prompt(name) => {
+            let declaration = config
+                .and_then(|config| config.prompts.as_ref())
+                .and_then(|prompts| prompts.iter().find(|prompt| &prompt.name == name));
+
+            describe_prompt(reference, declaration)
+        }
+        ReferenceType::Secret(_) => format!("`{reference}` secret (value not shown)"),
+        ReferenceType::Provider(_) => format!("`{reference}` provider value"),
+        ReferenceType::Dependency(_) => format!("`{reference}` dependency value"),
+        ReferenceType::Unknown(_) => unreachable!("unknown references aren't matched"),
+    }
+}
+
+fn describe_var(
+    config: Option<&ParsedConfig>,
+    reference: &ReferenceType,
+    declaration: Option<&ParsedConfigVariable>,
+) -> String {
+    let mut contents = format!("`{reference}` variable");
+
+    let Some(declaration) = declaration else {
+        return contents;
+    };
+
+    if let Some(default) = &declaration.default {
+        contents.push_str(&format!("\n\nDefault: `{default}`"));
+    }
+
+    let mut values: Vec<(&String, &String)> = config
+        .and_then(|config| config.envs.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|(env, values)| Some((env, values.get(&declaration.name)?)))
+        .collect();
+
+    values.sort();
+
+    if !values.is_empty() {
+        contents.push_str("\n\n| Environment | Value |\n| --- | --- |");
+
+        for (env, value) in values {
+            contents.push_str(&format!("\n| `{env}` | `{value}` |"));
+        }
+    }
+
+    contents
+}
+
+fn describe_prompt(reference: &ReferenceType, declaration: Option<&ParsedConfigPrompt>) -> String {
+    let mut contents = format!("`{reference}` prompt");
+
+    let Some(declaration) = declaration else {
+        return contents;
+    };
+
+    if let Some(description) = &declaration.description {
+        contents.push_str(&format!("\n\n{description}"));
+    }
+
+    if let Some(default) = &declaration.default {
+        contents.push_str(&format!("\n\nDefault: `{default}`"));
+    }
+
+    contents
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{ast::Ast, parser::parse};
+
+    use super::{Hover, hover};
+
+    const SOURCE: &str = r#"```%config
+secrets = ["api_key"]
+
+[[vars]]
+name = "base_url"
+default = "http://localhost:3000"
+
+[[prompts]]
+name = "user_id"
+description = "Id of the user to get"
+default = "1"
+
+[envs.dev]
+base_url = "https://dev.example.com"
+
+[envs.prod]
+base_url = "https://example.com"
+```
+
+```%request
+GET {{:base_url}}/users/{{?user_id}} HTTP/1.1
+x-api-key: {{!api_key}}
+x-env: {{@env}}
+x-trimmed: {(trim(:base_url))}
+```
+"#;
+
+    fn hover_at(needle: &str, offset: usize) -> Option<Hover> {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+        let index = SOURCE.find(needle).unwrap() + offset;
+
+        hover(&reqfile, index)
+    }
+
+    fn span_of(needle: &str) -> std::ops::Range<usize> {
+        let start = SOURCE.find(needle).unwrap();
+
+        start..start + needle.len()
+    }
+
+    #[test]
+    fn hover_variable() {
+        assert_eq!(
+            Some(Hover {
+                contents: "`{{:base_url}}` variable\n\nDefault: `http://localhost:3000`\n\n| Environment | Value |\n| --- | --- |\n| `dev` | `https://dev.example.com` |\n| `prod` | `https://example.com` |".to_string(),
+                span: span_of("{{:base_url}}"),
+            }),
+            hover_at("{{:base_url}}", 4)
+        );
+    }
+
+    #[test]
+    fn hover_prompt() {
+        assert_eq!(
+            Some(Hover {
+                contents: "`{{?user_id}}` prompt\n\nId of the user to get\n\nDefault: `1`"
+                    .to_string(),
+                span: span_of("{{?user_id}}"),
+            }),
+            hover_at("{{?user_id}}", 0)
+        );
+    }
+
+    #[test]
+    fn hover_secret() {
+        assert_eq!(
+            Some(Hover {
+                contents: "`{{!api_key}}` secret (value not shown)".to_string(),
+                span: span_of("{{!api_key}}"),
+            }),
+            hover_at("{{!api_key}}", 11)
+        );
+    }
+
+    #[test]
+    fn hover_provider() {
+        assert_eq!(
+            Some("`{{@env}}` provider value".to_string()),
+            hover_at("{{@env}}", 3).map(|hover| hover.contents)
+        );
+    }
+
+    #[test]
+    fn hover_expression() {
+        assert_eq!(
+            Some(Hover {
+                contents: "`{(trim(:base_url))}` expression\n\nType: `String`".to_string(),
+                span: span_of("{(trim(:base_url))}"),
+            }),
+            hover_at("{(trim(:base_url))}", 8)
+        );
+    }
+
+    #[test]
+    fn hover_outside_of_references() {
+        assert_eq!(None, hover_at("/users/", 1));
+        assert_eq!(None, hover_at("{{:base_url}}", 13));
+    }
+}
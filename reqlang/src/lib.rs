@@ -1,16 +1,28 @@
 pub mod assert_response;
 pub mod ast;
+pub mod auth;
+pub mod completion;
+pub mod dependencies;
 pub mod diagnostics;
 pub mod errors;
 pub mod export;
 pub mod extract_codeblocks;
+pub mod extractor;
 pub mod fetch;
+pub mod hover;
+pub mod lint;
 pub mod parser;
 pub mod prelude;
+pub mod report;
+pub mod secrets;
 pub mod span;
 pub mod str_idxpos;
+pub mod symbols;
 pub mod templater;
+#[cfg(test)]
+mod test_utils;
 pub mod types;
+pub mod workspace;
 
 #[cfg(test)]
 mod tests {
@@ -23,8 +35,8 @@ mod tests {
         parser::parse,
         templater::template,
         types::{
-            ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequestFile,
-            ReferenceType, TemplatedRequestFile,
+            ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequest,
+            ParsedRequestFile, ReferenceType, TemplatedRequestFile,
             http::{HttpRequest, HttpResponse, HttpStatusCode},
         },
     };
@@ -72,29 +84,36 @@ mod tests {
 
         assert_eq!(
             Ok(ParsedRequestFile {
-                request: (
-                    HttpRequest {
-                        verb: "POST".into(),
-                        target: "/?query={{:query_value}}".to_string(),
-                        http_version: "1.1".into(),
-                        headers: vec![
-                            ("x-test".to_string(), "{{?test_value}}".to_string()),
-                            ("x-api-key".to_string(), "{{!api_key}}".to_string()),
-                        ],
-                        body: Some("[1, 2, 3]\n\n".to_string())
-                    },
-                    237..334
-                ),
-                response: Some((
-                    HttpResponse {
-                        http_version: "1.1".into(),
-                        status_code: HttpStatusCode::new(200),
-                        status_text: "OK".to_string(),
-                        headers: vec![],
-                        body: Some("{{?expected_response_body}}\n\n\n".to_string())
-                    },
-                    353..398
-                )),
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: "POST".into(),
+                            target: "/?query={{:query_value}}".to_string(),
+                            http_version: "1.1".into(),
+                            headers: vec![
+                                ("x-test".to_string(), "{{?test_value}}".to_string()),
+                                ("x-api-key".to_string(), "{{!api_key}}".to_string()),
+                            ],
+                            body: Some("[1, 2, 3]\n\n".to_string()),
+                            body_file: None,
+                        },
+                        237..334
+                    ),
+                    response: Some((
+                        HttpResponse {
+                            http_version: "1.1".into(),
+                            status_code: HttpStatusCode::new(200),
+                            status_text: "OK".to_string(),
+                            headers: vec![],
+                            body: Some("{{?expected_response_body}}\n\n\n".to_string()),
+                            binary_body: None,
+                            metrics: None
+                        },
+                        353..398
+                    ))
+                }],
+                selected: 0,
                 config: Some((
                     ParsedConfig {
                         vars: Some(vec![ParsedConfigVariable {
@@ -130,17 +149,23 @@ mod tests {
                             }
                         ]),
                         secrets: Some(vec!["api_key".to_string()]),
-                        auth: None
+                        auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None
                     },
                     12..219
                 )),
                 refs: vec![
-                    (ReferenceType::Variable("query_value".to_string()), 237..334),
-                    (ReferenceType::Prompt("test_value".to_string()), 237..334),
-                    (ReferenceType::Secret("api_key".to_string()), 237..334),
+                    (ReferenceType::Variable("query_value".to_string()), 250..266),
+                    (ReferenceType::Prompt("test_value".to_string()), 284..299),
+                    (ReferenceType::Secret("api_key".to_string()), 311..323),
                     (
                         ReferenceType::Prompt("expected_response_body".to_string()),
-                        353..398
+                        370..397
                     )
                 ],
                 exprs: vec![],
@@ -216,15 +241,20 @@ mod tests {
                         ("x-test".to_string(), "test_value_value".to_string()),
                         ("x-api-key".to_string(), "api_key_value".to_string()),
                     ],
-                    body: Some("[1, 2, 3]\n\n".to_string())
+                    body: Some("[1, 2, 3]\n\n".to_string()),
+                    body_file: None,
                 },
                 response: Some(HttpResponse {
                     http_version: "1.1".into(),
                     status_code: HttpStatusCode::new(200),
                     status_text: "OK".to_string(),
                     headers: vec![],
-                    body: Some("expected_response_body_value\n\n\n".to_string())
+                    body: Some("expected_response_body_value\n\n\n".to_string()),
+                    binary_body: None,
+                    metrics: None
                 }),
+                auth: None,
+                client: None,
             }),
             templated_reqfile
         );
@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+
+use crate::{
+    ast::Ast,
+    parser::{ConfigSpans, find_span},
+    span::Span,
+    types::{
+        ParsedConfig, ParsedRequestFile, ReferenceType,
+        http::HttpRequest,
+        lint::{Lint, LintLevel},
+    },
+};
+
+/// Environment names checked by [Lint::InsecureProdTarget]
+const PROD_ENVIRONMENT_NAMES: [&str; 2] = ["prod", "production"];
+
+/// A problem found by a [Lint] in a request file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub lint: Lint,
+    pub level: LintLevel,
+    pub message: String,
+    pub span: Span,
+}
+
+impl LintFinding {
+    /// If the finding should be treated as an error
+    pub fn is_denied(&self) -> bool {
+        self.level == LintLevel::Deny
+    }
+}
+
+/// Check a parsed request file for problems that don't stop it from being used
+///
+/// The level of each lint is taken from `levels` first, then the `[lints]`
+/// table in the config, then [Lint::default_level]. Allowed lints aren't checked.
+pub fn lint(
+    reqfile: &ParsedRequestFile,
+    source: &str,
+    levels: &HashMap<Lint, LintLevel>,
+) -> Vec<LintFinding> {
+    let config = reqfile.config.as_ref().map(|(config, _)| config);
+
+    let level = |lint: Lint| {
+        levels
+            .get(&lint)
+            .or_else(|| config.and_then(|config| config.lints.as_ref()?.get(&lint)))
+            .copied()
+            .unwrap_or(lint.default_level())
+    };
+
+    let mut findings: Vec<LintFinding> = vec![];
+
+    for lint in Lint::ALL {
+        let level = level(lint);
+
+        if level == LintLevel::Allow {
+            continue;
+        }
+
+        let found: Vec<(String, Span)> = match lint {
+            Lint::UnusedValue => unused_values(reqfile, source),
+            Lint::MissingContentType => missing_content_types(reqfile, source),
+            Lint::InsecureProdTarget => insecure_prod_targets(reqfile, source),
+            Lint::SecretInUrl => secrets_in_urls(reqfile, source),
+        };
+
+        findings.extend(found.into_iter().map(|(message, span)| LintFinding {
+            lint,
+            level,
+            message,
+            span,
+        }));
+    }
+
+    findings.sort_by_key(|finding| (finding.span.start, finding.span.end));
+
+    findings
+}
+
+fn unused_values(reqfile: &ParsedRequestFile, source: &str) -> Vec<(String, Span)> {
+    let Some((config, config_span)) = &reqfile.config else {
+        return vec![];
+    };
+
+    let config_spans = Ast::from(source)
+        .config()
+        .map(ConfigSpans::new)
+        .unwrap_or_default();
+
+    let field_span = |spans: &[(String, Span)], name: &str| {
+        find_span(spans, name).unwrap_or(config_span.clone())
+    };
+
+    let declared = config
+        .vars()
+        .into_iter()
+        .map(|name| {
+            (
+                ReferenceType::Variable(name.clone()),
+                field_span(&config_spans.vars, &name),
+            )
+        })
+        .chain(config.prompts().into_iter().map(|name| {
+            let span = field_span(&config_spans.prompts, &name);
+            (ReferenceType::Prompt(name), span)
+        }))
+        .chain(config.secrets().into_iter().map(|name| {
+            let span = field_span(&config_spans.secrets, &name);
+            (ReferenceType::Secret(name), span)
+        }))
+        .chain(config.dependency_values().into_iter().map(|name| {
+            let span = field_span(&config_spans.dependency_values, &name);
+            (ReferenceType::Dependency(name), span)
+        }));
+
+    declared
+        .filter(|(reference, _)| !reqfile.refs.iter().any(|(x, _)| x == reference))
+        .map(|(reference, span)| {
+            (
+                format!(
+                    "Value was declared but not used. Try adding the template reference {reference} to the request or response."
+                ),
+                span,
+            )
+        })
+        .collect()
+}
+
+/// The span of the request line (e.g. `GET / HTTP/1.1`) of each request
+fn request_lines<'a>(
+    reqfile: &'a ParsedRequestFile,
+    source: &str,
+) -> impl Iterator<Item = (&'a HttpRequest, Span)> {
+    let line_ends: Vec<Span> = reqfile
+        .requests
+        .iter()
+        .map(|request| {
+            let span = &request.request.1;
+            let text = source.get(span.clone()).unwrap_or_default();
+            let len = text.find('\n').unwrap_or(text.len());
+
+            span.start..span.start + text[..len].trim_end_matches('\r').len()
+        })
+        .collect();
+
+    reqfile
+        .requests
+        .iter()
+        .zip(line_ends)
+        .map(|(request, span)| (&request.request.0, span))
+}
+
+fn missing_content_types(reqfile: &ParsedRequestFile, source: &str) -> Vec<(String, Span)> {
+    request_lines(reqfile, source)
+        .filter(|(request, _)| {
+            request.body_file.is_some()
+                || request
+                    .body
+                    .as_ref()
+                    .is_some_and(|body| !body.trim().is_empty())
+        })
+        .filter(|(request, _)| {
+            !request
+                .headers
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        })
+        .map(|(_, span)| {
+            (
+                "Request has a body but no content-type header".to_string(),
+                span,
+            )
+        })
+        .collect()
+}
+
+fn insecure_prod_targets(reqfile: &ParsedRequestFile, source: &str) -> Vec<(String, Span)> {
+    let Some((config, _)) = &reqfile.config else {
+        return vec![];
+    };
+
+    let mut envs: Vec<&String> = config
+        .envs
+        .iter()
+        .flatten()
+        .map(|(env, _)| env)
+        .filter(|env| {
+            PROD_ENVIRONMENT_NAMES
+                .iter()
+                .any(|name| env.eq_ignore_ascii_case(name))
+        })
+        .collect();
+
+    envs.sort();
+
+    let mut findings = vec![];
+
+    for (request, span) in request_lines(reqfile, source) {
+        for env in &envs {
+            let target = target_in_env(config, env, &request.target);
+
+            if target.to_ascii_lowercase().starts_with("http://") {
+                findings.push((
+                    format!(
+                        "Request target '{target}' uses HTTP in the '{env}' environment. Try using HTTPS instead."
+                    ),
+                    span.clone(),
+                ));
+            }
+        }
+    }
+
+    findings
+}
+
+/// The request target with variables replaced by their values in the environment
+fn target_in_env(config: &ParsedConfig, env: &str, target: &str) -> String {
+    let env_values = config.envs.as_ref().and_then(|envs| envs.get(env));
+
+    config
+        .vars
+        .iter()
+        .flatten()
+        .fold(target.to_string(), |target, var| {
+            let value = env_values
+                .and_then(|values| values.get(&var.name))
+                .or(var.default.as_ref());
+
+            match value {
+                Some(value) => target.replace(&format!("{{{{:{}}}}}", var.name), value),
+                None => target,
+            }
+        })
+}
+
+fn secrets_in_urls(reqfile: &ParsedRequestFile, source: &str) -> Vec<(String, Span)> {
+    let request_lines: Vec<Span> = request_lines(reqfile, source)
+        .map(|(_, span)| span)
+        .collect();
+
+    reqfile
+        .refs
+        .iter()
+        .filter(|(reference, _)| matches!(reference, ReferenceType::Secret(_)))
+        .filter(|(_, span)| {
+            request_lines
+                .iter()
+                .any(|line| line.start <= span.start && span.end <= line.end)
+        })
+        .map(|(reference, span)| {
+            (
+                format!(
+                    "Secret {reference} is in the request target where it may be logged. Try sending it in a header instead."
+                ),
+                span.clone(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        parser::parse,
+        types::lint::{Lint, LintLevel},
+    };
+
+    use super::{LintFinding, lint};
+
+    fn lint_source(source: &str, levels: &HashMap<Lint, LintLevel>) -> Vec<LintFinding> {
+        let reqfile = parse(&Ast::from(source)).unwrap();
+
+        lint(&reqfile, source, levels)
+    }
+
+    fn span_of(source: &str, needle: &str) -> std::ops::Range<usize> {
+        let start = source.find(needle).unwrap();
+
+        start..start + needle.len()
+    }
+
+    #[test]
+    fn no_findings() {
+        let source = "```%config\nsecrets = [\"api_key\"]\n```\n\n```%request\nPOST https://example.com HTTP/1.1\ncontent-type: application/json\nx-api-key: {{!api_key}}\n\n{}\n```\n";
+
+        assert_eq!(
+            Vec::<LintFinding>::new(),
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn unused_variable() {
+        let source = "```%config\n[[vars]]\nname = \"base_url\"\n\n[envs.dev]\nbase_url = \"https://example.com\"\n```\n\n```%request\nGET https://example.com HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::UnusedValue,
+                level: LintLevel::Warn,
+                message: "Value was declared but not used. Try adding the template reference {{:base_url}} to the request or response.".to_string(),
+                span: 28..36,
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn unused_prompt() {
+        let source = "```%config\n[[prompts]]\nname = \"base_url\"\n```\n\n```%request\nGET http://example.com HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::UnusedValue,
+                level: LintLevel::Warn,
+                message: "Value was declared but not used. Try adding the template reference {{?base_url}} to the request or response.".to_string(),
+                span: 31..39,
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn unused_secret() {
+        let source = "```%config\nsecrets = [\"base_url\"]\n```\n\n```%request\nGET http://example.com HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::UnusedValue,
+                level: LintLevel::Warn,
+                message: "Value was declared but not used. Try adding the template reference {{!base_url}} to the request or response.".to_string(),
+                span: 23..31,
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn unused_variable_with_the_name_of_a_used_secret() {
+        let source = "```%config\nsecrets = [\"token\"]\n\n[[vars]]\nname = \"token\"\n\n[envs.dev]\ntoken = \"abc\"\n```\n\n```%request\nGET https://example.com HTTP/1.1\nauthorization: {{!token}}\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::UnusedValue,
+                level: LintLevel::Warn,
+                message: "Value was declared but not used. Try adding the template reference {{:token}} to the request or response.".to_string(),
+                span: 49..54,
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn unused_dependency_value() {
+        let source = "```%config\n[[depends]]\nname = \"login\"\npath = \"./login.reqlang\"\n\n[[depends.values]]\nname = \"token\"\njson = \"/access_token\"\n```\n\n```%request\nGET https://example.com HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::UnusedValue,
+                level: LintLevel::Warn,
+                message: "Value was declared but not used. Try adding the template reference {{>login.token}} to the request or response.".to_string(),
+                span: 91..96,
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn missing_content_type() {
+        let source = "```%request\nPOST https://example.com HTTP/1.1\n\n{\"name\": \"Ada\"}\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::MissingContentType,
+                level: LintLevel::Info,
+                message: "Request has a body but no content-type header".to_string(),
+                span: span_of(source, "POST https://example.com HTTP/1.1"),
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn insecure_prod_target() {
+        let source = "```%config\n[[vars]]\nname = \"base_url\"\n\n[envs.dev]\nbase_url = \"http://localhost:3000\"\n\n[envs.prod]\nbase_url = \"http://example.com\"\n```\n\n```%request\nGET {{:base_url}}/users HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::InsecureProdTarget,
+                level: LintLevel::Warn,
+                message: "Request target 'http://example.com/users' uses HTTP in the 'prod' environment. Try using HTTPS instead.".to_string(),
+                span: span_of(source, "GET {{:base_url}}/users HTTP/1.1"),
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn secure_prod_target() {
+        let source = "```%config\n[[vars]]\nname = \"base_url\"\ndefault = \"http://localhost:3000\"\n\n[envs.dev]\n\n[envs.prod]\nbase_url = \"https://example.com\"\n```\n\n```%request\nGET {{:base_url}}/users HTTP/1.1\n```\n";
+
+        assert_eq!(
+            Vec::<LintFinding>::new(),
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn secret_in_url() {
+        let source = "```%config\nsecrets = [\"api_key\"]\n```\n\n```%request\nGET https://example.com/?key={{!api_key}} HTTP/1.1\n```\n";
+
+        assert_eq!(
+            vec![LintFinding {
+                lint: Lint::SecretInUrl,
+                level: LintLevel::Warn,
+                message: "Secret {{!api_key}} is in the request target where it may be logged. Try sending it in a header instead.".to_string(),
+                span: span_of(source, "{{!api_key}}"),
+            }],
+            lint_source(source, &HashMap::new())
+        );
+    }
+
+    #[test]
+    fn lint_levels_from_config() {
+        let source = "```%config\nsecrets = [\"api_key\"]\n\n[lints]\nsecret_in_url = \"deny\"\nmissing_content_type = \"allow\"\n```\n\n```%request\nPOST https://example.com/?key={{!api_key}} HTTP/1.1\n\nname=Ada\n```\n";
+
+        let findings = lint_source(source, &HashMap::new());
+
+        assert_eq!(
+            vec![(Lint::SecretInUrl, LintLevel::Deny)],
+            findings
+                .iter()
+                .map(|finding| (finding.lint, finding.level))
+                .collect::<Vec<_>>()
+        );
+        assert!(findings[0].is_denied());
+    }
+
+    #[test]
+    fn lint_levels_override_config() {
+        let source = "```%config\nsecrets = [\"api_key\"]\n\n[lints]\nsecret_in_url = \"deny\"\n```\n\n```%request\nPOST https://example.com/?key={{!api_key}} HTTP/1.1\n\nname=Ada\n```\n";
+
+        let findings = lint_source(
+            source,
+            &HashMap::from([
+                (Lint::SecretInUrl, LintLevel::Allow),
+                (Lint::MissingContentType, LintLevel::Deny),
+            ]),
+        );
+
+        assert_eq!(
+            vec![(Lint::MissingContentType, LintLevel::Deny)],
+            findings
+                .iter()
+                .map(|finding| (finding.lint, finding.level))
+                .collect::<Vec<_>>()
+        );
+    }
+}
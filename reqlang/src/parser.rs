@@ -1,23 +1,31 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use regex::Regex;
+use serde::Deserialize;
 
 use crate::{
+    assert_response::ExpectedValue,
     ast::Ast,
-    errors::{ParseError, ReqlangError},
-    span::{NO_SPAN, Spanned},
+    errors::{ParseError, ReqlangError, ResolverError, WorkspaceError},
+    span::{NO_SPAN, Span, Spanned},
     types::{
-        ParsedConfig, ParsedRequestFile, ReferenceType,
-        http::{HttpRequest, HttpResponse},
+        ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequest, ParsedRequestFile,
+        ReferenceType,
+        http::{HttpRequest, HttpResponse, HttpVersion},
     },
+    workspace::Workspace,
 };
 
-pub const TEMPLATE_REFERENCE_PATTERN: &str = r"\{\{(.+)\}\}";
-pub const TEMPLATE_REFERENCE_PATTERN_INNER: &str = r"([:?!@]{1})([a-zA-Z][_a-zA-Z0-9.]*)";
+pub const TEMPLATE_REFERENCE_PATTERN: &str = r"\{\{(.+?)\}\}";
+pub const TEMPLATE_REFERENCE_PATTERN_INNER: &str = r"([:?!@>]{1})([a-zA-Z][_a-zA-Z0-9.]*)";
 
 pub const TEMPLATE_EXPR_REFERENCE_PATTERN: &str = r"\{\((.*)\)\}";
 
-static FORBIDDEN_REQUEST_HEADER_NAMES: &[&str] = &[
+pub(crate) static FORBIDDEN_REQUEST_HEADER_NAMES: &[&str] = &[
     "host",
     "accept-charset",
     "accept-encoding",
@@ -39,272 +47,864 @@ static FORBIDDEN_REQUEST_HEADER_NAMES: &[&str] = &[
     "via",
 ];
 
+/// Information about a request file from outside of its source
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseContext {
+    /// Path to the request file
+    pub path: Option<PathBuf>,
+    /// Declarations shared by a workspace
+    ///
+    /// See [ParsedConfig::merge_shared]
+    pub shared_config: Option<ParsedConfig>,
+    /// Name of the request to select when the request file has more than one
+    ///
+    /// Without a name the unnamed request is selected, or the first request if they're all named.
+    pub request: Option<String>,
+}
+
+impl ParseContext {
+    /// Context for the request file at `path`, sharing the declarations of its workspace
+    pub fn for_path(path: impl AsRef<Path>) -> Result<Self, WorkspaceError> {
+        let path = path.as_ref();
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            shared_config: Workspace::discover(path)?.map(|workspace| workspace.shared_config()),
+            request: None,
+        })
+    }
+}
+
 /// Parse [crate::ast::Ast] into a [ParsedRequestFile]
 pub fn parse(ast: &Ast) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
-    match ast.request() {
-        Some(request) => {
-            let mut parse_errors: Vec<Spanned<ReqlangError>> = vec![];
-
-            let response = ast.response().cloned();
-            let config = ast.config().cloned();
-
-            let request_refs = parse_references(request);
-            let response_refs = parse_references(&response.clone().unwrap_or_default());
-            let config_refs = parse_references(&config.clone().unwrap_or_default());
-            let mut refs: Vec<(ReferenceType, std::ops::Range<usize>)> = vec![];
-            refs.extend(request_refs);
-            refs.extend(response_refs);
-            refs.extend(config_refs);
-
-            // Extract expression references from the request, response, and config
-
-            let request_exprs = parse_expressions(request);
-            let response_exprs = parse_expressions(&response.clone().unwrap_or_default());
-            let config_exprs = parse_expressions(&config.clone().unwrap_or_default());
-            let mut exprs: Vec<Spanned<String>> = vec![];
-            exprs.extend(request_exprs);
-            exprs.extend(response_exprs);
-            exprs.extend(config_exprs);
-
-            // Extract template references from expression references
-
-            for (expr, expr_span) in exprs.iter() {
-                let expr_refs = parse_inner_references(&(expr.clone(), expr_span.clone()));
-                refs.extend(expr_refs);
+    parse_with_context(ast, &ParseContext::default())
+}
+
+/// Parse [crate::ast::Ast] into a [ParsedRequestFile] with [ParseContext]
+pub fn parse_with_context(
+    ast: &Ast,
+    context: &ParseContext,
+) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
+    let mut parse_errors: Vec<Spanned<ReqlangError>> = ast.validate();
+
+    let requests = ast.requests();
+
+    if requests.is_empty() {
+        parse_errors.push((ParseError::MissingRequest.into(), 0..0));
+
+        return Err(parse_errors);
+    }
+
+    let responses = ast.responses();
+    let config = ast.config().cloned();
+    let config_spans = config.as_ref().map(ConfigSpans::new).unwrap_or_default();
+
+    let selected_name = match ast.select_request(context.request.as_deref()) {
+        Some((name, _)) => name.cloned(),
+        None => {
+            let name = context.request.clone().unwrap_or_default();
+
+            parse_errors.push((ResolverError::InvalidRequestName(name).into(), NO_SPAN));
+
+            None
+        }
+    };
+
+    let blocks: Vec<&Spanned<String>> = requests
+        .iter()
+        .chain(responses.iter())
+        .map(|(_, block)| *block)
+        .chain(config.as_ref())
+        .collect();
+
+    let mut refs: Vec<(ReferenceType, std::ops::Range<usize>)> = vec![];
+
+    for block in blocks.iter() {
+        refs.extend(parse_references(block));
+    }
+
+    // Extract expression references from the requests, responses, and config
+
+    let mut exprs: Vec<Spanned<String>> = vec![];
+
+    for block in blocks.iter() {
+        exprs.extend(parse_expressions(block));
+    }
+
+    // Extract template references from expression references
+
+    for (expr, expr_span) in exprs.iter() {
+        // The expression starts after its opening `{(`
+        let expr_start = expr_span.start + 2;
+        let expr_refs =
+            parse_inner_references(&(expr.clone(), expr_start..expr_start + expr.len()));
+        refs.extend(expr_refs);
+    }
+
+    let mut parsed_responses: Vec<(Option<&String>, Spanned<HttpResponse>)> = vec![];
+
+    for (name, response) in responses.iter() {
+        let (response_text, response_span) = response;
+
+        for (line, line_span) in lines_with_spans(response_text, response_span) {
+            if let Err(message) = ExpectedValue::parse(line) {
+                parse_errors.push((
+                    ParseError::InvalidResponseMatcher(message).into(),
+                    line_span,
+                ));
             }
+        }
 
-            let request = match parse_request(request) {
-                Ok((request, span)) => {
-                    for key in request.headers.iter().map(|x| &x.0) {
-                        if FORBIDDEN_REQUEST_HEADER_NAMES.contains(&key.to_lowercase().as_str()) {
-                            parse_errors.push((
-                                ParseError::ForbiddenRequestHeaderNameError(key.to_lowercase())
-                                    .into(),
-                                span.clone(),
-                            ))
-                        }
-                    }
+        match parse_response(&Some((*response).clone())) {
+            Some(Ok(response)) => parsed_responses.push((*name, response)),
+            Some(Err(err)) => parse_errors.extend(err),
+            None => {}
+        }
+    }
 
-                    Some((request, span))
-                }
-                Err(err) => {
-                    parse_errors.extend(err);
-                    None
-                }
-            };
+    let mut parsed_requests: Vec<ParsedRequest> = vec![];
 
-            let response = match parse_response(&response) {
-                Some(Ok(response)) => Some(response),
-                Some(Err(err)) => {
-                    parse_errors.extend(err);
-                    None
-                }
-                None => None,
-            };
+    for (name, request_block) in requests.iter() {
+        match parse_request(request_block) {
+            Ok((mut request, span)) => {
+                request.split_body_file();
 
-            let config = match parse_config(&config) {
-                Some(Ok((config, config_span))) => Some((config, config_span)),
-                Some(Err(err)) => {
-                    parse_errors.extend(err);
-                    None
+                for key in request.headers.iter().map(|x| &x.0) {
+                    if FORBIDDEN_REQUEST_HEADER_NAMES.contains(&key.to_lowercase().as_str()) {
+                        parse_errors.push((
+                            ParseError::ForbiddenRequestHeaderNameError(key.to_lowercase()).into(),
+                            header_line_span(request_block, key).unwrap_or(span.clone()),
+                        ))
+                    }
                 }
-                None => None,
-            };
 
-            if let Some((config, config_span)) = &config {
-                let vars = config.vars();
-                let env_names = config.envs();
+                let mut body_files: Vec<String> = request.body_file.clone().into_iter().collect();
 
-                for var in vars.iter() {
-                    if env_names.is_empty() {
+                match request.multipart_parts() {
+                    Some(Ok(parts)) => body_files.extend(
+                        parts
+                            .iter()
+                            .filter_map(|part| part.file())
+                            .map(str::to_string),
+                    ),
+                    Some(Err(message)) => parse_errors.push((
+                        ParseError::InvalidMultipartPart(message).into(),
+                        span.clone(),
+                    )),
+                    None => {}
+                }
+
+                // Body files with template references are checked once they're templated
+                for path in body_files.iter().filter(|path| !path.contains("{{")) {
+                    if let Err(err) = fs::metadata(resolve_path(path, context.path.as_deref())) {
                         parse_errors.push((
-                            ParseError::VariableNotDefinedInAnyEnvironment(var.to_string()).into(),
-                            config_span.clone(),
+                            ParseError::InvalidBodyFile {
+                                path: path.to_string(),
+                                message: err.to_string(),
+                            }
+                            .into(),
+                            span.clone(),
                         ));
                     }
+                }
 
-                    let mut default_values = HashMap::new();
+                let response = parsed_responses
+                    .iter()
+                    .find(|(response_name, _)| response_name == name)
+                    .map(|(_, response)| response.clone());
 
-                    let default_values_pairs: Vec<(String, String)> = config
-                        .vars
-                        .clone()
-                        .unwrap_or_default()
-                        .iter()
-                        .filter(|x| x.default.is_some())
-                        .map(|x| (x.name.clone(), x.default.clone().unwrap_or_default()))
-                        .collect();
+                parsed_requests.push(ParsedRequest {
+                    name: name.cloned(),
+                    request: (request, span),
+                    response,
+                });
+            }
+            Err(err) => {
+                parse_errors.extend(err);
+            }
+        };
+    }
 
-                    for (key, value) in &default_values_pairs {
-                        default_values.insert(key.clone(), value.clone());
-                    }
+    let config = match config.as_ref().map(parse_config_toml) {
+        Some(Ok((config, config_span))) => Some((config, config_span)),
+        Some(Err(err)) => {
+            parse_errors.extend(err);
+            None
+        }
+        None => None,
+    };
 
-                    // Check that environments are defining the declared variables
-                    for env_name in env_names.iter() {
-                        match &config.env(env_name) {
-                            Some(env) => {
-                                if !env.contains_key(var) && !default_values.contains_key(var) {
-                                    parse_errors.push((
-                                        ParseError::VariableUndefinedInEnvironment(
-                                            var.clone(),
-                                            env_name.clone(),
-                                        )
-                                        .into(),
-                                        config_span.clone(),
-                                    ));
-                                }
-                            }
-                            None => todo!(),
-                        }
-                    }
+    // Values declared in the request file are kept, unlike imported or shared values
+    let (declared_vars, declared_prompts, declared_secrets) = config
+        .as_ref()
+        .map(|(config, _)| (config.vars(), config.prompts(), config.secrets()))
+        .unwrap_or_default();
+
+    // Merge the declarations from imported files
+    let config = config.map(|(mut config, config_span)| {
+        parse_errors.extend(import_config(
+            &mut config,
+            &config_span,
+            context.path.as_deref(),
+        ));
+
+        // Imported values that aren't referenced are dropped so they aren't required
+        let is_referenced = |ref_type: ReferenceType| refs.iter().any(|(x, _)| *x == ref_type);
+
+        if let Some(vars) = &mut config.vars {
+            vars.retain(|var| {
+                declared_vars.contains(&var.name)
+                    || is_referenced(ReferenceType::Variable(var.name.clone()))
+            });
+        }
+
+        if let Some(prompts) = &mut config.prompts {
+            prompts.retain(|prompt| {
+                declared_prompts.contains(&prompt.name)
+                    || is_referenced(ReferenceType::Prompt(prompt.name.clone()))
+            });
+        }
+
+        if let Some(secrets) = &mut config.secrets {
+            secrets.retain(|secret| {
+                declared_secrets.contains(secret)
+                    || is_referenced(ReferenceType::Secret(secret.clone()))
+            });
+        }
+
+        (config, config_span)
+    });
+
+    // Merge the shared declarations referenced by the request file
+    let config = match &context.shared_config {
+        Some(shared_config) => {
+            let ref_types: Vec<ReferenceType> =
+                refs.iter().map(|(ref_type, _)| ref_type.clone()).collect();
+
+            match config {
+                Some((mut config, config_span)) => {
+                    config.merge_shared(shared_config, &ref_types);
+
+                    Some((config, config_span))
+                }
+                None => {
+                    let mut config = ParsedConfig::default();
+
+                    config.merge_shared(shared_config, &ref_types);
+
+                    (config != ParsedConfig::default()).then_some((config, NO_SPAN))
                 }
             }
+        }
+        None => config,
+    };
 
-            // Validate template references are declared/defined vars, secrets, prompts, etc.
-            for (ref_type, span) in refs.iter() {
-                match ref_type {
-                    ReferenceType::Variable(name) => {
-                        if let Some((config, _)) = &config {
-                            if !config.vars().contains(name) {
-                                parse_errors.push((
-                                    ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                        ReferenceType::Variable(name.to_string()),
-                                    )),
-                                    span.clone(),
-                                ));
-                            }
-                        } else {
-                            parse_errors.push((
-                                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                    ReferenceType::Variable(name.to_string()),
-                                )),
-                                span.clone(),
-                            ));
-                        }
-                    }
-                    ReferenceType::Prompt(name) => {
-                        if let Some((config, _)) = &config {
-                            if !config.prompts().contains(name) {
-                                parse_errors.push((
-                                    ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                        ReferenceType::Prompt(name.to_string()),
-                                    )),
-                                    span.clone(),
-                                ));
-                            }
-                        } else {
-                            parse_errors.push((
-                                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                    ReferenceType::Prompt(name.to_string()),
-                                )),
-                                span.clone(),
-                            ));
-                        }
-                    }
-                    ReferenceType::Secret(name) => {
-                        if let Some((config, _)) = &config {
-                            if !config.secrets().contains(name) {
-                                parse_errors.push((
-                                    ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                        ReferenceType::Secret(name.to_string()),
-                                    )),
-                                    span.clone(),
-                                ));
-                            }
-                        } else {
+    if let Some((config, config_span)) = &config {
+        let vars = config.vars();
+        let env_names = config.envs();
+
+        // Declarations from imported or shared files are reported on the config
+        let field_span = |spans: &[Spanned<String>], name: &str| {
+            find_span(spans, name).unwrap_or(config_span.clone())
+        };
+
+        for var in vars.iter() {
+            if env_names.is_empty() {
+                parse_errors.push((
+                    ParseError::VariableNotDefinedInAnyEnvironment(var.to_string()).into(),
+                    field_span(&config_spans.vars, var),
+                ));
+            }
+
+            let mut default_values = HashMap::new();
+
+            let default_values_pairs: Vec<(String, String)> = config
+                .vars
+                .clone()
+                .unwrap_or_default()
+                .iter()
+                .filter(|x| x.default.is_some())
+                .map(|x| (x.name.clone(), x.default.clone().unwrap_or_default()))
+                .collect();
+
+            for (key, value) in &default_values_pairs {
+                default_values.insert(key.clone(), value.clone());
+            }
+
+            // Check that environments are defining the declared variables
+            for env_name in env_names.iter() {
+                match &config.env(env_name) {
+                    Some(env) => {
+                        if !env.contains_key(var) && !default_values.contains_key(var) {
                             parse_errors.push((
-                                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                                    ReferenceType::Secret(name.to_string()),
-                                )),
-                                span.clone(),
+                                ParseError::VariableUndefinedInEnvironment(
+                                    var.clone(),
+                                    env_name.clone(),
+                                )
+                                .into(),
+                                field_span(&config_spans.envs, env_name),
                             ));
                         }
                     }
-                    ReferenceType::Provider(_name) => {}
-                    ReferenceType::Unknown(_name) => {}
+                    None => todo!(),
                 }
             }
+        }
+    }
 
-            if let Some((ref config, ref span)) = config {
-                let ref_names: Vec<String> = refs
-                    .clone()
-                    .into_iter()
-                    .map(|(x, _)| match x {
-                        ReferenceType::Variable(name) => name,
-                        ReferenceType::Prompt(name) => name,
-                        ReferenceType::Secret(name) => name,
-                        ReferenceType::Provider(name) => name,
-                        ReferenceType::Unknown(name) => name,
-                    })
-                    .collect();
-
-                for var in &config.vars() {
-                    if !ref_names.contains(var) {
+    // Validate template references are declared/defined vars, secrets, prompts, etc.
+    for (ref_type, span) in refs.iter() {
+        match ref_type {
+            ReferenceType::Variable(name) => {
+                if let Some((config, _)) = &config {
+                    if !config.vars().contains(name) {
                         parse_errors.push((
-                            ReqlangError::ParseError(ParseError::UnusedValueError(
-                                ReferenceType::Variable(var.clone()),
+                            ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                                ReferenceType::Variable(name.to_string()),
                             )),
                             span.clone(),
-                        ))
+                        ));
                     }
+                } else {
+                    parse_errors.push((
+                        ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                            ReferenceType::Variable(name.to_string()),
+                        )),
+                        span.clone(),
+                    ));
                 }
-
-                for key in &config.prompts() {
-                    if !ref_names.contains(key) {
+            }
+            ReferenceType::Prompt(name) => {
+                if let Some((config, _)) = &config {
+                    if !config.prompts().contains(name) {
                         parse_errors.push((
-                            ReqlangError::ParseError(ParseError::UnusedValueError(
-                                ReferenceType::Prompt(key.clone()),
+                            ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                                ReferenceType::Prompt(name.to_string()),
                             )),
                             span.clone(),
-                        ))
+                        ));
                     }
+                } else {
+                    parse_errors.push((
+                        ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                            ReferenceType::Prompt(name.to_string()),
+                        )),
+                        span.clone(),
+                    ));
                 }
-
-                for secret in &config.secrets() {
-                    if !ref_names.contains(secret) {
+            }
+            ReferenceType::Secret(name) => {
+                if let Some((config, _)) = &config {
+                    if !config.secrets().contains(name) {
                         parse_errors.push((
-                            ReqlangError::ParseError(ParseError::UnusedValueError(
-                                ReferenceType::Secret(secret.clone()),
+                            ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                                ReferenceType::Secret(name.to_string()),
                             )),
                             span.clone(),
-                        ))
+                        ));
+                    }
+                } else {
+                    parse_errors.push((
+                        ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                            ReferenceType::Secret(name.to_string()),
+                        )),
+                        span.clone(),
+                    ));
+                }
+            }
+            ReferenceType::Dependency(name) => {
+                if let Some((config, _)) = &config {
+                    if !config.dependency_values().contains(name) {
+                        parse_errors.push((
+                            ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                                ReferenceType::Dependency(name.to_string()),
+                            )),
+                            span.clone(),
+                        ));
                     }
+                } else {
+                    parse_errors.push((
+                        ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                            ReferenceType::Dependency(name.to_string()),
+                        )),
+                        span.clone(),
+                    ));
                 }
             }
+            // Auth provider values are only defined if the auth is declared
+            ReferenceType::Provider(name) if name.starts_with("auth.") => {
+                let auth_provider_values = config
+                    .as_ref()
+                    .and_then(|(config, _)| config.auth.as_ref())
+                    .map(|auth| auth.provider_values())
+                    .unwrap_or_default();
+
+                if !auth_provider_values.contains(name) {
+                    parse_errors.push((
+                        ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                            ReferenceType::Provider(name.to_string()),
+                        )),
+                        span.clone(),
+                    ));
+                }
+            }
+            ReferenceType::Provider(_name) => {}
+            ReferenceType::Unknown(_name) => {}
+        }
+    }
 
-            if !parse_errors.is_empty() {
-                return Err(parse_errors);
+    if let Some((ref config, ref span)) = config {
+        let field_span =
+            |spans: &[Spanned<String>], name: &str| find_span(spans, name).unwrap_or(span.clone());
+
+        // Validate the rules for extracting dependency values
+        for dependency in config.depends.iter().flatten() {
+            for value in dependency.values.iter().flatten() {
+                if let Err(message) = value.extractor.validate() {
+                    parse_errors.push((
+                        ParseError::InvalidConfigError {
+                            message: format!(
+                                "Invalid rule for dependency value '{}.{}': {message}",
+                                dependency.name, value.name
+                            ),
+                        }
+                        .into(),
+                        field_span(
+                            &config_spans.dependency_values,
+                            &format!("{}.{}", dependency.name, value.name),
+                        ),
+                    ))
+                }
             }
+        }
 
-            Ok(ParsedRequestFile {
-                request: request.unwrap(),
-                response,
-                config,
-                refs,
-                exprs,
-                comments: ast.comments(),
-            })
+        // Validate the rules for capturing response values
+        let mut capture_names: Vec<&str> = vec![];
+
+        for (i, capture) in config.captures.iter().flatten().enumerate() {
+            // Captures are spanned in the order they're declared
+            let capture_span = config_spans
+                .captures
+                .get(i)
+                .map(|(_, span)| span.clone())
+                .unwrap_or(span.clone());
+
+            if capture_names.contains(&capture.name.as_str()) {
+                parse_errors.push((
+                    ParseError::InvalidConfigError {
+                        message: format!("Capture '{}' is declared more than once", capture.name),
+                    }
+                    .into(),
+                    capture_span.clone(),
+                ))
+            }
+
+            capture_names.push(&capture.name);
+
+            if let Err(message) = capture.extractor.validate() {
+                parse_errors.push((
+                    ParseError::InvalidConfigError {
+                        message: format!("Invalid rule for capture '{}': {message}", capture.name),
+                    }
+                    .into(),
+                    capture_span,
+                ))
+            }
+        }
+
+        // Basic and bearer auth both set the authorization header
+        if config
+            .auth
+            .as_ref()
+            .is_some_and(|auth| auth.basic.is_some() && auth.bearer.is_some())
+        {
+            parse_errors.push((
+                ParseError::InvalidConfigError {
+                    message: "Only one of [auth.basic] or [auth.bearer] can be declared"
+                        .to_string(),
+                }
+                .into(),
+                field_span(&config_spans.keys, "auth"),
+            ))
+        }
+
+        // Validate the client options, certificates with template references are checked once they're templated
+        if let Some(client) = &config.client {
+            for message in client.validate() {
+                parse_errors.push((
+                    ParseError::InvalidConfigError { message }.into(),
+                    field_span(&config_spans.keys, "client"),
+                ))
+            }
+
+            for path in client
+                .paths()
+                .into_iter()
+                .filter(|path| !path.contains("{{"))
+            {
+                if let Err(err) = fs::metadata(resolve_path(path, context.path.as_deref())) {
+                    parse_errors.push((
+                        ParseError::InvalidConfigError {
+                            message: format!("Unable to read '{path}': {err}"),
+                        }
+                        .into(),
+                        field_span(&config_spans.keys, "client"),
+                    ))
+                }
+            }
+        }
+
+        // Validate the required fields for the OAuth2 grant are defined
+        if let Some(oauth2) = config.auth.as_ref().and_then(|auth| auth.oauth2.as_ref()) {
+            for field in oauth2.missing_fields() {
+                parse_errors.push((
+                    ParseError::MissingOAuth2ConfigField(field).into(),
+                    field_span(&config_spans.keys, "auth"),
+                ))
+            }
         }
-        None => Err(vec![(ParseError::MissingRequest.into(), 0..0)]),
     }
+
+    if !parse_errors.is_empty() {
+        return Err(parse_errors);
+    }
+
+    let selected = parsed_requests
+        .iter()
+        .position(|request| request.name == selected_name)
+        .expect("should have parsed the selected request");
+
+    Ok(ParsedRequestFile {
+        requests: parsed_requests,
+        selected,
+        config,
+        refs,
+        exprs,
+        comments: ast.comments(),
+    })
 }
 
+/// Parse the config, merging the declarations from its `imports`
+///
+/// Imports are resolved relative to `path`, the request file, or the current
+/// directory if the path isn't known.
 pub fn parse_config(
     config: &Option<Spanned<String>>,
+    path: Option<&Path>,
 ) -> Option<Result<Spanned<ParsedConfig>, Vec<Spanned<ReqlangError>>>> {
-    config.as_ref().map(
-        |(config, span)| match toml::from_str::<ParsedConfig>(config) {
-            Ok(parsed_config) => Ok((parsed_config, span.clone())),
+    config.as_ref().map(|config| {
+        let (mut parsed_config, span) = parse_config_toml(config)?;
+
+        let errs = import_config(&mut parsed_config, &span, path);
+
+        if errs.is_empty() {
+            Ok((parsed_config, span))
+        } else {
+            Err(errs)
+        }
+    })
+}
+
+fn parse_config_toml(
+    (config, span): &Spanned<String>,
+) -> Result<Spanned<ParsedConfig>, Vec<Spanned<ReqlangError>>> {
+    match toml::from_str::<ParsedConfig>(config) {
+        Ok(parsed_config) => Ok((parsed_config, span.clone())),
+        Err(toml_err) => {
+            let toml_span = toml_err.span().unwrap_or(NO_SPAN);
+            let err = ReqlangError::ParseError(ParseError::InvalidConfigError {
+                message: toml_err.message().trim_end().to_string(),
+            });
+            let err_span = span.start + toml_span.start..span.start + toml_span.end;
+
+            Err(vec![(err, err_span)])
+        }
+    }
+}
+
+/// Resolve a path in a request file (e.g. a body file `< ./payload.json`) relative to the request file
+pub fn resolve_path(path: &str, reqfile_path: Option<&Path>) -> PathBuf {
+    let path = Path::new(path);
+    let path = path.strip_prefix(".").unwrap_or(path);
+
+    reqfile_path
+        .and_then(Path::parent)
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Declarations in an imported file
+///
+/// Spans are kept to report errors in the imported file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ImportedConfig {
+    vars: Option<Vec<toml::Spanned<ParsedConfigVariable>>>,
+    envs: Option<HashMap<String, HashMap<String, toml::Spanned<String>>>>,
+    prompts: Option<Vec<toml::Spanned<ParsedConfigPrompt>>>,
+    secrets: Option<Vec<String>>,
+}
+
+/// Merge the `vars`, `envs`, `prompts`, and `secrets` from the config's imports in to the config
+///
+/// Errors are reported on the config's span and include the location in the imported file.
+fn import_config(
+    config: &mut ParsedConfig,
+    span: &Span,
+    path: Option<&Path>,
+) -> Vec<Spanned<ReqlangError>> {
+    let mut errs: Vec<Spanned<ReqlangError>> = vec![];
+
+    let base_dir = path
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    for import in config.imports.clone().unwrap_or_default() {
+        let import_path = base_dir.join(&import).display().to_string();
+
+        let invalid_import = |message: String, import_span: Span| {
+            (
+                ParseError::InvalidImport {
+                    path: import_path.clone(),
+                    message,
+                    span: import_span,
+                }
+                .into(),
+                span.clone(),
+            )
+        };
+
+        let input = match fs::read_to_string(&import_path) {
+            Ok(input) => input,
+            Err(err) => {
+                errs.push(invalid_import(err.to_string(), NO_SPAN));
+                continue;
+            }
+        };
+
+        let imported = match toml::from_str::<ImportedConfig>(&input) {
+            Ok(imported) => imported,
             Err(toml_err) => {
-                let toml_span = toml_err.span().unwrap_or(NO_SPAN);
-                let err = ReqlangError::ParseError(ParseError::InvalidConfigError {
-                    message: toml_err.message().to_string(),
-                });
-                let err_span = span.start + toml_span.start..span.start + toml_span.end;
+                errs.push(invalid_import(
+                    toml_err.message().to_string(),
+                    toml_err.span().unwrap_or(NO_SPAN),
+                ));
+                continue;
+            }
+        };
 
-                Err(vec![(err, err_span)])
+        let mut conflict = |name: &str, import_span: Span| {
+            errs.push((
+                ParseError::ConflictingImport {
+                    name: name.to_string(),
+                    path: import_path.clone(),
+                    span: import_span,
+                }
+                .into(),
+                span.clone(),
+            ))
+        };
+
+        for var in imported.vars.into_iter().flatten() {
+            let vars = config.vars.get_or_insert_default();
+
+            match vars.iter().find(|x| x.name == var.get_ref().name) {
+                Some(existing) if existing != var.get_ref() => conflict(&existing.name, var.span()),
+                Some(_) => {}
+                None => vars.push(var.into_inner()),
             }
-        },
-    )
+        }
+
+        for (env_name, values) in imported.envs.into_iter().flatten() {
+            let env = config
+                .envs
+                .get_or_insert_default()
+                .entry(env_name)
+                .or_default();
+
+            for (name, value) in values {
+                match env.get(&name) {
+                    Some(existing) if existing != value.get_ref() => conflict(&name, value.span()),
+                    Some(_) => {}
+                    None => {
+                        env.insert(name, value.into_inner());
+                    }
+                }
+            }
+        }
+
+        for prompt in imported.prompts.into_iter().flatten() {
+            let prompts = config.prompts.get_or_insert_default();
+
+            match prompts.iter().find(|x| x.name == prompt.get_ref().name) {
+                Some(existing) if existing != prompt.get_ref() => {
+                    conflict(&existing.name, prompt.span())
+                }
+                Some(_) => {}
+                None => prompts.push(prompt.into_inner()),
+            }
+        }
+
+        for secret in imported.secrets.into_iter().flatten() {
+            let secrets = config.secrets.get_or_insert_default();
+
+            if !secrets.contains(&secret) {
+                secrets.push(secret);
+            }
+        }
+    }
+
+    errs
+}
+
+/// Names in a `[[vars]]`, `[[prompts]]`, or `[[captures]]` entry
+#[derive(Deserialize)]
+struct SpannedName {
+    name: toml::Spanned<String>,
+}
+
+#[derive(Deserialize)]
+struct SpannedDependency {
+    name: toml::Spanned<String>,
+    values: Option<Vec<SpannedName>>,
+}
+
+/// Variable keys and their values in an environment
+type SpannedEnv = HashMap<toml::Spanned<String>, toml::Value>;
+
+/// The names and keys in a config, other fields are ignored
+#[derive(Deserialize)]
+struct SpannedConfig {
+    vars: Option<Vec<SpannedName>>,
+    envs: Option<HashMap<toml::Spanned<String>, SpannedEnv>>,
+    prompts: Option<Vec<SpannedName>>,
+    secrets: Option<Vec<toml::Spanned<String>>>,
+    depends: Option<Vec<SpannedDependency>>,
+    captures: Option<Vec<SpannedName>>,
+}
+
+/// Spans of the names and keys in a config, in the request file
+///
+/// Used to report errors on the offending declaration instead of the whole
+/// config. Spans of quoted names don't include the quotes. A config that
+/// doesn't parse has no spans.
+#[derive(Debug, Default)]
+pub(crate) struct ConfigSpans {
+    /// Top level keys (e.g. `auth` in `[auth.basic]`)
+    pub keys: Vec<Spanned<String>>,
+    /// Names in `[[vars]]`
+    pub vars: Vec<Spanned<String>>,
+    /// Names in `[[prompts]]`
+    pub prompts: Vec<Spanned<String>>,
+    /// Names in `secrets`
+    pub secrets: Vec<Spanned<String>>,
+    /// Environment names in `[envs.*]` headers
+    pub envs: Vec<Spanned<String>>,
+    /// Variable keys in the environments
+    pub env_values: Vec<Spanned<String>>,
+    /// Dependency values (e.g. `login.token`) spanned to their names in `[[depends]]`
+    pub dependency_values: Vec<Spanned<String>>,
+    /// Names in `[[captures]]`
+    pub captures: Vec<Spanned<String>>,
+}
+
+impl ConfigSpans {
+    pub(crate) fn new((config, span): &Spanned<String>) -> Self {
+        let in_request_file = |name: &toml::Spanned<String>| {
+            let name_span = name.span();
+            let name_span = span.start + name_span.start..span.start + name_span.end;
+
+            // Skip the quotes of a quoted name
+            let name_span = if name_span.len() == name.get_ref().len() + 2 {
+                name_span.start + 1..name_span.end - 1
+            } else {
+                name_span
+            };
+
+            (name.get_ref().clone(), name_span)
+        };
+
+        let mut spans = ConfigSpans::default();
+
+        if let Ok(keys) = toml::from_str::<HashMap<toml::Spanned<String>, toml::Value>>(config) {
+            spans.keys = keys.keys().map(in_request_file).collect();
+        }
+
+        let Ok(config) = toml::from_str::<SpannedConfig>(config) else {
+            return spans;
+        };
+
+        let names = |entries: Option<Vec<SpannedName>>| -> Vec<Spanned<String>> {
+            entries
+                .iter()
+                .flatten()
+                .map(|entry| in_request_file(&entry.name))
+                .collect()
+        };
+
+        spans.vars = names(config.vars);
+        spans.prompts = names(config.prompts);
+        spans.captures = names(config.captures);
+        spans.secrets = config
+            .secrets
+            .iter()
+            .flatten()
+            .map(in_request_file)
+            .collect();
+
+        for (env_name, values) in config.envs.iter().flatten() {
+            spans.envs.push(in_request_file(env_name));
+            spans.env_values.extend(values.keys().map(in_request_file));
+        }
+
+        for dependency in config.depends.iter().flatten() {
+            for value in dependency.values.iter().flatten() {
+                let (name, span) = in_request_file(&value.name);
+
+                spans
+                    .dependency_values
+                    .push((format!("{}.{name}", dependency.name.get_ref()), span));
+            }
+        }
+
+        spans.envs.sort_by_key(|(_, span)| span.start);
+        spans.env_values.sort_by_key(|(_, span)| span.start);
+
+        spans
+    }
+}
+
+/// The span of the first `name` in `spans`
+pub(crate) fn find_span(spans: &[Spanned<String>], name: &str) -> Option<Span> {
+    spans
+        .iter()
+        .find(|(x, _)| x == name)
+        .map(|(_, span)| span.clone())
+}
+
+/// The lines in a block, spanned in the request file without their line endings
+fn lines_with_spans<'a>(text: &'a str, span: &Span) -> impl Iterator<Item = (&'a str, Span)> + 'a {
+    let start = span.start;
+
+    text.split('\n').scan(start, |line_start, line| {
+        let line_span = *line_start..*line_start + line.trim_end_matches('\r').len();
+        *line_start += line.len() + 1;
+
+        Some((line.trim_end_matches('\r'), line_span))
+    })
+}
+
+/// The span of the line declaring the header `name` in a request block
+fn header_line_span((request, span): &Spanned<String>, name: &str) -> Option<Span> {
+    lines_with_spans(request, span)
+        .skip(1)
+        .take_while(|(line, _)| !line.trim().is_empty())
+        .find(|(line, _)| {
+            line.split_once(':')
+                .is_some_and(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        })
+        .map(|(_, span)| span)
 }
 
 /// Extract template references from a string
@@ -313,38 +913,53 @@ pub fn parse_references((input, span): &Spanned<String>) -> Vec<Spanned<Referenc
 
     let outer_re = Regex::new(TEMPLATE_REFERENCE_PATTERN).unwrap();
     let inner_re = Regex::new(TEMPLATE_REFERENCE_PATTERN_INNER).unwrap();
-    for (_, [inner]) in outer_re.captures_iter(input).map(|cap| cap.extract()) {
+    for captures in outer_re.captures_iter(input) {
+        let (outer, inner) = (captures.get(0).unwrap(), &captures[1]);
+
+        // Response matchers (e.g. `{{*}}` and `{{/pattern/}}`) aren't references
+        if inner.starts_with(['*', '/']) {
+            continue;
+        }
+
+        // Each reference is spanned from its opening to its closing braces
+        let ref_span = span.start + outer.start()..span.start + outer.end();
+
         for (_, [prefix, name]) in inner_re.captures_iter(inner).map(|cap| cap.extract()) {
-            captured_refs.push(match prefix {
-                ":" => (ReferenceType::Variable(name.to_string()), span.to_owned()),
-                "?" => (ReferenceType::Prompt(name.to_string()), span.to_owned()),
-                "!" => (ReferenceType::Secret(name.to_string()), span.to_owned()),
-                "@" => (ReferenceType::Provider(name.to_string()), span.to_owned()),
-                _ => (ReferenceType::Unknown(name.to_string()), span.to_owned()),
-            });
+            captured_refs.push((reference_type(prefix, name), ref_span.clone()));
         }
     }
 
     captured_refs
 }
 
+/// Extract the references (e.g. `:var`) from an expression, spanned in the input
 pub fn parse_inner_references((input, span): &Spanned<String>) -> Vec<Spanned<ReferenceType>> {
     let mut captured_refs: Vec<Spanned<ReferenceType>> = vec![];
 
     let inner_re = Regex::new(TEMPLATE_REFERENCE_PATTERN_INNER).unwrap();
-    for (_, [prefix, name]) in inner_re.captures_iter(input).map(|cap| cap.extract()) {
-        captured_refs.push(match prefix {
-            ":" => (ReferenceType::Variable(name.to_string()), span.to_owned()),
-            "?" => (ReferenceType::Prompt(name.to_string()), span.to_owned()),
-            "!" => (ReferenceType::Secret(name.to_string()), span.to_owned()),
-            "@" => (ReferenceType::Provider(name.to_string()), span.to_owned()),
-            _ => (ReferenceType::Unknown(name.to_string()), span.to_owned()),
-        });
+    for captures in inner_re.captures_iter(input) {
+        let whole = captures.get(0).unwrap();
+
+        captured_refs.push((
+            reference_type(&captures[1], &captures[2]),
+            span.start + whole.start()..span.start + whole.end(),
+        ));
     }
 
     captured_refs
 }
 
+fn reference_type(prefix: &str, name: &str) -> ReferenceType {
+    match prefix {
+        ":" => ReferenceType::Variable(name.to_string()),
+        "?" => ReferenceType::Prompt(name.to_string()),
+        "!" => ReferenceType::Secret(name.to_string()),
+        "@" => ReferenceType::Provider(name.to_string()),
+        ">" => ReferenceType::Dependency(name.to_string()),
+        _ => ReferenceType::Unknown(name.to_string()),
+    }
+}
+
 /// Extract template references from a string
 pub fn parse_expressions((input, span): &Spanned<String>) -> Vec<Spanned<String>> {
     let mut captured_exprs: Vec<Spanned<String>> = vec![];
@@ -352,14 +967,60 @@ pub fn parse_expressions((input, span): &Spanned<String>) -> Vec<Spanned<String>
     {
         let re = Regex::new(TEMPLATE_EXPR_REFERENCE_PATTERN).unwrap();
 
-        for (_, [expr]) in re.captures_iter(input).map(|cap| cap.extract()) {
-            captured_exprs.push((expr.to_string(), span.clone()));
+        // Each expression is spanned from its opening to its closing braces
+        for captures in re.captures_iter(input) {
+            let whole = captures.get(0).unwrap();
+
+            captured_exprs.push((
+                captures[1].to_string(),
+                span.start + whole.start()..span.start + whole.end(),
+            ));
         }
     };
 
     captured_exprs
 }
 
+/// Versions written in a start line that are parsed as [HttpVersion::two]
+const HTTP2_VERSIONS: [&str; 2] = ["HTTP/2", "HTTP/2.0"];
+
+/// Swap an HTTP/2 version in the start line of a message for `HTTP/1.1`, returning if it was swapped
+///
+/// The message can then be parsed by httparse, which only supports HTTP/1.x.
+fn swap_http2_version(message: &str, is_request: bool) -> (String, bool) {
+    let (start_line, rest) = match message.split_once('\n') {
+        Some((start_line, rest)) => (start_line, Some(rest)),
+        None => (message, None),
+    };
+
+    let swapped_start_line = if is_request {
+        start_line
+            .rsplit_once(' ')
+            .filter(|(_, version)| HTTP2_VERSIONS.contains(&version.trim_end()))
+            .map(|(request_line, _)| format!("{request_line} HTTP/1.1"))
+    } else {
+        start_line
+            .split_once(' ')
+            .filter(|(version, _)| HTTP2_VERSIONS.contains(version))
+            .map(|(_, status)| format!("HTTP/1.1 {status}"))
+    };
+
+    match (swapped_start_line, rest) {
+        (Some(start_line), Some(rest)) => (format!("{start_line}\n{rest}"), true),
+        (Some(start_line), None) => (start_line, true),
+        (None, _) => (message.to_string(), false),
+    }
+}
+
+/// The [HttpVersion] of a message parsed by httparse
+fn parsed_http_version(minor_version: u8, is_http2: bool) -> HttpVersion {
+    if is_http2 {
+        HttpVersion::two()
+    } else {
+        format!("1.{minor_version}").into()
+    }
+}
+
 pub fn parse_request(
     (request, span): &Spanned<String>,
 ) -> Result<Spanned<HttpRequest>, Vec<Spanned<ReqlangError>>> {
@@ -368,6 +1029,7 @@ pub fn parse_request(
     let mut headers = [httparse::EMPTY_HEADER; 64];
     let mut req = httparse::Request::new(&mut headers);
 
+    let (request, is_http2) = swap_http2_version(request, true);
     let request = format!("{request}\n\n");
     let parse_result = req.parse(request.as_bytes());
 
@@ -419,9 +1081,10 @@ pub fn parse_request(
         HttpRequest {
             verb: req.method.unwrap().into(),
             target: req.path.unwrap().to_string(),
-            http_version: format!("1.{}", req.version.unwrap()).into(),
+            http_version: parsed_http_version(req.version.unwrap(), is_http2),
             headers: mapped_headers,
             body: Some(body.to_string()),
+            body_file: None,
         },
         span.clone(),
     ))
@@ -440,6 +1103,7 @@ pub fn parse_response(
         None => return None,
     };
 
+    let (response, is_http2) = swap_http2_version(response, false);
     let response = format!("{response}\n\n");
 
     let parse_result = res.parse(response.as_bytes());
@@ -474,7 +1138,7 @@ pub fn parse_response(
 
                 Some(Ok((
                     HttpResponse {
-                        http_version: format!("1.{}", res.version.unwrap()).into(),
+                        http_version: parsed_http_version(res.version.unwrap(), is_http2),
                         status_code: res
                             .code
                             .unwrap()
@@ -484,6 +1148,8 @@ pub fn parse_response(
                         status_text: res.reason.unwrap().to_string(),
                         headers: mapped_headers,
                         body: Some(body.to_string()),
+                        binary_body: None,
+                        metrics: None,
                     },
                     span.clone(),
                 )))
@@ -553,7 +1219,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::UndefinedReferenceError(
                     ReferenceType::Variable("value".to_string())
                 )),
-                13..44
+                34..44
             )])
         );
 
@@ -571,7 +1237,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::UndefinedReferenceError(
                     ReferenceType::Prompt("value".to_string())
                 )),
-                13..44
+                34..44
             )])
         );
 
@@ -589,7 +1255,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::UndefinedReferenceError(
                     ReferenceType::Secret("value".to_string())
                 )),
-                13..44
+                34..44
             )])
         );
 
@@ -611,7 +1277,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::UndefinedReferenceError(
                     ReferenceType::Variable("value".to_string())
                 )),
-                46..78
+                68..78
             )])
         );
 
@@ -633,7 +1299,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::UndefinedReferenceError(
                     ReferenceType::Prompt("value".to_string())
                 )),
-                46..78
+                68..78
             )])
         );
 
@@ -642,92 +1308,274 @@ mod test {
             textwrap::dedent(
                 "
                 ```%request
-                GET / HTTP/1.1
+                GET / HTTP/1.1
+                ```
+
+                ```%response
+                HTTP/1.1 200 OK
+                test: {{!value}}
+                ```
+                "
+            ),
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                    ReferenceType::Secret("value".to_string())
+                )),
+                68..78
+            )])
+        );
+
+        // Unused Config Data
+
+        parser_test!(
+            unused_variable,
+            textwrap::dedent(
+                r#"
+                ```%config
+                [[vars]]
+                name = "base_url"
+                ```
+
+                ```%request
+                GET http://example.com HTTP/1.1
+                ```
+                "#
+            ),
+            Err(vec![(
+                ParseError::VariableNotDefinedInAnyEnvironment("base_url".to_string()).into(),
+                29..37
+            )])
+        );
+
+        // Dependencies
+
+        parser_test!(
+            reference_undefined_dependency_value,
+            textwrap::dedent(
+                r#"
+                ```%config
+                [[depends]]
+                name = "login"
+                path = "./login.reqlang"
+                ```
+
+                ```%request
+                GET https://example.com HTTP/1.1
+                authorization: Bearer {{>login.token}}
+                ```
+                "#
+            ),
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                    ReferenceType::Dependency("login.token".to_string())
+                )),
+                136..152
+            )])
+        );
+
+        parser_test!(
+            invalid_dependency_value_regex,
+            textwrap::dedent(
+                r#"
+                ```%config
+                [[depends]]
+                name = "login"
+                path = "./login.reqlang"
+
+                [[depends.values]]
+                name = "token"
+                regex = "token=("
+                ```
+
+                ```%request
+                GET https://example.com HTTP/1.1
+                authorization: Bearer {{>login.token}}
+                ```
+                "#
+            ),
+            Err(vec![(
+                ParseError::InvalidConfigError {
+                    message: "Invalid rule for dependency value 'login.token': regex parse error:\n    token=(\n          ^\nerror: unclosed group".to_string()
+                }
+                .into(),
+                92..97
+            )])
+        );
+
+        // Captures
+
+        parser_test!(
+            invalid_capture_rule,
+            textwrap::dedent(
+                r#"
+                ```%config
+                [[captures]]
+                name = "token"
+                status = "reason"
+                ```
+
+                ```%request
+                GET https://example.com HTTP/1.1
+                ```
+                "#
+            ),
+            Err(vec![(
+                ParseError::InvalidConfigError {
+                    message: "Invalid rule for capture 'token': Status must be either 'code' or 'text': 'reason'".to_string()
+                }
+                .into(),
+                33..38
+            )])
+        );
+
+        parser_test!(
+            duplicate_capture_name,
+            textwrap::dedent(
+                r#"
+                ```%config
+                [[captures]]
+                name = "token"
+                json = "/token"
+
+                [[captures]]
+                name = "token"
+                header = "x-token"
+                ```
+
+                ```%request
+                GET https://example.com HTTP/1.1
+                ```
+                "#
+            ),
+            Err(vec![(
+                ParseError::InvalidConfigError {
+                    message: "Capture 'token' is declared more than once".to_string()
+                }
+                .into(),
+                78..83
+            )])
+        );
+
+        parser_test!(
+            invalid_response_regex_matcher,
+            textwrap::dedent(
+                r#"
+                ```%request
+                GET https://example.com HTTP/1.1
                 ```
 
                 ```%response
                 HTTP/1.1 200 OK
-                test: {{!value}}
+                x-request-id: {{/[0-9+/}}
                 ```
-                "
+                "#
             ),
             Err(vec![(
-                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
-                    ReferenceType::Secret("value".to_string())
-                )),
-                46..78
+                ParseError::InvalidResponseMatcher(
+                    "Invalid regex matcher '/[0-9+/': regex parse error:\n    [0-9+\n    ^\nerror: unclosed character class".to_string()
+                )
+                .into(),
+                80..105
             )])
         );
 
-        // Unused Config Data
-
         parser_test!(
-            unused_variable,
+            oauth2_missing_required_fields,
             textwrap::dedent(
                 r#"
                 ```%config
-                [[vars]]
-                name = "base_url"
+                [auth.oauth2]
+                client_secret = "secret"
                 ```
 
                 ```%request
-                GET http://example.com HTTP/1.1
+                GET https://example.com HTTP/1.1
                 ```
                 "#
             ),
             Err(vec![
                 (
-                    ParseError::VariableNotDefinedInAnyEnvironment("base_url".to_string()).into(),
-                    12..38
+                    ParseError::MissingOAuth2ConfigField("access_token_url".to_string()).into(),
+                    13..17
                 ),
                 (
-                    ParseError::UnusedValueError(ReferenceType::Variable("base_url".to_string()))
-                        .into(),
-                    12..38
+                    ParseError::MissingOAuth2ConfigField("client_id".to_string()).into(),
+                    13..17
                 )
             ])
         );
 
         parser_test!(
-            unused_prompt,
+            oauth2_password_grant_missing_fields,
             textwrap::dedent(
-                "
+                r#"
                 ```%config
-                [[prompts]]
-                name = \"base_url\"
+                [auth.oauth2]
+                grant = "password"
+                access_token_url = "https://example.com/token"
+                client_id = "reqlang"
                 ```
 
                 ```%request
-                GET http://example.com HTTP/1.1
+                GET https://example.com HTTP/1.1
                 ```
-                "
+                "#
+            ),
+            Err(vec![
+                (
+                    ParseError::MissingOAuth2ConfigField("username".to_string()).into(),
+                    13..17
+                ),
+                (
+                    ParseError::MissingOAuth2ConfigField("password".to_string()).into(),
+                    13..17
+                )
+            ])
+        );
+
+        parser_test!(
+            reference_undefined_oauth2_access_token,
+            textwrap::dedent(
+                r#"
+                ```%request
+                GET https://example.com HTTP/1.1
+                authorization: Bearer {{@auth.oauth2.access_token}}
+                ```
+                "#
             ),
             Err(vec![(
-                ReqlangError::ParseError(ParseError::UnusedValueError(ReferenceType::Prompt(
-                    "base_url".to_string()
-                ))),
-                12..41
+                ParseError::UndefinedReferenceError(ReferenceType::Provider(
+                    "auth.oauth2.access_token".to_string()
+                ))
+                .into(),
+                68..97
             )])
         );
 
         parser_test!(
-            unused_secret,
+            basic_and_bearer_auth,
             textwrap::dedent(
-                "
+                r#"
                 ```%config
-                secrets = [\"base_url\"]
+                [auth.basic]
+                username = "user"
+
+                [auth.bearer]
+                token = "token"
                 ```
 
                 ```%request
-                GET http://example.com HTTP/1.1
+                GET https://example.com HTTP/1.1
                 ```
-                "
+                "#
             ),
             Err(vec![(
-                ReqlangError::ParseError(ParseError::UnusedValueError(ReferenceType::Secret(
-                    "base_url".to_string()
-                ))),
-                12..34
+                ParseError::InvalidConfigError {
+                    message: "Only one of [auth.basic] or [auth.bearer] can be declared"
+                        .to_string()
+                }
+                .into(),
+                13..17
             )])
         );
 
@@ -747,7 +1595,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "host".to_string()
                 )),
-                13..45
+                28..45
             )])
         );
 
@@ -765,7 +1613,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "host".to_string()
                 )),
-                13..45
+                28..45
             )])
         );
 
@@ -783,7 +1631,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "host".to_string()
                 )),
-                13..45
+                28..45
             )])
         );
 
@@ -801,7 +1649,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "accept-charset".to_string()
                 )),
-                13..68
+                47..68
             )])
         );
 
@@ -819,7 +1667,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "accept-encoding".to_string()
                 )),
-                13..69
+                47..69
             )])
         );
 
@@ -837,7 +1685,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "access-control-request-headers".to_string()
                 )),
-                13..84
+                47..84
             )])
         );
 
@@ -855,7 +1703,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "access-control-request-method".to_string()
                 )),
-                13..83
+                47..83
             )])
         );
 
@@ -873,7 +1721,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "connection".to_string()
                 )),
-                13..64
+                47..64
             )])
         );
 
@@ -891,7 +1739,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "content-length".to_string()
                 )),
-                13..68
+                47..68
             )])
         );
 
@@ -909,7 +1757,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "cookie".to_string()
                 )),
-                13..60
+                47..60
             )])
         );
 
@@ -927,7 +1775,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "date".to_string()
                 )),
-                13..58
+                47..58
             )])
         );
 
@@ -945,7 +1793,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "dnt".to_string()
                 )),
-                13..57
+                47..57
             )])
         );
 
@@ -963,7 +1811,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "expect".to_string()
                 )),
-                13..60
+                47..60
             )])
         );
 
@@ -981,7 +1829,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "keep-alive".to_string()
                 )),
-                13..64
+                47..64
             )])
         );
 
@@ -999,7 +1847,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "origin".to_string()
                 )),
-                13..60
+                47..60
             )])
         );
 
@@ -1017,7 +1865,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "permission-policy".to_string()
                 )),
-                13..71
+                47..71
             )])
         );
 
@@ -1035,7 +1883,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "te".to_string()
                 )),
-                13..56
+                47..56
             )])
         );
 
@@ -1053,7 +1901,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "trailer".to_string()
                 )),
-                13..61
+                47..61
             )])
         );
 
@@ -1071,7 +1919,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "transfer-encoding".to_string()
                 )),
-                13..71
+                47..71
             )])
         );
 
@@ -1089,7 +1937,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "upgrade".to_string()
                 )),
-                13..61
+                47..61
             )])
         );
 
@@ -1107,7 +1955,7 @@ mod test {
                 ReqlangError::ParseError(ParseError::ForbiddenRequestHeaderNameError(
                     "via".to_string()
                 )),
-                13..57
+                47..57
             )])
         );
 
@@ -1163,8 +2011,8 @@ mod test {
         use std::collections::HashMap;
 
         use crate::types::{
-            ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequestFile,
-            ReferenceType,
+            ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequest,
+            ParsedRequestFile, ReferenceType,
             http::{HttpRequest, HttpResponse, HttpStatusCode, HttpVerb, HttpVersion},
         };
 
@@ -1179,17 +2027,22 @@ mod test {
             ),
             Ok(ParsedRequestFile {
                 config: None,
-                request: (
-                    HttpRequest {
-                        verb: HttpVerb::get(),
-                        target: "https://example.com/".to_string(),
-                        http_version: "1.1".into(),
-                        headers: vec![],
-                        body: Some("".to_string())
-                    },
-                    13..46
-                ),
-                response: None,
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: HttpVerb::get(),
+                            target: "https://example.com/".to_string(),
+                            http_version: "1.1".into(),
+                            headers: vec![],
+                            body: Some("".to_string()),
+                            body_file: None,
+                        },
+                        13..46
+                    ),
+                    response: None
+                }],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![("\n".to_string(), 0..1,)],
@@ -1211,26 +2064,33 @@ mod test {
             ),
             Ok(ParsedRequestFile {
                 config: None,
-                request: (
-                    HttpRequest {
-                        verb: HttpVerb::get(),
-                        target: "http://example.com".to_string(),
-                        http_version: "1.1".into(),
-                        headers: vec![],
-                        body: Some("".to_string())
-                    },
-                    13..44
-                ),
-                response: Some((
-                    HttpResponse {
-                        http_version: HttpVersion::one_point_one(),
-                        status_code: HttpStatusCode::new(200),
-                        status_text: "OK".to_owned(),
-                        headers: vec![],
-                        body: Some("".to_string())
-                    },
-                    63..78
-                )),
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: HttpVerb::get(),
+                            target: "http://example.com".to_string(),
+                            http_version: "1.1".into(),
+                            headers: vec![],
+                            body: Some("".to_string()),
+                            body_file: None,
+                        },
+                        13..44
+                    ),
+                    response: Some((
+                        HttpResponse {
+                            http_version: HttpVersion::one_point_one(),
+                            status_code: HttpStatusCode::new(200),
+                            status_text: "OK".to_owned(),
+                            headers: vec![],
+                            body: Some("".to_string()),
+                            binary_body: None,
+                            metrics: None
+                        },
+                        63..78
+                    ))
+                }],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![("\n".to_string(), 0..1), ("\n\n".to_string(), 48..50),],
@@ -1280,24 +2140,35 @@ mod test {
                         ),])),
                         prompts: None,
                         secrets: None,
-                        auth: None
+                        auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None
                     },
                     12..99
                 )),
-                request: (
-                    HttpRequest {
-                        verb: HttpVerb::get(),
-                        target: "http://example.com?value={{:bar}}".to_string(),
-                        http_version: "1.1".into(),
-                        headers: vec![],
-                        body: Some("".to_string())
-                    },
-                    117..163
-                ),
-                response: None,
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: HttpVerb::get(),
+                            target: "http://example.com?value={{:bar}}".to_string(),
+                            http_version: "1.1".into(),
+                            headers: vec![],
+                            body: Some("".to_string()),
+                            body_file: None,
+                        },
+                        117..163
+                    ),
+                    response: None
+                }],
+                selected: 0,
                 refs: vec![
-                    (ReferenceType::Variable("bar".to_string()), 117..163),
-                    (ReferenceType::Variable("foo".to_string()), 12..99),
+                    (ReferenceType::Variable("bar".to_string()), 146..154),
+                    (ReferenceType::Variable("foo".to_string()), 90..98),
                 ],
                 exprs: vec![],
                 comments: vec![("\n".to_string(), 0..1), ("\n\n".to_string(), 103..105),],
@@ -1345,30 +2216,37 @@ mod test {
                 "#
             ),
             Ok(ParsedRequestFile {
-                request: (
-                    HttpRequest {
-                        verb: HttpVerb::post(),
-                        target: "/?query={{:query_value}}".to_string(),
-                        http_version: "1.1".into(),
-                        headers: vec![
-                            ("x-test".to_string(), "{{?test_value}}".to_string()),
-                            ("x-api-key".to_string(), "{{!api_key}}".to_string()),
-                            ("x-provider".to_string(), "{{@provider}}".to_string()),
-                        ],
-                        body: Some("[1, 2, 3]\n\n".to_string())
-                    },
-                    238..361
-                ),
-                response: Some((
-                    HttpResponse {
-                        http_version: "1.1".into(),
-                        status_code: HttpStatusCode::new(200),
-                        status_text: "OK".to_string(),
-                        headers: vec![],
-                        body: Some("{{?expected_response_body}}\n\n\n".to_string())
-                    },
-                    380..425
-                )),
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: HttpVerb::post(),
+                            target: "/?query={{:query_value}}".to_string(),
+                            http_version: "1.1".into(),
+                            headers: vec![
+                                ("x-test".to_string(), "{{?test_value}}".to_string()),
+                                ("x-api-key".to_string(), "{{!api_key}}".to_string()),
+                                ("x-provider".to_string(), "{{@provider}}".to_string()),
+                            ],
+                            body: Some("[1, 2, 3]\n\n".to_string()),
+                            body_file: None,
+                        },
+                        238..361
+                    ),
+                    response: Some((
+                        HttpResponse {
+                            http_version: "1.1".into(),
+                            status_code: HttpStatusCode::new(200),
+                            status_text: "OK".to_string(),
+                            headers: vec![],
+                            body: Some("{{?expected_response_body}}\n\n\n".to_string()),
+                            binary_body: None,
+                            metrics: None
+                        },
+                        380..425
+                    ))
+                }],
+                selected: 0,
                 config: Some((
                     ParsedConfig {
                         vars: Some(vec![ParsedConfigVariable {
@@ -1404,18 +2282,24 @@ mod test {
                             }
                         ]),
                         secrets: Some(vec!["api_key".to_string()]),
-                        auth: None
+                        auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None
                     },
                     12..220
                 )),
                 refs: vec![
-                    (ReferenceType::Variable("query_value".to_string()), 238..361),
-                    (ReferenceType::Prompt("test_value".to_string()), 238..361),
-                    (ReferenceType::Secret("api_key".to_string()), 238..361),
-                    (ReferenceType::Provider("provider".to_string()), 238..361),
+                    (ReferenceType::Variable("query_value".to_string()), 251..267),
+                    (ReferenceType::Prompt("test_value".to_string()), 285..300),
+                    (ReferenceType::Secret("api_key".to_string()), 312..324),
+                    (ReferenceType::Provider("provider".to_string()), 337..350),
                     (
                         ReferenceType::Prompt("expected_response_body".to_string()),
-                        380..425
+                        397..424
                     )
                 ],
                 exprs: vec![],
@@ -1471,40 +2355,51 @@ mod test {
                     ParsedConfig {
                         vars: None,
                         envs: None,
-                        prompts: Some(vec![
-                            ParsedConfigPrompt {
-                                name: "status_code".to_string(),
-                                description: Some("Status code the response will return".to_string()),
-                                default: None,
-                            }
-                        ]),
+                        prompts: Some(vec![ParsedConfigPrompt {
+                            name: "status_code".to_string(),
+                            description: Some("Status code the response will return".to_string()),
+                            default: None,
+                        }]),
                         secrets: None,
-                        auth: None
+                        auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None
                     },
                     299..384
                 )),
-                request: (
-                    HttpRequest {
-                        verb: HttpVerb::get(),
-                        target: String::from("https://httpbin.org/status/{{?status_code}}"),
-                        http_version: HttpVersion::one_point_one(),
-                        headers: vec![],
-                        body: Some(String::default())
-                    },
-                    466..522
-                ),
-                response: Some((
-                    HttpResponse {
-                        http_version: HttpVersion::one_point_one(),
-                        status_code: HttpStatusCode::new(200),
-                        status_text: "OK".to_owned(),
-                        headers: vec![("content-type".to_string(), "application/json".to_string())],
-                        body: Some("\n".to_owned())
-                    },
-                    608..655
-                )),
+                requests: vec![ParsedRequest {
+                    name: None,
+                    request: (
+                        HttpRequest {
+                            verb: HttpVerb::get(),
+                            target: String::from("https://httpbin.org/status/{{?status_code}}"),
+                            http_version: HttpVersion::one_point_one(),
+                            headers: vec![],
+                            body: Some(String::default()),
+                            body_file: None,
+                        },
+                        466..522
+                    ),
+                    response: Some((
+                        HttpResponse {
+                            http_version: HttpVersion::one_point_one(),
+                            status_code: HttpStatusCode::new(200),
+                            status_text: "OK".to_owned(),
+                            headers: vec![("content-type".to_string(), "application/json".to_string())],
+                            body: Some("\n".to_owned()),
+                            binary_body: None,
+                            metrics: None
+                        },
+                        608..655
+                    ))
+                }],
+                selected: 0,
                 refs: vec![
-                    (ReferenceType::Prompt(String::from("status_code")), 466..522)
+                    (ReferenceType::Prompt(String::from("status_code")), 497..513)
                 ],
                 exprs: vec![],
                 comments: vec![
@@ -1652,3 +2547,582 @@ mod resolve_tests {
         Some(HashMap::from([("value".to_string(), "foo".to_string())]))
     );
 }
+
+#[cfg(test)]
+mod import_tests {
+    use std::{collections::HashMap, path::Path};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        errors::{ParseError, ReqlangError},
+        parser::{ParseContext, parse_with_context},
+        span::{NO_SPAN, Spanned},
+        test_utils::{temp_dir, write_files},
+        types::ParsedRequestFile,
+    };
+
+    const ENVS: &str = r#"secrets = ["api_key", "other_key"]
+
+[[vars]]
+name = "base_url"
+
+[envs.dev]
+base_url = "https://dev.example.com"
+
+[envs.prod]
+base_url = "https://example.com"
+"#;
+
+    fn parse_file(
+        dir: &Path,
+        name: &str,
+        source: &str,
+    ) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
+        let context = ParseContext {
+            path: Some(dir.join(name)),
+            shared_config: None,
+            request: None,
+        };
+
+        parse_with_context(&Ast::from(source), &context)
+    }
+
+    #[test]
+    fn import_declarations() {
+        let dir = write_files(
+            "imports-import_declarations",
+            &[("envs.toml", ENVS), ("requests/get.reqlang", "")],
+        );
+
+        let reqfile = parse_file(
+            &dir,
+            "requests/get.reqlang",
+            &textwrap::dedent(
+                r#"
+                ```%config
+                imports = ["../envs.toml"]
+                ```
+
+                ```%request
+                GET {{:base_url}} HTTP/1.1
+                x-api-key: {{!api_key}}
+                ```
+                "#,
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(vec!["base_url".to_string()], reqfile.vars());
+        assert_eq!(vec!["api_key".to_string()], reqfile.secrets());
+        assert_eq!(
+            Some(HashMap::from([(
+                "base_url".to_string(),
+                "https://example.com".to_string()
+            )])),
+            reqfile.env("prod")
+        );
+    }
+
+    #[test]
+    fn import_conflicting_env_value() {
+        let dir = write_files(
+            "imports-import_conflicting_env_value",
+            &[("envs.toml", ENVS)],
+        );
+
+        let reqfile = parse_file(
+            &dir,
+            "get.reqlang",
+            &textwrap::dedent(
+                r#"
+                ```%config
+                imports = ["envs.toml"]
+
+                [envs.dev]
+                base_url = "http://localhost:3000"
+                ```
+
+                ```%request
+                GET {{:base_url}} HTTP/1.1
+                ```
+                "#,
+            ),
+        );
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::ConflictingImport {
+                    name: "base_url".to_string(),
+                    path: dir.join("envs.toml").display().to_string(),
+                    span: 86..111,
+                }),
+                12..82
+            )]),
+            reqfile
+        );
+    }
+
+    #[test]
+    fn import_missing_file() {
+        let dir = temp_dir("imports-import_missing_file");
+
+        let reqfile = parse_file(
+            &dir,
+            "get.reqlang",
+            "```%config\nimports = [\"envs.toml\"]\n```\n\n```%request\nGET / HTTP/1.1\n```\n",
+        );
+
+        let errs = reqfile.unwrap_err();
+
+        assert_eq!(1, errs.len());
+        assert_eq!(11..34, errs[0].1);
+        assert!(matches!(
+            &errs[0].0,
+            ReqlangError::ParseError(ParseError::InvalidImport { path, span, .. })
+                if *path == dir.join("envs.toml").display().to_string() && *span == NO_SPAN
+        ));
+    }
+
+    #[test]
+    fn import_unknown_field() {
+        let dir = write_files(
+            "imports-import_unknown_field",
+            &[("envs.toml", "[auth.bearer]\ntoken = \"abc\"\n")],
+        );
+
+        let reqfile = parse_file(
+            &dir,
+            "get.reqlang",
+            "```%config\nimports = [\"envs.toml\"]\n```\n\n```%request\nGET / HTTP/1.1\n```\n",
+        );
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::InvalidImport {
+                    path: dir.join("envs.toml").display().to_string(),
+                    message:
+                        "unknown field `auth`, expected one of `vars`, `envs`, `prompts`, `secrets`"
+                            .to_string(),
+                    span: 1..5,
+                }),
+                11..34
+            )]),
+            reqfile
+        );
+    }
+}
+
+#[cfg(test)]
+mod named_request_tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        errors::{ParseError, ReqlangError, ResolverError},
+        parser::{ParseContext, parse_with_context},
+        span::{NO_SPAN, Spanned},
+        types::{ParsedRequestFile, http::HttpRequest},
+    };
+
+    const SOURCE: &str = r#"```%config
+secrets = ["api_key"]
+```
+
+```%request list-users
+GET /users HTTP/1.1
+```
+
+```%response list-users
+HTTP/1.1 200 OK
+```
+
+```%request create-user
+POST /users HTTP/1.1
+x-api-key: {{!api_key}}
+```
+"#;
+
+    fn parse_request_named(
+        source: &str,
+        request: Option<&str>,
+    ) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
+        parse_with_context(
+            &Ast::from(source),
+            &ParseContext {
+                request: request.map(str::to_string),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn parse_all_named_requests() {
+        let reqfile = parse_request_named(SOURCE, None).unwrap();
+
+        let names: Vec<Option<String>> = reqfile.requests.iter().map(|x| x.name.clone()).collect();
+
+        assert_eq!(
+            vec![
+                Some("list-users".to_string()),
+                Some("create-user".to_string())
+            ],
+            names
+        );
+
+        // The first request is selected if they're all named
+        assert_eq!(
+            HttpRequest::get("/users", "1.1", vec![]),
+            reqfile.request().0
+        );
+        assert!(reqfile.response().is_some());
+        assert!(reqfile.requests[1].response.is_none());
+    }
+
+    #[test]
+    fn select_named_request() {
+        let reqfile = parse_request_named(SOURCE, Some("create-user")).unwrap();
+
+        assert_eq!("POST", reqfile.request().0.verb.to_string());
+        assert_eq!(None, reqfile.response());
+    }
+
+    #[test]
+    fn select_undefined_request() {
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ResolverError(ResolverError::InvalidRequestName(
+                    "delete-user".to_string()
+                )),
+                NO_SPAN
+            )]),
+            parse_request_named(SOURCE, Some("delete-user"))
+        );
+    }
+
+    #[test]
+    fn unnamed_request_is_selected_by_default() {
+        let reqfile = parse_request_named(
+            "```%request other\nPOST / HTTP/1.1\n```\n\n```%request\nGET / HTTP/1.1\n```\n",
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(HttpRequest::get("/", "1.1", vec![]), reqfile.request().0);
+    }
+
+    #[test]
+    fn duplicate_request_name() {
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::DuplicateRequestName("get".to_string())),
+                36..70
+            )]),
+            parse_request_named(
+                "```%request get\nGET / HTTP/1.1\n```\n\n```%request get\nGET / HTTP/1.1\n```\n",
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn response_without_request() {
+        assert_eq!(
+            Err(vec![
+                (
+                    ReqlangError::ParseError(ParseError::ResponseWithoutRequest(Some(
+                        "other".to_string()
+                    ))),
+                    55..70
+                ),
+                (
+                    ReqlangError::ParseError(ParseError::ResponseWithoutRequest(None)),
+                    89..104
+                )
+            ]),
+            parse_request_named(
+                "```%request get\nGET / HTTP/1.1\n```\n\n```%response other\nHTTP/1.1 200 OK\n```\n\n```%response\nHTTP/1.1 200 OK\n```\n",
+                None
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod body_file_tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        errors::{ParseError, ReqlangError},
+        parser::{ParseContext, parse_with_context, resolve_path},
+        span::Spanned,
+        test_utils::write_files,
+        types::ParsedRequestFile,
+    };
+
+    fn parse_file(
+        test_name: &str,
+        source: &str,
+    ) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
+        let context = ParseContext {
+            path: Some(
+                write_files(
+                    &format!("body-file-{test_name}"),
+                    &[("payload.json", "{}"), ("request.reqlang", source)],
+                )
+                .join("request.reqlang"),
+            ),
+            ..Default::default()
+        };
+
+        parse_with_context(&Ast::from(source), &context)
+    }
+
+    #[test]
+    fn resolve_relative_to_request_file() {
+        assert_eq!(
+            PathBuf::from("/requests/payload.json"),
+            resolve_path(
+                "./payload.json",
+                Some(&PathBuf::from("/requests/request.reqlang"))
+            )
+        );
+        assert_eq!(
+            PathBuf::from("/tmp/payload.json"),
+            resolve_path(
+                "/tmp/payload.json",
+                Some(&PathBuf::from("/requests/request.reqlang"))
+            )
+        );
+        assert_eq!(
+            PathBuf::from("payload.json"),
+            resolve_path("payload.json", None)
+        );
+    }
+
+    #[test]
+    fn body_file_exists() {
+        let reqfile = parse_file(
+            "exists",
+            "```%request\nPOST / HTTP/1.1\n\n< ./payload.json\n```\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some("./payload.json"),
+            reqfile.request().0.body_file.as_deref()
+        );
+    }
+
+    #[test]
+    fn body_file_missing() {
+        let errs = parse_file(
+            "missing",
+            "```%request\nPOST / HTTP/1.1\n\n< ./missing.json\n```\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(1, errs.len());
+        assert_eq!(12..45, errs[0].1);
+        assert!(matches!(
+            &errs[0].0,
+            ReqlangError::ParseError(ParseError::InvalidBodyFile { path, .. }) if path == "./missing.json"
+        ));
+    }
+
+    #[test]
+    fn multipart_file_missing() {
+        let errs = parse_file(
+            "multipart-missing",
+            "```%request\nPOST / HTTP/1.1\ncontent-type: multipart/form-data\n\nname=Ada\npayload=@./payload.json\navatar=@./missing.png\n```\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(1, errs.len());
+        assert!(matches!(
+            &errs[0].0,
+            ReqlangError::ParseError(ParseError::InvalidBodyFile { path, .. }) if path == "./missing.png"
+        ));
+    }
+
+    #[test]
+    fn multipart_invalid_part() {
+        let errs = parse_file(
+            "multipart-invalid",
+            "```%request\nPOST / HTTP/1.1\ncontent-type: multipart/form-data\n\nname\n```\n",
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            vec![(
+                ReqlangError::ParseError(ParseError::InvalidMultipartPart(
+                    "Expected `name=value` or `name=@path` but got `name`".to_string()
+                )),
+                12..67
+            )],
+            errs
+        );
+    }
+
+    #[test]
+    fn templated_body_file_is_not_checked() {
+        let reqfile = parse_file(
+            "templated",
+            "```%config\nprompts = [{ name = \"file\" }]\n```\n\n```%request\nPOST / HTTP/1.1\n\n< ./{{?file}}\n```\n",
+        );
+
+        assert!(reqfile.is_ok());
+    }
+}
+
+#[cfg(test)]
+mod http_version_tests {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use crate::{
+        parser::{parse_request, parse_response},
+        types::http::HttpVersion,
+    };
+
+    #[rstest]
+    #[case("GET https://example.com HTTP/1.0", HttpVersion::one_point_zero())]
+    #[case("GET https://example.com HTTP/1.1", HttpVersion::one_point_one())]
+    #[case("GET https://example.com HTTP/2", HttpVersion::two())]
+    #[case("GET https://example.com HTTP/2.0", HttpVersion::two())]
+    #[case("GET http://localhost:8080 HTTP/2\nx-test: value", HttpVersion::two())]
+    fn request_http_version(#[case] request: &str, #[case] expected: HttpVersion) {
+        let (request, _) = parse_request(&(request.to_string(), 0..request.len())).unwrap();
+
+        assert_eq!(expected, request.http_version);
+    }
+
+    #[test]
+    fn http2_request_keeps_headers_and_body() {
+        let source = "POST https://example.com HTTP/2\nx-test: value\n\n[1, 2, 3]";
+
+        let (request, _) = parse_request(&(source.to_string(), 0..source.len())).unwrap();
+
+        assert_eq!(
+            vec![("x-test".to_string(), "value".to_string())],
+            request.headers
+        );
+        assert_eq!(Some("[1, 2, 3]\n\n".to_string()), request.body);
+    }
+
+    #[test]
+    fn unsupported_request_http_version() {
+        let source = "GET https://example.com HTTP/3";
+
+        assert!(parse_request(&(source.to_string(), 0..source.len())).is_err());
+    }
+
+    #[rstest]
+    #[case("HTTP/1.0 200 OK", HttpVersion::one_point_zero())]
+    #[case("HTTP/1.1 200 OK", HttpVersion::one_point_one())]
+    #[case("HTTP/2 200 OK", HttpVersion::two())]
+    #[case("HTTP/2 204", HttpVersion::two())]
+    fn response_http_version(#[case] response: &str, #[case] expected: HttpVersion) {
+        let (response, _) = parse_response(&Some((response.to_string(), 0..response.len())))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(expected, response.http_version);
+    }
+}
+
+#[cfg(test)]
+mod client_config_tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::Ast,
+        errors::{ParseError, ReqlangError},
+        parser::parse,
+        span::Spanned,
+        types::{
+            ParsedRequestFile,
+            client::{ClientDuration, ParsedConfigClient},
+        },
+    };
+
+    fn parse_config(config: &str) -> Result<ParsedRequestFile, Vec<Spanned<ReqlangError>>> {
+        parse(&Ast::from(format!(
+            "```%config\n{config}\n```\n\n```%request\nGET https://example.com HTTP/1.1\n```\n"
+        )))
+    }
+
+    fn invalid_config(message: &str) -> ReqlangError {
+        ParseError::InvalidConfigError {
+            message: message.to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn client_config() {
+        let reqfile =
+            parse_config("[client]\ntimeout = \"30s\"\nfollow_redirects = false\ninsecure = true")
+                .unwrap();
+
+        assert_eq!(
+            Some(ParsedConfigClient {
+                timeout: Some(ClientDuration(Duration::from_secs(30))),
+                follow_redirects: Some(false),
+                insecure: Some(true),
+                ..Default::default()
+            }),
+            reqfile.config.unwrap().0.client
+        );
+    }
+
+    #[test]
+    fn invalid_duration() {
+        assert_eq!(
+            Err(vec![(
+                invalid_config(
+                    "Invalid duration '30', expected a number followed by ms, s, or m (e.g. 30s)"
+                ),
+                30..34
+            )]),
+            parse_config("[client]\ntimeout = \"30\"")
+        );
+    }
+
+    #[test]
+    fn unknown_option() {
+        let errs = parse_config("[client]\ntimout = \"30s\"").unwrap_err();
+
+        assert_eq!(20..26, errs[0].1);
+    }
+
+    #[test]
+    fn max_redirects_without_following_redirects() {
+        assert_eq!(
+            Err(vec![(
+                invalid_config("max_redirects can't be set when follow_redirects is false"),
+                12..18
+            )]),
+            parse_config("[client]\nfollow_redirects = false\nmax_redirects = 3")
+        );
+    }
+
+    #[test]
+    fn missing_ca_cert() {
+        let errs = parse_config("[client]\nca_cert = \"./missing.pem\"").unwrap_err();
+
+        assert_eq!(1, errs.len());
+        assert_eq!(12..18, errs[0].1);
+        assert!(matches!(
+            &errs[0].0,
+            ReqlangError::ParseError(ParseError::InvalidConfigError { message })
+                if message.starts_with("Unable to read './missing.pem': ")
+        ));
+    }
+}
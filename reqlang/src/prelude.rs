@@ -1,4 +1,4 @@
-pub use crate::assert_response::assert_response;
+pub use crate::assert_response::{assert_response, assert_response_with};
 pub use crate::ast::{Ast, AstNode};
 pub use crate::fetch::{Fetch, HttpRequestFetcher};
 pub use crate::parser::parse;
@@ -0,0 +1,526 @@
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assert_response::{ResponseDiff, ResponseDiffs},
+    types::http::HttpResponse,
+};
+
+/// Supported formats to report the results of testing request files
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ReportFormat {
+    /// JUnit XML
+    JUnit,
+    /// Test Anything Protocol (version 13)
+    Tap,
+}
+
+impl Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportFormat::JUnit => write!(f, "junit"),
+            ReportFormat::Tap => write!(f, "tap"),
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "junit" => Ok(Self::JUnit),
+            "tap" => Ok(Self::Tap),
+            _ => Err(format!("Unknown report format: {s}")),
+        }
+    }
+}
+
+/// Outcome of a test case
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestCaseOutcome {
+    Passed,
+    /// The response didn't match the expected response
+    Failed(String),
+    /// The request file couldn't be resolved or the request failed
+    Errored(String),
+}
+
+/// Part of a request file that was tested
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestCase {
+    /// `request`, `status`, `header <name>`, or `body`
+    pub name: String,
+    pub duration: Duration,
+    pub outcome: TestCaseOutcome,
+}
+
+impl TestCase {
+    fn new(name: impl Into<String>, duration: Duration, outcome: TestCaseOutcome) -> Self {
+        Self {
+            name: name.into(),
+            duration,
+            outcome,
+        }
+    }
+}
+
+/// Test cases from running a request file
+///
+/// The `request` test case covers executing the request, including its
+/// `max_latency`, and is timed. The status, each expected header, and the body
+/// of the expected response are separate test cases.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequestFileReport {
+    /// Path of the request file
+    pub path: String,
+    pub test_cases: Vec<TestCase>,
+}
+
+impl RequestFileReport {
+    /// Report a request file that errored before its response could be tested
+    pub fn errored(
+        path: impl Into<String>,
+        duration: Duration,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            test_cases: vec![TestCase::new(
+                "request",
+                duration,
+                TestCaseOutcome::Errored(message.into()),
+            )],
+        }
+    }
+
+    /// Report the result of asserting the response of a request file
+    ///
+    /// Without an `expected` response only the `request` test case is reported.
+    pub fn from_response(
+        path: impl Into<String>,
+        duration: Duration,
+        expected: Option<&HttpResponse>,
+        result: &Result<(), Box<ResponseDiffs>>,
+    ) -> Self {
+        let diffs = match result {
+            Ok(_) => vec![],
+            Err(diffs) => diffs.diffs(),
+        };
+
+        let outcome = |name: &str| {
+            let messages: Vec<String> = diffs
+                .iter()
+                .filter(|diff| test_case_name(diff) == name)
+                .map(|diff| diff.to_string())
+                .collect();
+
+            if messages.is_empty() {
+                TestCaseOutcome::Passed
+            } else {
+                TestCaseOutcome::Failed(messages.join("\n"))
+            }
+        };
+
+        let mut test_cases = vec![TestCase::new("request", duration, outcome("request"))];
+
+        if let Some(expected) = expected {
+            let mut names = vec!["status".to_string()];
+
+            for (header, _) in &expected.headers {
+                let name = format!("header {}", header.to_lowercase());
+
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+
+            if expected.body.is_some() {
+                names.push("body".to_string());
+            }
+
+            for name in names {
+                let outcome = outcome(&name);
+
+                test_cases.push(TestCase::new(name, Duration::ZERO, outcome));
+            }
+        }
+
+        Self {
+            path: path.into(),
+            test_cases,
+        }
+    }
+
+    /// Total duration of the test cases
+    pub fn duration(&self) -> Duration {
+        self.test_cases
+            .iter()
+            .map(|test_case| test_case.duration)
+            .sum()
+    }
+
+    /// Number of failed test cases
+    pub fn failures(&self) -> usize {
+        self.count(|outcome| matches!(outcome, TestCaseOutcome::Failed(_)))
+    }
+
+    /// Number of errored test cases
+    pub fn errors(&self) -> usize {
+        self.count(|outcome| matches!(outcome, TestCaseOutcome::Errored(_)))
+    }
+
+    fn count(&self, f: fn(&TestCaseOutcome) -> bool) -> usize {
+        self.test_cases
+            .iter()
+            .filter(|test_case| f(&test_case.outcome))
+            .count()
+    }
+}
+
+/// Name of the test case a difference fails
+fn test_case_name(diff: &ResponseDiff) -> String {
+    match diff {
+        ResponseDiff::StatusCode { .. }
+        | ResponseDiff::StatusText { .. }
+        | ResponseDiff::HttpVersion { .. }
+        | ResponseDiff::UnmatchedStatusText { .. } => "status".to_string(),
+        ResponseDiff::MissingHeader(header)
+        | ResponseDiff::MismatchHeaderValue { header, .. }
+        | ResponseDiff::UnmatchedHeaderValue { header, .. } => {
+            format!("header {}", header.to_lowercase())
+        }
+        ResponseDiff::Body { .. }
+        | ResponseDiff::UnmatchedBody { .. }
+        | ResponseDiff::JsonBody(_) => "body".to_string(),
+        ResponseDiff::Latency { .. } => "request".to_string(),
+    }
+}
+
+/// Report the results of testing request files in a specified [ReportFormat]
+pub fn report(reports: &[RequestFileReport], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::JUnit => junit(reports),
+        ReportFormat::Tap => tap(reports),
+    }
+}
+
+fn junit(reports: &[RequestFileReport]) -> String {
+    let tests: usize = reports.iter().map(|x| x.test_cases.len()).sum();
+    let failures: usize = reports.iter().map(RequestFileReport::failures).sum();
+    let errors: usize = reports.iter().map(RequestFileReport::errors).sum();
+    let time: Duration = reports.iter().map(RequestFileReport::duration).sum();
+
+    let mut output = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    output.push_str(&format!(
+        "<testsuites name=\"reqlang\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{:.3}\">\n",
+        time.as_secs_f64()
+    ));
+
+    for report in reports {
+        let path = xml_escape(&report.path);
+
+        output.push_str(&format!(
+            "  <testsuite name=\"{path}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            report.test_cases.len(),
+            report.failures(),
+            report.errors(),
+            report.duration().as_secs_f64()
+        ));
+
+        for test_case in &report.test_cases {
+            let open_tag = format!(
+                "    <testcase name=\"{}\" classname=\"{path}\" time=\"{:.3}\"",
+                xml_escape(&test_case.name),
+                test_case.duration.as_secs_f64()
+            );
+
+            let (tag, message) = match &test_case.outcome {
+                TestCaseOutcome::Passed => {
+                    output.push_str(&format!("{open_tag}/>\n"));
+                    continue;
+                }
+                TestCaseOutcome::Failed(message) => ("failure", message),
+                TestCaseOutcome::Errored(message) => ("error", message),
+            };
+
+            let summary = message.lines().next().unwrap_or_default();
+
+            output.push_str(&format!(
+                "{open_tag}>\n      <{tag} message=\"{}\">{}</{tag}>\n    </testcase>\n",
+                xml_escape(summary),
+                xml_escape(message)
+            ));
+        }
+
+        output.push_str("  </testsuite>\n");
+    }
+
+    output.push_str("</testsuites>");
+
+    output
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn tap(reports: &[RequestFileReport]) -> String {
+    let test_cases: Vec<(&str, &TestCase)> = reports
+        .iter()
+        .flat_map(|report| {
+            report
+                .test_cases
+                .iter()
+                .map(|test_case| (report.path.as_str(), test_case))
+        })
+        .collect();
+
+    let mut output = format!("TAP version 13\n1..{}", test_cases.len());
+
+    for (number, (path, test_case)) in test_cases.into_iter().enumerate() {
+        let (status, message) = match &test_case.outcome {
+            TestCaseOutcome::Passed => ("ok", None),
+            TestCaseOutcome::Failed(message) => ("not ok", Some(message)),
+            TestCaseOutcome::Errored(message) => ("not ok", Some(message)),
+        };
+
+        output.push_str(&format!(
+            "\n{status} {} - {path} {}",
+            number + 1,
+            test_case.name
+        ));
+
+        // YAML diagnostics for timed or failed test cases
+        if message.is_none() && test_case.duration.is_zero() {
+            continue;
+        }
+
+        output.push_str("\n  ---");
+        output.push_str(&format!(
+            "\n  duration_ms: {:.3}",
+            test_case.duration.as_secs_f64() * 1000.0
+        ));
+
+        if let Some(message) = message {
+            output.push_str("\n  message: |");
+
+            for line in message.lines() {
+                if line.is_empty() {
+                    output.push('\n');
+                } else {
+                    output.push_str(&format!("\n    {line}"));
+                }
+            }
+        }
+
+        output.push_str("\n  ...");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        assert_response::{assert_response, assert_response_with},
+        types::{
+            ParsedConfigAssert,
+            http::{HttpResponse, HttpResponseMetrics, HttpStatusCode},
+        },
+    };
+
+    use super::{ReportFormat, RequestFileReport, TestCaseOutcome, report};
+
+    fn expected_response() -> HttpResponse {
+        HttpResponse {
+            http_version: "1.1".into(),
+            status_code: HttpStatusCode::new(200),
+            status_text: "OK".to_string(),
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: Some("Hello".to_string()),
+            binary_body: None,
+            metrics: None,
+        }
+    }
+
+    fn reports() -> Vec<RequestFileReport> {
+        let expected = expected_response();
+
+        let actual = HttpResponse {
+            status_code: HttpStatusCode::new(404),
+            status_text: "Not Found".to_string(),
+            ..expected.clone()
+        };
+
+        vec![
+            RequestFileReport::from_response(
+                "users/get.reqlang",
+                Duration::from_millis(125),
+                Some(&expected),
+                &assert_response(&expected, &actual),
+            ),
+            RequestFileReport::errored(
+                "<invalid>.reqlang",
+                Duration::ZERO,
+                "Undefined variable: base_url",
+            ),
+        ]
+    }
+
+    #[test]
+    fn report_test_case_per_part_of_response() {
+        let report = &reports()[0];
+
+        let outcomes: Vec<(&str, &TestCaseOutcome)> = report
+            .test_cases
+            .iter()
+            .map(|test_case| (test_case.name.as_str(), &test_case.outcome))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("request", &TestCaseOutcome::Passed),
+                (
+                    "status",
+                    &TestCaseOutcome::Failed(
+                        "Expected status code 200 got 404\nExpected status text 'OK' got 'Not Found'"
+                            .to_string()
+                    )
+                ),
+                ("header content-type", &TestCaseOutcome::Passed),
+                ("body", &TestCaseOutcome::Passed),
+            ],
+            outcomes
+        );
+        assert_eq!(1, report.failures());
+        assert_eq!(0, report.errors());
+    }
+
+    #[test]
+    fn report_max_latency_as_request_test_case() {
+        let expected = expected_response();
+
+        let actual = HttpResponse {
+            metrics: Some(HttpResponseMetrics {
+                total_ms: 250.0,
+                ..Default::default()
+            }),
+            ..expected.clone()
+        };
+
+        let options = ParsedConfigAssert {
+            max_latency: Some("100ms".parse().unwrap()),
+            ..Default::default()
+        };
+
+        let report = RequestFileReport::from_response(
+            "users/get.reqlang",
+            Duration::from_millis(250),
+            Some(&expected),
+            &assert_response_with(&expected, &actual, &options),
+        );
+
+        assert_eq!(
+            TestCaseOutcome::Failed("Expected a response within 100ms got 250.0 ms".to_string()),
+            report.test_cases[0].outcome
+        );
+        assert_eq!(1, report.failures());
+    }
+
+    #[test]
+    fn report_without_expected_response() {
+        let report = RequestFileReport::from_response(
+            "get.reqlang",
+            Duration::from_millis(5),
+            None,
+            &Ok(()),
+        );
+
+        assert_eq!(1, report.test_cases.len());
+        assert_eq!(Duration::from_millis(5), report.duration());
+    }
+
+    #[test]
+    fn report_as_junit() {
+        assert_eq!(
+            textwrap::dedent(
+                r#"
+                <?xml version="1.0" encoding="UTF-8"?>
+                <testsuites name="reqlang" tests="5" failures="1" errors="1" time="0.125">
+                  <testsuite name="users/get.reqlang" tests="4" failures="1" errors="0" time="0.125">
+                    <testcase name="request" classname="users/get.reqlang" time="0.125"/>
+                    <testcase name="status" classname="users/get.reqlang" time="0.000">
+                      <failure message="Expected status code 200 got 404">Expected status code 200 got 404
+                Expected status text &apos;OK&apos; got &apos;Not Found&apos;</failure>
+                    </testcase>
+                    <testcase name="header content-type" classname="users/get.reqlang" time="0.000"/>
+                    <testcase name="body" classname="users/get.reqlang" time="0.000"/>
+                  </testsuite>
+                  <testsuite name="&lt;invalid&gt;.reqlang" tests="1" failures="0" errors="1" time="0.000">
+                    <testcase name="request" classname="&lt;invalid&gt;.reqlang" time="0.000">
+                      <error message="Undefined variable: base_url">Undefined variable: base_url</error>
+                    </testcase>
+                  </testsuite>
+                </testsuites>
+                "#
+            )
+            .trim(),
+            report(&reports(), ReportFormat::JUnit)
+        );
+    }
+
+    #[test]
+    fn report_as_tap() {
+        assert_eq!(
+            textwrap::dedent(
+                r#"
+                TAP version 13
+                1..5
+                ok 1 - users/get.reqlang request
+                  ---
+                  duration_ms: 125.000
+                  ...
+                not ok 2 - users/get.reqlang status
+                  ---
+                  duration_ms: 0.000
+                  message: |
+                    Expected status code 200 got 404
+                    Expected status text 'OK' got 'Not Found'
+                  ...
+                ok 3 - users/get.reqlang header content-type
+                ok 4 - users/get.reqlang body
+                not ok 5 - <invalid>.reqlang request
+                  ---
+                  duration_ms: 0.000
+                  message: |
+                    Undefined variable: base_url
+                  ...
+                "#
+            )
+            .trim(),
+            report(&reports(), ReportFormat::Tap)
+        );
+    }
+
+    #[test]
+    fn report_format_from_str() {
+        assert_eq!(Ok(ReportFormat::JUnit), "junit".parse());
+        assert_eq!(Ok(ReportFormat::Tap), "tap".parse());
+        assert_eq!(
+            Err("Unknown report format: xml".to_string()),
+            "xml".parse::<ReportFormat>()
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use std::{collections::HashMap, fs, io, path::Path, process::Command};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    dependencies::parse_with_dependencies, errors::SecretProviderError, parser::ParseContext,
+    types::ReferenceType,
+};
+
+/// Source of secret values
+///
+/// Secret values passed directly take precedence over providers. See [resolve_secrets]
+pub trait SecretProvider: Send + Sync {
+    /// Get the value of a secret, if the provider has it
+    fn get(&self, name: &str) -> Result<Option<String>, SecretProviderError>;
+}
+
+/// Secret values from environment variables
+///
+/// The secret `api_key` with the prefix `REQLANG_` is read from `REQLANG_api_key`
+/// or, if that isn't set, `REQLANG_API_KEY`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EnvSecretProvider {
+    pub prefix: String,
+}
+
+impl EnvSecretProvider {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl SecretProvider for EnvSecretProvider {
+    fn get(&self, name: &str) -> Result<Option<String>, SecretProviderError> {
+        let prefix = &self.prefix;
+
+        Ok(std::env::var(format!("{prefix}{name}"))
+            .or_else(|_| std::env::var(format!("{prefix}{}", name.to_uppercase())))
+            .ok())
+    }
+}
+
+/// Secret values from a dotenv (`.env`) file
+///
+/// ```text
+/// # Comment
+/// api_key=abc123
+/// export password="hunter2"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DotenvSecretProvider {
+    pub values: HashMap<String, String>,
+}
+
+impl DotenvSecretProvider {
+    /// Load a dotenv file
+    ///
+    /// A missing file has no values since dotenv files are usually untracked.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SecretProviderError> {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(input) => Self::parse(&path.display().to_string(), &input),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(SecretProviderError::ReadError(
+                path.display().to_string(),
+                err.to_string(),
+            )),
+        }
+    }
+
+    /// Parse the `input` of a dotenv file, using `path` in errors
+    pub fn parse(path: &str, input: &str) -> Result<Self, SecretProviderError> {
+        let mut values = HashMap::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let line = line.strip_prefix("export ").unwrap_or(line);
+
+            let invalid = || SecretProviderError::InvalidDotenv(path.to_string(), index + 1);
+
+            let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+            let key = key.trim();
+
+            if key.is_empty() {
+                return Err(invalid());
+            }
+
+            values.insert(key.to_string(), dotenv_value(value.trim()));
+        }
+
+        Ok(Self { values })
+    }
+}
+
+/// Unquote a dotenv value, or remove the comment from an unquoted value
+fn dotenv_value(value: &str) -> String {
+    let quoted = |quote: char| {
+        value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+    };
+
+    if let Some(value) = quoted('"') {
+        return value
+            .replace("\\n", "\n")
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\");
+    }
+
+    if let Some(value) = quoted('\'') {
+        return value.to_string();
+    }
+
+    match value.split_once(" #") {
+        Some((value, _)) => value.trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+impl SecretProvider for DotenvSecretProvider {
+    fn get(&self, name: &str) -> Result<Option<String>, SecretProviderError> {
+        Ok(self.values.get(name).cloned())
+    }
+}
+
+/// Secret values printed to stdout by a shell command
+///
+/// `{name}` in the command is replaced with the secret name (e.g. `pass show {name}`).
+/// Trailing newlines are removed from the output. A command that fails doesn't have
+/// the secret, so the next provider is tried.
+///
+/// Secret names come from request files, so names with characters other than
+/// letters, digits, `_`, `.`, and `-` are refused instead of being run by the shell.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandSecretProvider {
+    pub command: String,
+}
+
+impl CommandSecretProvider {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+        }
+    }
+}
+
+impl SecretProvider for CommandSecretProvider {
+    fn get(&self, name: &str) -> Result<Option<String>, SecretProviderError> {
+        if !is_command_safe_name(name) {
+            return Err(SecretProviderError::InvalidSecretName(name.to_string()));
+        }
+
+        let command = self.command.replace("{name}", name);
+
+        let output = shell(&command)
+            .output()
+            .map_err(|err| SecretProviderError::CommandError(name.to_string(), err.to_string()))?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        Ok(Some(stdout.trim_end_matches(['\n', '\r']).to_string()))
+    }
+}
+
+/// If a secret name can be put in a shell command as is
+fn is_command_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+/// Configuration of a [SecretProvider], used by the workspace manifest and the CLI
+///
+/// ```toml
+/// [[secret_providers]]
+/// type = "env"
+/// prefix = "REQLANG_"
+///
+/// [[secret_providers]]
+/// type = "dotenv"
+/// path = ".env"
+///
+/// [[secret_providers]]
+/// type = "command"
+/// command = "pass show {name}"
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "lowercase")]
+#[ts(export)]
+pub enum SecretProviderConfig {
+    /// See [EnvSecretProvider]
+    Env {
+        #[serde(default)]
+        prefix: String,
+    },
+    /// See [DotenvSecretProvider]
+    Dotenv { path: String },
+    /// See [CommandSecretProvider]
+    Command { command: String },
+}
+
+impl SecretProviderConfig {
+    /// Create the provider, resolving paths relative to `base_dir`
+    pub fn provider(
+        &self,
+        base_dir: &Path,
+    ) -> Result<Box<dyn SecretProvider>, SecretProviderError> {
+        Ok(match self {
+            SecretProviderConfig::Env { prefix } => Box::new(EnvSecretProvider::new(prefix)),
+            SecretProviderConfig::Dotenv { path } => {
+                Box::new(DotenvSecretProvider::load(base_dir.join(path))?)
+            }
+            SecretProviderConfig::Command { command } => {
+                Box::new(CommandSecretProvider::new(command))
+            }
+        })
+    }
+}
+
+/// Get the values of the secrets `names` that weren't passed in `secrets` from the providers
+///
+/// Providers are tried in order and the first value found is used. Secrets
+/// without a value are left out so templating reports them as not passed.
+pub fn resolve_secrets(
+    names: &[String],
+    secrets: &HashMap<String, String>,
+    providers: &[Box<dyn SecretProvider>],
+) -> Result<HashMap<String, String>, SecretProviderError> {
+    let mut values = secrets.clone();
+
+    for name in names {
+        if values.contains_key(name) {
+            continue;
+        }
+
+        for provider in providers {
+            if let Some(value) = provider.get(name)? {
+                values.insert(name.clone(), value);
+                break;
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// Names of the secrets referenced by a request file and the request files it depends on
+///
+/// Secrets only referenced by requests that weren't selected aren't needed. See [parse_with_dependencies]
+pub fn referenced_secrets(
+    path: impl AsRef<Path>,
+    source: &str,
+    context: &ParseContext,
+) -> Vec<String> {
+    let mut names = vec![];
+
+    for reqfile in parse_with_dependencies(path, source, context) {
+        for name in reqfile.secrets() {
+            let is_referenced = reqfile.is_referenced(&ReferenceType::Secret(name.clone()));
+
+            if is_referenced && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        errors::SecretProviderError,
+        parser::ParseContext,
+        test_utils::{temp_dir, write_files},
+    };
+
+    use super::{
+        CommandSecretProvider, DotenvSecretProvider, EnvSecretProvider, SecretProvider,
+        SecretProviderConfig, referenced_secrets, resolve_secrets,
+    };
+
+    #[test]
+    fn env_provider_uses_prefix_and_uppercase_name() {
+        // Cargo sets the package environment variables when running tests
+        let provider = EnvSecretProvider::new("CARGO_PKG_");
+
+        assert_eq!(Ok(Some("reqlang".to_string())), provider.get("name"));
+        assert_eq!(Ok(None), provider.get("undefined_secret"));
+    }
+
+    #[test]
+    fn dotenv_provider_parses_values() {
+        let provider = DotenvSecretProvider::parse(
+            ".env",
+            &textwrap::dedent(
+                r#"
+                # Comment
+                api_key=abc123
+                export password="hunter\"2"
+                single = 'a # b'
+                unquoted=value # comment
+                "#,
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            HashMap::from([
+                ("api_key".to_string(), "abc123".to_string()),
+                ("password".to_string(), "hunter\"2".to_string()),
+                ("single".to_string(), "a # b".to_string()),
+                ("unquoted".to_string(), "value".to_string()),
+            ]),
+            provider.values
+        );
+    }
+
+    #[test]
+    fn dotenv_provider_invalid_line() {
+        assert_eq!(
+            Err(SecretProviderError::InvalidDotenv(".env".to_string(), 2)),
+            DotenvSecretProvider::parse(".env", "api_key=abc123\napi_secret\n")
+        );
+    }
+
+    #[test]
+    fn dotenv_provider_missing_file() {
+        assert_eq!(
+            Ok(DotenvSecretProvider::default()),
+            DotenvSecretProvider::load("does/not/exist/.env")
+        );
+    }
+
+    #[test]
+    fn command_provider_replaces_name() {
+        let provider = CommandSecretProvider::new("echo {name}-value");
+
+        assert_eq!(
+            Ok(Some("api_key-value".to_string())),
+            provider.get("api_key")
+        );
+    }
+
+    #[test]
+    fn command_provider_failure() {
+        let provider = CommandSecretProvider::new("echo not found >&2 && exit 1");
+
+        assert_eq!(Ok(None), provider.get("api_key"));
+    }
+
+    #[test]
+    fn command_provider_refuses_injected_name() {
+        let marker = temp_dir("secrets-injected_name").join("marker");
+
+        let provider = CommandSecretProvider::new("echo {name}");
+        let name = format!("x; touch {}", marker.display());
+
+        assert_eq!(
+            Err(SecretProviderError::InvalidSecretName(name.clone())),
+            provider.get(&name)
+        );
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn resolve_secrets_with_providers_in_order() {
+        let providers = vec![
+            SecretProviderConfig::Env {
+                prefix: "REQLANG_UNDEFINED_".to_string(),
+            }
+            .provider(Path::new(""))
+            .unwrap(),
+            Box::new(DotenvSecretProvider {
+                values: HashMap::from([("api_key".to_string(), "from_dotenv".to_string())]),
+            }) as Box<dyn SecretProvider>,
+            Box::new(CommandSecretProvider::new("echo from_command")),
+        ];
+
+        let secrets = resolve_secrets(
+            &[
+                "passed".to_string(),
+                "api_key".to_string(),
+                "password".to_string(),
+            ],
+            &HashMap::from([("passed".to_string(), "from_args".to_string())]),
+            &providers,
+        );
+
+        assert_eq!(
+            Ok(HashMap::from([
+                ("passed".to_string(), "from_args".to_string()),
+                ("api_key".to_string(), "from_dotenv".to_string()),
+                ("password".to_string(), "from_command".to_string()),
+            ])),
+            secrets
+        );
+    }
+
+    #[test]
+    fn resolve_secrets_after_failed_command() {
+        let providers: Vec<Box<dyn SecretProvider>> = vec![
+            Box::new(CommandSecretProvider::new("exit 1")),
+            Box::new(DotenvSecretProvider {
+                values: HashMap::from([("api_key".to_string(), "from_dotenv".to_string())]),
+            }),
+        ];
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "api_key".to_string(),
+                "from_dotenv".to_string()
+            )])),
+            resolve_secrets(&["api_key".to_string()], &HashMap::new(), &providers)
+        );
+    }
+
+    #[test]
+    fn referenced_secrets_includes_dependencies() {
+        let dir = write_files(
+            "secrets-referenced_secrets",
+            &[(
+                "login.reqlang",
+                "```%config\nsecrets = [\"password\"]\n```\n\n```%request\nPOST /login HTTP/1.1\n\n{{!password}}\n```\n",
+            )],
+        );
+
+        let source = textwrap::dedent(
+            r#"
+            ```%config
+            secrets = ["api_key", "unused_key"]
+
+            [[depends]]
+            name = "login"
+            path = "login.reqlang"
+            ```
+
+            ```%request
+            GET / HTTP/1.1
+            x-api-key: {{!api_key}}
+            ```
+            "#,
+        );
+
+        assert_eq!(
+            vec!["api_key".to_string(), "password".to_string()],
+            referenced_secrets(dir.join("get.reqlang"), &source, &ParseContext::default())
+        );
+    }
+}
@@ -0,0 +1,410 @@
+use regex::Regex;
+
+use crate::{
+    ast::Ast,
+    errors::RenameError,
+    parser::ConfigSpans,
+    span::{Span, Spanned},
+    types::{ParsedRequestFile, ReferenceType},
+};
+
+/// Names of values that can be renamed
+///
+/// Dots aren't allowed since they would be dotted keys in the environments.
+const NAME_PATTERN: &str = r"^[a-zA-Z][_a-zA-Z0-9]*$";
+
+/// Spans of the names of the variables, prompts, and secrets in a request file
+#[derive(Debug, Default)]
+struct Symbols {
+    /// Names in `[[vars]]`, `[[prompts]]`, and `secrets`
+    declarations: Vec<Spanned<ReferenceType>>,
+    /// Keys in `[envs.*]`
+    environments: Vec<Spanned<ReferenceType>>,
+    /// Names in template references and expressions
+    references: Vec<Spanned<ReferenceType>>,
+}
+
+impl Symbols {
+    fn new(reqfile: &ParsedRequestFile, source: &str) -> Self {
+        let mut symbols = Symbols::default();
+
+        if let Some(config) = Ast::from(source).config() {
+            let config_spans = ConfigSpans::new(config);
+
+            let declarations =
+                |spans: Vec<Spanned<String>>, symbol: fn(String) -> ReferenceType| {
+                    spans
+                        .into_iter()
+                        .map(move |(name, span)| (symbol(name), span))
+                };
+
+            symbols.declarations = declarations(config_spans.vars, ReferenceType::Variable)
+                .chain(declarations(config_spans.prompts, ReferenceType::Prompt))
+                .chain(declarations(config_spans.secrets, ReferenceType::Secret))
+                .collect();
+
+            symbols.environments =
+                declarations(config_spans.env_values, ReferenceType::Variable).collect();
+        }
+
+        for (reference, span) in reqfile.refs.iter().filter(|(x, _)| is_symbol(x)) {
+            let lookup_name = reference.lookup_name();
+
+            // Skip the prefix (e.g. `:`) of the name
+            if let Some(start) = source
+                .get(span.clone())
+                .and_then(|text| text.find(&lookup_name))
+            {
+                let start = span.start + start + 1;
+
+                symbols
+                    .references
+                    .push((reference.clone(), start..start + lookup_name.len() - 1));
+            }
+        }
+
+        symbols
+    }
+
+    /// The symbol with a name at `index`, including the index just after the name
+    fn at(&self, index: usize) -> Option<&Spanned<ReferenceType>> {
+        self.declarations
+            .iter()
+            .chain(self.environments.iter())
+            .chain(self.references.iter())
+            .find(|(_, span)| span.start <= index && index <= span.end)
+    }
+
+    fn declaration(&self, symbol: &ReferenceType) -> Option<&Span> {
+        self.declarations
+            .iter()
+            .find(|(x, _)| x == symbol)
+            .map(|(_, span)| span)
+    }
+
+    /// Spans of the environment values for a variable
+    fn environments(&self, symbol: &ReferenceType) -> Vec<Span> {
+        spans_of(&self.environments, symbol)
+    }
+
+    fn references(&self, symbol: &ReferenceType) -> Vec<Span> {
+        spans_of(&self.references, symbol)
+    }
+}
+
+fn spans_of(symbols: &[Spanned<ReferenceType>], symbol: &ReferenceType) -> Vec<Span> {
+    symbols
+        .iter()
+        .filter(|(x, _)| x == symbol)
+        .map(|(_, span)| span.clone())
+        .collect()
+}
+
+/// If the reference is to a value declared in the config
+fn is_symbol(reference: &ReferenceType) -> bool {
+    matches!(
+        reference,
+        ReferenceType::Variable(_) | ReferenceType::Prompt(_) | ReferenceType::Secret(_)
+    )
+}
+
+/// Get the spans defining the variable, prompt, or secret at `index` in `source`
+///
+/// These are the name in its declaration followed by its keys in the environments.
+pub fn definitions(reqfile: &ParsedRequestFile, source: &str, index: usize) -> Vec<Span> {
+    let symbols = Symbols::new(reqfile, source);
+
+    let Some((symbol, _)) = symbols.at(index) else {
+        return vec![];
+    };
+
+    symbols
+        .declaration(symbol)
+        .cloned()
+        .into_iter()
+        .chain(symbols.environments(symbol))
+        .collect()
+}
+
+/// Get the spans of the references to the variable, prompt, or secret at `index` in `source`
+///
+/// The declaration and environment keys are included if `include_declaration` is true.
+pub fn references(
+    reqfile: &ParsedRequestFile,
+    source: &str,
+    index: usize,
+    include_declaration: bool,
+) -> Vec<Span> {
+    let symbols = Symbols::new(reqfile, source);
+
+    let Some((symbol, _)) = symbols.at(index) else {
+        return vec![];
+    };
+
+    let mut spans = symbols.references(symbol);
+
+    if include_declaration {
+        spans.extend(symbols.declaration(symbol).cloned());
+        spans.extend(symbols.environments(symbol));
+    }
+
+    spans.sort_by_key(|span| span.start);
+
+    spans
+}
+
+/// Get the span of the name of the variable, prompt, or secret at `index` in `source`
+pub fn symbol_span(reqfile: &ParsedRequestFile, source: &str, index: usize) -> Option<Span> {
+    Symbols::new(reqfile, source)
+        .at(index)
+        .map(|(_, span)| span.clone())
+}
+
+/// Get the edits renaming the variable, prompt, or secret at `index` in `source` to `new_name`
+///
+/// The declaration, environment keys, and references are all renamed. Values
+/// declared outside of the request file (e.g. imported values) can't be renamed.
+pub fn rename(
+    reqfile: &ParsedRequestFile,
+    source: &str,
+    index: usize,
+    new_name: &str,
+) -> Result<Vec<Spanned<String>>, RenameError> {
+    let symbols = Symbols::new(reqfile, source);
+
+    let (symbol, _) = symbols.at(index).ok_or(RenameError::NotRenameable)?;
+
+    let Some(declaration) = symbols.declaration(symbol) else {
+        return Err(RenameError::NotDeclared(
+            symbol.lookup_name()[1..].to_string(),
+        ));
+    };
+
+    if !Regex::new(NAME_PATTERN).unwrap().is_match(new_name) {
+        return Err(RenameError::InvalidName(new_name.to_string()));
+    }
+
+    let renamed = match symbol {
+        ReferenceType::Variable(_) => ReferenceType::Variable(new_name.to_string()),
+        ReferenceType::Prompt(_) => ReferenceType::Prompt(new_name.to_string()),
+        _ => ReferenceType::Secret(new_name.to_string()),
+    };
+
+    if symbols.declaration(&renamed).is_some() {
+        return Err(RenameError::AlreadyDeclared(new_name.to_string()));
+    }
+
+    let mut spans = vec![declaration.clone()];
+    spans.extend(symbols.environments(symbol));
+    spans.extend(symbols.references(symbol));
+    spans.sort_by_key(|span| span.start);
+
+    Ok(spans
+        .into_iter()
+        .map(|span| (new_name.to_string(), span))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{ast::Ast, errors::RenameError, parser::parse, span::Span};
+
+    use super::{definitions, references, rename, symbol_span};
+
+    const SOURCE: &str = r#"```%config
+secrets = ["api_key"]
+
+[[vars]]
+name = "base_url"
+
+[[vars]]
+name = "user_path"
+
+[[prompts]]
+name = "user_id"
+
+[envs.dev]
+base_url = "https://dev.example.com"
+user_path = "{{:base_url}}/users"
+
+[envs.prod]
+base_url = "https://example.com"
+user_path = "{{:base_url}}/users"
+```
+
+```%request
+GET {{:user_path}}/{{?user_id}} HTTP/1.1
+x-api-key: {{!api_key}}
+x-base: {(trim(:base_url))}
+```
+"#;
+
+    /// The spans of every occurrence of `needle` in the source
+    fn spans_of(needle: &str) -> Vec<Span> {
+        SOURCE
+            .match_indices(needle)
+            .map(|(start, _)| start..start + needle.len())
+            .collect()
+    }
+
+    fn index_of(needle: &str) -> usize {
+        SOURCE.find(needle).unwrap()
+    }
+
+    /// The source with the edits applied
+    fn apply(mut edits: Vec<(String, Span)>) -> String {
+        let mut source = SOURCE.to_string();
+
+        edits.sort_by_key(|(_, span)| std::cmp::Reverse(span.start));
+
+        for (text, span) in edits {
+            source.replace_range(span, &text);
+        }
+
+        source
+    }
+
+    #[test]
+    fn definitions_of_variable_reference() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        assert_eq!(
+            vec![
+                spans_of("\"base_url\"")[0].start + 1..spans_of("\"base_url\"")[0].end - 1,
+                spans_of("base_url =")[0].start..spans_of("base_url =")[0].start + 8,
+                spans_of("base_url =")[1].start..spans_of("base_url =")[1].start + 8,
+            ],
+            definitions(&reqfile, SOURCE, index_of("{(trim(:base_url") + 9)
+        );
+    }
+
+    #[test]
+    fn definitions_of_prompt_reference() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+        let declaration = index_of("\"user_id\"") + 1;
+
+        assert_eq!(
+            vec![declaration..declaration + 7],
+            definitions(&reqfile, SOURCE, index_of("{{?user_id}}") + 3)
+        );
+    }
+
+    #[test]
+    fn definitions_outside_of_symbols() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        assert_eq!(
+            Vec::<Span>::new(),
+            definitions(&reqfile, SOURCE, index_of("HTTP/1.1"))
+        );
+    }
+
+    #[test]
+    fn references_to_variable() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        let reference_spans: Vec<Span> = spans_of("{{:base_url}}")
+            .into_iter()
+            .map(|span| span.start + 3..span.end - 2)
+            .chain(
+                spans_of(":base_url))")
+                    .into_iter()
+                    .map(|span| span.start + 1..span.start + 9),
+            )
+            .collect();
+
+        assert_eq!(
+            reference_spans,
+            references(&reqfile, SOURCE, index_of("\"base_url\"") + 1, false)
+        );
+
+        // With the declaration and the keys in both environments
+        assert_eq!(
+            6,
+            references(&reqfile, SOURCE, index_of("\"base_url\"") + 1, true).len()
+        );
+    }
+
+    #[test]
+    fn references_from_environment_key() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+        let reference = index_of("{{:user_path}}") + 3;
+
+        assert_eq!(
+            vec![reference..reference + 9],
+            references(&reqfile, SOURCE, index_of("user_path ="), false)
+        );
+    }
+
+    #[test]
+    fn symbol_span_of_reference() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+        let reference = index_of("{{!api_key}}") + 3;
+
+        assert_eq!(
+            Some(reference..reference + 7),
+            symbol_span(&reqfile, SOURCE, reference + 7)
+        );
+        assert_eq!(None, symbol_span(&reqfile, SOURCE, index_of("x-api-key")));
+    }
+
+    #[test]
+    fn rename_variable() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        let edits = rename(&reqfile, SOURCE, index_of("{{:base_url}}") + 5, "root_url").unwrap();
+
+        assert_eq!(6, edits.len());
+        assert_eq!(SOURCE.replace("base_url", "root_url"), apply(edits.clone()));
+
+        let renamed = apply(edits);
+
+        assert!(parse(&Ast::from(&renamed)).is_ok());
+    }
+
+    #[test]
+    fn rename_secret() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        let edits = rename(&reqfile, SOURCE, index_of("\"api_key\"") + 1, "token").unwrap();
+
+        assert_eq!(
+            SOURCE
+                .replace("api_key", "token")
+                .replace("x-token", "x-api-key"),
+            apply(edits)
+        );
+    }
+
+    #[test]
+    fn rename_to_invalid_name() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        assert_eq!(
+            Err(RenameError::InvalidName("base url".to_string())),
+            rename(&reqfile, SOURCE, index_of("{{:base_url}}") + 3, "base url")
+        );
+    }
+
+    #[test]
+    fn rename_to_declared_name() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        assert_eq!(
+            Err(RenameError::AlreadyDeclared("user_path".to_string())),
+            rename(&reqfile, SOURCE, index_of("{{:base_url}}") + 3, "user_path")
+        );
+    }
+
+    #[test]
+    fn rename_outside_of_symbols() {
+        let reqfile = parse(&Ast::from(SOURCE)).unwrap();
+
+        assert_eq!(
+            Err(RenameError::NotRenameable),
+            rename(&reqfile, SOURCE, index_of("GET"), "verb")
+        );
+    }
+}
@@ -1,13 +1,19 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, path::Path};
 
 use reqlang_expr::prelude::*;
 
 use crate::{
-    ast::Ast,
-    errors::{ReqlangError, ResolverError},
-    parser::{parse, parse_request, parse_response},
+    ast::{Ast, AstNode},
+    errors::{ParseError, ReqlangError, ResolverError},
+    parser::{
+        ParseContext, parse_config, parse_request, parse_response, parse_with_context, resolve_path,
+    },
     span::{NO_SPAN, Spanned},
-    types::{ParsedRequestFile, ReferenceType, TemplatedRequestFile},
+    types::{
+        ParsedConfig, ParsedRequestFile, ReferenceType, TemplatedRequestFile,
+        client::ParsedConfigClient,
+        http::{HttpRequest, MultipartValue},
+    },
 };
 
 /// Template a request file string into a [TemplatedRequestFile].
@@ -18,8 +24,311 @@ pub fn template(
     secrets: &HashMap<String, String>,
     provider_values: &HashMap<String, String>, // TODO: Wire this up
 ) -> Result<TemplatedRequestFile, Vec<Spanned<ReqlangError>>> {
+    template_with_context(
+        reqfile_string,
+        env,
+        prompts,
+        secrets,
+        provider_values,
+        &ParseContext::default(),
+    )
+}
+
+/// Template a request file string into a [TemplatedRequestFile] with [ParseContext].
+pub fn template_with_context(
+    reqfile_string: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    provider_values: &HashMap<String, String>,
+    context: &ParseContext,
+) -> Result<TemplatedRequestFile, Vec<Spanned<ReqlangError>>> {
+    let (reqfile_string, parsed_reqfile) = &parse_selected(reqfile_string, context)?;
+
+    let mut templating_errors: Vec<Spanned<ReqlangError>> = vec![];
+
+    // Validate all dependency values were passed
+    {
+        let missing_dependency_values_errs = parsed_reqfile
+            .dependency_values()
+            .into_iter()
+            .filter(|value| !provider_values.contains_key(value))
+            .map(|value| ResolverError::DependencyValueNotPassed(value.clone()).into())
+            .map(|err| (err, NO_SPAN))
+            .collect::<Vec<Spanned<ReqlangError>>>();
+
+        templating_errors.extend(missing_dependency_values_errs);
+    };
+
+    // Validate all auth values were passed
+    {
+        let missing_auth_values_errs = parsed_reqfile
+            .auth_provider_values()
+            .into_iter()
+            .filter(|value| !provider_values.contains_key(value))
+            .map(|value| ResolverError::AuthValueNotPassed(value.clone()).into())
+            .map(|err| (err, NO_SPAN))
+            .collect::<Vec<Spanned<ReqlangError>>>();
+
+        templating_errors.extend(missing_auth_values_errs);
+    };
+
+    if !templating_errors.is_empty() {
+        return Err(templating_errors);
+    }
+
+    let templated_input = template_input(
+        reqfile_string,
+        parsed_reqfile,
+        env,
+        prompts,
+        secrets,
+        provider_values,
+        None,
+    )?;
+
+    let ast = Ast::from(&templated_input);
+    let (request_name, request) = ast
+        .select_request(context.request.as_deref())
+        .expect("should have a request");
+    let request = request.clone();
+    let response = ast.response_for(request_name.map(String::as_str)).cloned();
+
+    // Parse the templated request
+    let (mut request, request_span) = parse_request(&request).unwrap();
+
+    // Only a body file written in the request file is read, not a templated value starting with `< `
+    if parsed_reqfile.request().0.body_file.is_some() {
+        request.split_body_file();
+    }
+
+    if let Err(err) = resolve_body_files(&mut request, context.path.as_deref()) {
+        return Err(vec![(err, request_span)]);
+    }
+
+    // Parse the templated response
+    let response = parse_response(&response).map(|x| x.unwrap().0);
+
+    // Parse the templated auth and client config
+    let (auth, client) = match parse_config(&ast.config().cloned(), context.path.as_deref()) {
+        Some(Ok((config, _))) => (config.auth, config.client),
+        _ => (None, None),
+    };
+
+    let client = resolve_client(client, context).map_err(|errs| {
+        let span = ast
+            .config()
+            .map(|(_, span)| span.clone())
+            .unwrap_or(NO_SPAN);
+
+        errs.into_iter()
+            .map(|err| (err, span.clone()))
+            .collect::<Vec<_>>()
+    })?;
+
+    Ok(TemplatedRequestFile {
+        request,
+        response,
+        auth,
+        client,
+    })
+}
+
+/// Parse a request file string without the requests and responses that weren't selected
+///
+/// Values referenced only by other requests aren't required to template the selected request.
+fn parse_selected(
+    reqfile_string: &str,
+    context: &ParseContext,
+) -> Result<(String, ParsedRequestFile), Vec<Spanned<ReqlangError>>> {
     let ast = Ast::from(reqfile_string);
-    let parsed_reqfile = parse(&ast)?;
+    let parsed_reqfile = parse_with_context(&ast, context)?;
+
+    let selected_input = blank_unselected_blocks(reqfile_string, &ast, &parsed_reqfile);
+    let parsed_reqfile = parse_with_context(&Ast::from(&selected_input), context)?;
+
+    Ok((selected_input, parsed_reqfile))
+}
+
+/// Replace the request and response blocks that aren't the selected request and response with whitespace
+///
+/// Spans in the rest of the request file are unchanged, so errors are still reported in the right place.
+fn blank_unselected_blocks(
+    reqfile_string: &str,
+    ast: &Ast,
+    parsed_reqfile: &ParsedRequestFile,
+) -> String {
+    let selected_name = parsed_reqfile.selected_request().name.as_ref();
+
+    let mut input = reqfile_string.to_string();
+
+    for (node, span) in ast.iter() {
+        let name = match node {
+            AstNode::RequestBlock(name, _) | AstNode::ResponseBlock(name, _) => name.as_ref(),
+            _ => continue,
+        };
+
+        if name != selected_name {
+            let blank: String = input[span.clone()]
+                .chars()
+                .map(|c| match c {
+                    '\n' => "\n".to_string(),
+                    c => " ".repeat(c.len_utf8()),
+                })
+                .collect();
+
+            input.replace_range(span.clone(), &blank);
+        }
+    }
+
+    input
+}
+
+/// Resolve the templated client options, falling back to the options shared by the workspace
+///
+/// Certificate paths are resolved relative to the request file.
+pub(crate) fn resolve_client(
+    client: Option<ParsedConfigClient>,
+    context: &ParseContext,
+) -> Result<Option<ParsedConfigClient>, Vec<ReqlangError>> {
+    let shared_client = context
+        .shared_config
+        .as_ref()
+        .and_then(|shared| shared.client.clone());
+
+    let Some(mut client) = client else {
+        return Ok(shared_client);
+    };
+
+    client.resolve_paths(|path| {
+        resolve_path(path, context.path.as_deref())
+            .display()
+            .to_string()
+    });
+
+    let mut errs: Vec<ReqlangError> = client
+        .validate()
+        .into_iter()
+        .map(|message| ParseError::InvalidConfigError { message }.into())
+        .collect();
+
+    for path in client.paths() {
+        if let Err(err) = fs::metadata(path) {
+            errs.push(
+                ParseError::InvalidConfigError {
+                    message: format!("Unable to read '{path}': {err}"),
+                }
+                .into(),
+            );
+        }
+    }
+
+    if !errs.is_empty() {
+        return Err(errs);
+    }
+
+    Ok(Some(match shared_client {
+        Some(shared_client) => client.merge(&shared_client),
+        None => client,
+    }))
+}
+
+/// Resolve the body file and multipart file parts of a templated request relative to the request file
+fn resolve_body_files(
+    request: &mut HttpRequest,
+    reqfile_path: Option<&Path>,
+) -> Result<(), ReqlangError> {
+    let resolve = |path: &str| -> Result<String, ReqlangError> {
+        let resolved = resolve_path(path, reqfile_path);
+
+        match fs::metadata(&resolved) {
+            Ok(_) => Ok(resolved.display().to_string()),
+            Err(err) => Err(ParseError::InvalidBodyFile {
+                path: path.to_string(),
+                message: err.to_string(),
+            }
+            .into()),
+        }
+    };
+
+    if let Some(path) = &request.body_file {
+        request.body_file = Some(resolve(path)?);
+    }
+
+    match request.multipart_parts() {
+        Some(Ok(parts)) => {
+            let mut lines: Vec<String> = vec![];
+
+            for mut part in parts {
+                if let Some(path) = part.file() {
+                    part.value = MultipartValue::File(resolve(path)?);
+                }
+
+                lines.push(part.to_string());
+            }
+
+            request.body = Some(lines.join("\n"));
+        }
+        Some(Err(message)) => return Err(ParseError::InvalidMultipartPart(message).into()),
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Template only the config of a request file string.
+///
+/// Used to resolve values needed before the request can be templated, like
+/// the `[auth.oauth2]` config used to obtain `{{@auth.oauth2.access_token}}`.
+pub fn template_config(
+    reqfile_string: &str,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    provider_values: &HashMap<String, String>,
+    context: &ParseContext,
+) -> Result<Option<ParsedConfig>, Vec<Spanned<ReqlangError>>> {
+    let (reqfile_string, parsed_reqfile) = &parse_selected(reqfile_string, context)?;
+
+    let Some((_, config_span)) = &parsed_reqfile.config else {
+        return Ok(None);
+    };
+
+    let templated_input = template_input(
+        reqfile_string,
+        parsed_reqfile,
+        env,
+        prompts,
+        secrets,
+        provider_values,
+        Some(config_span),
+    )?;
+
+    match parse_config(
+        &Ast::from(&templated_input).config().cloned(),
+        context.path.as_deref(),
+    ) {
+        Some(Ok((config, _))) => Ok(Some(config)),
+        Some(Err(errs)) => Err(errs),
+        None => Ok(None),
+    }
+}
+
+/// Replace the template references and expressions in a request file string.
+///
+/// If `only_span` is passed, only references and expressions within that span are replaced.
+fn template_input(
+    reqfile_string: &str,
+    parsed_reqfile: &ParsedRequestFile,
+    env: Option<&str>,
+    prompts: &HashMap<String, String>,
+    secrets: &HashMap<String, String>,
+    provider_values: &HashMap<String, String>,
+    only_span: Option<&Span>,
+) -> Result<String, Vec<Spanned<ReqlangError>>> {
+    let in_span = |span: &Span| {
+        only_span.is_none_or(|only_span| only_span.start <= span.start && span.end <= only_span.end)
+    };
 
     if let Some(env) = env {
         match &parsed_reqfile.config {
@@ -42,14 +351,27 @@ pub fn template(
         if !parsed_reqfile.envs().contains(&env.to_string()) {
             return Err(vec![(
                 ResolverError::InvalidEnvError(env.to_string()).into(),
-                parsed_reqfile.config.map(|x| x.1).unwrap_or_default(),
+                parsed_reqfile
+                    .config
+                    .as_ref()
+                    .map(|x| x.1.clone())
+                    .unwrap_or_default(),
             )]);
         }
     }
 
     let mut templating_errors: Vec<Spanned<ReqlangError>> = vec![];
 
-    let reqfile: &ParsedRequestFile = &parsed_reqfile;
+    let reqfile: &ParsedRequestFile = parsed_reqfile;
+
+    // Only referenced values are required, errors for missing values are reported on the first reference
+    let ref_span = |ref_type: ReferenceType| {
+        reqfile
+            .refs
+            .iter()
+            .find(|(x, _)| *x == ref_type)
+            .map(|(_, span)| span.clone())
+    };
 
     // Validate all required prompt values were passed
     {
@@ -58,8 +380,11 @@ pub fn template(
                 .required_prompts()
                 .into_iter()
                 .filter(|prompt| !prompts.contains_key(prompt))
-                .map(|prompt| ResolverError::PromptValueNotPassed(prompt.clone()).into())
-                .map(|err| (err, NO_SPAN))
+                .filter_map(|prompt| {
+                    let span = ref_span(ReferenceType::Prompt(prompt.clone()))?;
+
+                    Some((ResolverError::PromptValueNotPassed(prompt).into(), span))
+                })
                 .collect::<Vec<Spanned<ReqlangError>>>()
         };
 
@@ -72,8 +397,11 @@ pub fn template(
             .secrets()
             .into_iter()
             .filter(|secret| !secrets.contains_key(secret))
-            .map(|secret| ResolverError::SecretValueNotPassed(secret.clone()).into())
-            .map(|err| (err, NO_SPAN))
+            .filter_map(|secret| {
+                let span = ref_span(ReferenceType::Secret(secret.clone()))?;
+
+                Some((ResolverError::SecretValueNotPassed(secret).into(), span))
+            })
             .collect::<Vec<Spanned<ReqlangError>>>();
 
         templating_errors.extend(missing_secrets_errs);
@@ -93,6 +421,7 @@ pub fn template(
         let template_refs_to_replace: Vec<(String, ReferenceType, Span)> = reqfile
             .refs
             .iter()
+            .filter(|(_, template_reference_span)| in_span(template_reference_span))
             .map(|(template_reference, template_reference_span)| {
                 (
                     format!("{template_reference}"),
@@ -109,10 +438,17 @@ pub fn template(
             None => HashMap::new(),
         };
 
+        // Every referenced secret was validated as passed, unreferenced secrets may not be
+        let passed_secrets: Vec<String> = reqfile
+            .secrets()
+            .into_iter()
+            .filter(|x| secrets.contains_key(x))
+            .collect();
+
         let mut compiler_env = CompileTimeEnv::new(
             reqfile.vars(),
             reqfile.prompts(),
-            reqfile.secrets(),
+            passed_secrets.clone(),
             provider_values.keys().cloned().collect(),
         );
 
@@ -153,16 +489,13 @@ pub fn template(
                     .collect()
             };
 
-            let secret_values: Vec<Option<String>> = reqfile
-                .secrets()
-                .iter()
-                .map(|x| secrets.get(x).cloned())
-                .collect();
+            let secret_values: Vec<String> =
+                passed_secrets.iter().map(|x| secrets[x].clone()).collect();
 
             RuntimeEnv {
                 vars: var_values.clone(),
                 prompts: prompt_values,
-                secrets: secret_values.iter().filter_map(|x| x.clone()).collect(),
+                secrets: secret_values,
                 client_context: provider_values
                     .values()
                     .cloned()
@@ -179,7 +512,23 @@ pub fn template(
 
         let mut vm = Vm::new();
 
-        for (_, ref_type, ref_span) in &template_refs_to_replace {
+        for (template_ref, ref_type, ref_span) in &template_refs_to_replace {
+            // Dependency and auth values (e.g. `auth.oauth2.access_token`) are
+            // passed in as provider values by the client
+            let passed_value_name = match ref_type {
+                ReferenceType::Dependency(name) => Some(name),
+                ReferenceType::Provider(name) if name.contains('.') => Some(name),
+                _ => None,
+            };
+
+            if let Some(name) = passed_value_name {
+                if let Some(value) = provider_values.get(name) {
+                    input = input.replace(template_ref, value);
+                }
+
+                continue;
+            }
+
             match reqlang_expr::parser::parse(&ref_type.lookup_name()) {
                 Ok(expr) => match compile(&mut (expr, ref_span.clone()), &compiler_env) {
                     Ok(bytecode) => {
@@ -234,7 +583,7 @@ pub fn template(
         }
 
         let items = &reqfile.exprs.to_vec();
-        for (expr_ref, expr_span) in items {
+        for (expr_ref, expr_span) in items.iter().filter(|(_, span)| in_span(span)) {
             match reqlang_expr::parser::parse(&format!("({expr_ref})")) {
                 Ok(expr) => match compile(&mut (expr, expr_span.clone()), &compiler_env) {
                     Ok(bytecode) => {
@@ -295,20 +644,7 @@ pub fn template(
         input
     };
 
-    let ast = Ast::from(&templated_input);
-    let request = ast.request().cloned().expect("should have a request");
-    let response = ast.response().cloned();
-
-    // Parse the templated request
-    let request = {
-        let (request, request_span) = request;
-        parse_request(&(request, request_span.clone())).unwrap().0
-    };
-
-    // Parse the templated response
-    let response = parse_response(&response).map(|x| x.unwrap().0);
-
-    Ok(TemplatedRequestFile { request, response })
+    Ok(templated_input)
 }
 
 #[cfg(test)]
@@ -316,11 +652,15 @@ mod test {
     use std::collections::HashMap;
 
     use crate::{
-        errors::{ReqlangError, ResolverError},
+        ast::Ast,
+        errors::{ParseError, ReqlangError, ResolverError},
+        parser::{ParseContext, parse_with_context},
         span::NO_SPAN,
-        templater::template,
+        templater::{template, template_config, template_with_context},
+        test_utils::write_files,
         types::{
             TemplatedRequestFile,
+            auth::{ParsedBearerAuthConfig, ParsedConfigAuth, ParsedOAuth2Config},
             http::{HttpRequest, HttpResponse, HttpStatusCode},
         },
     };
@@ -402,15 +742,20 @@ HTTP/1.1 200 OK
                     ("x-test".to_string(), "test_value_value".to_string()),
                     ("x-api-key".to_string(), "api_key_value".to_string()),
                 ],
-                body: Some("[1, 2, 3]\n\n\n".to_string())
+                body: Some("[1, 2, 3]\n\n\n".to_string()),
+                body_file: None,
             },
             response: Some(HttpResponse {
                 http_version: "1.1".into(),
                 status_code: HttpStatusCode::new(200),
                 status_text: "OK".to_string(),
                 headers: vec![],
-                body: Some("expected_response_body_value\n\n\n".to_string())
+                body: Some("expected_response_body_value\n\n\n".to_string()),
+                binary_body: None,
+                metrics: None
             }),
+            auth: None,
+            client: None,
         })
     );
 
@@ -429,7 +774,7 @@ HTTP/1.1 200 OK
         &HashMap::default(),
         Err(vec![(
             ReqlangError::ResolverError(ResolverError::SecretValueNotPassed("api_key".to_string())),
-            NO_SPAN
+            330..342
         )])
     );
 
@@ -444,7 +789,7 @@ HTTP/1.1 200 OK
             ReqlangError::ResolverError(ResolverError::PromptValueNotPassed(
                 "expected_response_body".to_string()
             )),
-            NO_SPAN
+            390..417
         )])
     );
 
@@ -481,9 +826,12 @@ HTTP/1.1 200 OK
                 target: "https://example.com/?query={{!api_key}}".to_string(),
                 http_version: "1.1".into(),
                 headers: vec![],
-                body: Some("".to_string())
+                body: Some("".to_string()),
+                body_file: None,
             },
             response: None,
+            auth: None,
+            client: None,
         })
     );
 
@@ -604,9 +952,12 @@ HTTP/1.1 200 OK
                 target: "https://example.com/?query=123".to_string(),
                 http_version: "1.1".into(),
                 headers: vec![],
-                body: Some("".to_string())
+                body: Some("".to_string()),
+                body_file: None,
             },
             response: None,
+            auth: None,
+            client: None,
         })
     );
 
@@ -635,9 +986,12 @@ HTTP/1.1 200 OK
                 target: "https://example.com/?query=456".to_string(),
                 http_version: "1.1".into(),
                 headers: vec![],
-                body: Some("".to_string())
+                body: Some("".to_string()),
+                body_file: None,
             },
             response: None,
+            auth: None,
+            client: None,
         })
     );
 
@@ -672,9 +1026,455 @@ HTTP/1.1 200 OK
                 target: "https://example.com/?query=123456".to_string(),
                 http_version: "1.1".into(),
                 headers: vec![],
-                body: Some("".to_string())
+                body: Some("".to_string()),
+                body_file: None,
+            },
+            response: None,
+            auth: None,
+            client: None,
+        })
+    );
+
+    templater_test!(
+        use_dependency_value,
+        textwrap::dedent(
+            r#"
+            ```%config
+            [[depends]]
+            name = "login"
+            path = "./login.reqlang"
+
+            [[depends.values]]
+            name = "token"
+            json = "/access_token"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            authorization: Bearer {{>login.token}}
+            ```
+            "#
+        ),
+        None,
+        HashMap::new(),
+        HashMap::new(),
+        &HashMap::from([("login.token".to_string(), "abc123".to_string())]),
+        Ok(TemplatedRequestFile {
+            request: HttpRequest {
+                verb: "GET".into(),
+                target: "https://example.com".to_string(),
+                http_version: "1.1".into(),
+                headers: vec![("authorization".to_string(), "Bearer abc123".to_string())],
+                body: Some("".to_string()),
+                body_file: None,
             },
             response: None,
+            auth: None,
+            client: None,
         })
     );
+
+    templater_test!(
+        missing_dependency_value,
+        textwrap::dedent(
+            r#"
+            ```%config
+            [[depends]]
+            name = "login"
+            path = "./login.reqlang"
+
+            [[depends.values]]
+            name = "token"
+            json = "/access_token"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            authorization: Bearer {{>login.token}}
+            ```
+            "#
+        ),
+        None,
+        HashMap::new(),
+        HashMap::new(),
+        &HashMap::default(),
+        Err(vec![(
+            ReqlangError::ResolverError(ResolverError::DependencyValueNotPassed(
+                "login.token".to_string()
+            )),
+            NO_SPAN
+        )])
+    );
+
+    templater_test!(
+        use_oauth2_access_token,
+        textwrap::dedent(
+            r#"
+            ```%config
+            [auth.oauth2]
+            access_token_url = "https://example.com/token"
+            client_id = "reqlang"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            authorization: Bearer {{@auth.oauth2.access_token}}
+            ```
+            "#
+        ),
+        None,
+        HashMap::new(),
+        HashMap::new(),
+        &HashMap::from([("auth.oauth2.access_token".to_string(), "abc123".to_string())]),
+        Ok(TemplatedRequestFile {
+            request: HttpRequest {
+                verb: "GET".into(),
+                target: "https://example.com".to_string(),
+                http_version: "1.1".into(),
+                headers: vec![("authorization".to_string(), "Bearer abc123".to_string())],
+                body: Some("".to_string()),
+                body_file: None,
+            },
+            response: None,
+            auth: Some(ParsedConfigAuth {
+                oauth2: Some(ParsedOAuth2Config {
+                    access_token_url: Some("https://example.com/token".to_string()),
+                    client_id: Some("reqlang".to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            client: None,
+        })
+    );
+
+    templater_test!(
+        missing_oauth2_access_token,
+        textwrap::dedent(
+            r#"
+            ```%config
+            [auth.oauth2]
+            access_token_url = "https://example.com/token"
+            client_id = "reqlang"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            authorization: Bearer {{@auth.oauth2.access_token}}
+            ```
+            "#
+        ),
+        None,
+        HashMap::new(),
+        HashMap::new(),
+        &HashMap::default(),
+        Err(vec![(
+            ReqlangError::ResolverError(ResolverError::AuthValueNotPassed(
+                "auth.oauth2.access_token".to_string()
+            )),
+            NO_SPAN
+        )])
+    );
+
+    templater_test!(
+        template_auth_config,
+        textwrap::dedent(
+            r#"
+            ```%config
+            secrets = ["token"]
+
+            [auth.bearer]
+            token = "{{!token}}"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            ```
+            "#
+        ),
+        None,
+        HashMap::new(),
+        HashMap::from([("token".to_string(), "abc123".to_string())]),
+        &HashMap::default(),
+        Ok(TemplatedRequestFile {
+            request: HttpRequest {
+                verb: "GET".into(),
+                target: "https://example.com".to_string(),
+                http_version: "1.1".into(),
+                headers: vec![],
+                body: Some("".to_string()),
+                body_file: None,
+            },
+            response: None,
+            auth: Some(ParsedConfigAuth {
+                bearer: Some(ParsedBearerAuthConfig {
+                    token: "abc123".to_string()
+                }),
+                ..Default::default()
+            }),
+            client: None,
+        })
+    );
+
+    #[test]
+    fn template_config_only() {
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            secrets = ["client_secret"]
+
+            [auth.oauth2]
+            access_token_url = "https://example.com/token"
+            client_id = "reqlang"
+            client_secret = "{{!client_secret}}"
+            ```
+
+            ```%request
+            GET https://example.com HTTP/1.1
+            authorization: Bearer {{@auth.oauth2.access_token}}
+            ```
+            "#,
+        );
+
+        let config = template_config(
+            &reqfile,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("client_secret".to_string(), "shh".to_string())]),
+            &HashMap::new(),
+            &ParseContext::default(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            Some("shh".to_string()),
+            config.auth.unwrap().oauth2.unwrap().client_secret
+        );
+    }
+
+    #[test]
+    fn template_selected_request() {
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            secrets = ["api_key", "admin_key"]
+            ```
+
+            ```%request list-users
+            GET https://example.com/users HTTP/1.1
+            x-api-key: {{!api_key}}
+            ```
+
+            ```%request delete-user
+            DELETE https://example.com/users/1 HTTP/1.1
+            x-api-key: {{!admin_key}}
+            ```
+
+            ```%response delete-user
+            HTTP/1.1 204 No Content
+            ```
+            "#,
+        );
+
+        // Secrets only referenced by other requests aren't required
+        let templated_reqfile = template_with_context(
+            &reqfile,
+            None,
+            &HashMap::new(),
+            &HashMap::from([("admin_key".to_string(), "abc123".to_string())]),
+            &HashMap::new(),
+            &ParseContext {
+                request: Some("delete-user".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            HttpRequest {
+                verb: "DELETE".into(),
+                target: "https://example.com/users/1".to_string(),
+                http_version: "1.1".into(),
+                headers: vec![("x-api-key".to_string(), "abc123".to_string())],
+                body: Some("".to_string()),
+                body_file: None,
+            },
+            templated_reqfile.request
+        );
+
+        assert_eq!(
+            Some(HttpStatusCode::new(204)),
+            templated_reqfile.response.map(|x| x.status_code)
+        );
+    }
+
+    #[test]
+    fn blank_unselected_blocks() {
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            secrets = ["api_key"]
+            ```
+
+            ```%request list-users
+            GET https://example.com/users HTTP/1.1
+            x-api-key: {{!api_key}}
+            ```
+
+            ```%response list-users
+            HTTP/1.1 200 OK
+            ```
+
+            ```%request delete-user
+            DELETE https://example.com/users/1 HTTP/1.1
+            ```
+            "#,
+        );
+
+        let ast = Ast::from(&reqfile);
+        let parsed_reqfile = parse_with_context(
+            &ast,
+            &ParseContext {
+                request: Some("delete-user".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let blanked = super::blank_unselected_blocks(&reqfile, &ast, &parsed_reqfile);
+
+        // Spans in the selected request are unchanged
+        assert_eq!(reqfile.len(), blanked.len());
+        assert_eq!(reqfile.lines().count(), blanked.lines().count());
+        assert!(!blanked.contains("list-users"));
+        assert!(blanked.contains("```%config\nsecrets = [\"api_key\"]\n```"));
+        assert!(
+            blanked.contains(
+                "```%request delete-user\nDELETE https://example.com/users/1 HTTP/1.1\n```"
+            )
+        );
+    }
+
+    #[test]
+    fn template_body_file_relative_to_request_file() {
+        let dir = write_files("template-body_file", &[("user.json", "{}")]);
+
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            prompts = [{ name = "file" }]
+            ```
+
+            ```%request
+            POST https://example.com/users HTTP/1.1
+
+            < ./{{?file}}
+            ```
+            "#,
+        );
+
+        let template_file = |file: &str| {
+            template_with_context(
+                &reqfile,
+                None,
+                &HashMap::from([("file".to_string(), file.to_string())]),
+                &HashMap::new(),
+                &HashMap::new(),
+                &ParseContext {
+                    path: Some(dir.join("request.reqlang")),
+                    ..Default::default()
+                },
+            )
+        };
+
+        assert_eq!(
+            Some(dir.join("user.json").display().to_string()),
+            template_file("user.json").unwrap().request.body_file
+        );
+
+        let errs = template_file("missing.json").unwrap_err();
+
+        assert!(matches!(
+            &errs[0].0,
+            ReqlangError::ParseError(ParseError::InvalidBodyFile { path, .. }) if path == "./missing.json"
+        ));
+    }
+
+    #[test]
+    fn templated_body_is_not_read_as_body_file() {
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            prompts = [{ name = "body" }]
+            ```
+
+            ```%request
+            POST https://example.com/users HTTP/1.1
+
+            {{?body}}
+            ```
+            "#,
+        );
+
+        let templated_reqfile = template(
+            &reqfile,
+            None,
+            &HashMap::from([("body".to_string(), "< /etc/passwd".to_string())]),
+            &HashMap::new(),
+            &HashMap::new(),
+        )
+        .unwrap();
+
+        assert_eq!(None, templated_reqfile.request.body_file);
+        assert_eq!(
+            Some("< /etc/passwd\n\n".to_string()),
+            templated_reqfile.request.body
+        );
+    }
+
+    #[test]
+    fn template_multipart_files_relative_to_request_file() {
+        let dir = write_files(
+            "template-multipart",
+            &[("ada.png", [0x89, 0x50, 0x4e, 0x47])],
+        );
+
+        let reqfile = textwrap::dedent(
+            r#"
+            ```%config
+            prompts = [{ name = "name" }]
+            ```
+
+            ```%request
+            POST https://example.com/users HTTP/1.1
+            content-type: multipart/form-data
+
+            name={{?name}}
+            avatar=@./ada.png;type=image/png
+            ```
+            "#,
+        );
+
+        let templated_reqfile = template_with_context(
+            &reqfile,
+            None,
+            &HashMap::from([("name".to_string(), "Ada".to_string())]),
+            &HashMap::new(),
+            &HashMap::new(),
+            &ParseContext {
+                path: Some(dir.join("request.reqlang")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some(format!(
+                "name=Ada\navatar=@{};type=image/png",
+                dir.join("ada.png").display()
+            )),
+            templated_reqfile.request.body
+        );
+    }
 }
@@ -0,0 +1,26 @@
+use std::{fs, path::PathBuf};
+
+/// An empty directory under the system temp dir, named after the test using it
+///
+/// The directory is removed and recreated, so files from an earlier run don't leak into the test.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("reqlang-{name}"));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::canonicalize(dir).unwrap()
+}
+
+/// Write files, with paths relative to a new [temp_dir], returning the directory
+pub fn write_files<C: AsRef<[u8]>>(name: &str, files: &[(&str, C)]) -> PathBuf {
+    let dir = temp_dir(name);
+
+    for (file_name, contents) in files {
+        let path = dir.join(file_name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    dir
+}
@@ -0,0 +1,337 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::http::HttpRequest;
+
+/// Value used in place of credentials when exporting redacted requests
+pub const REDACTED: &str = "<redacted>";
+
+/// Authentication declared in the config
+///
+/// ```toml
+/// [auth.oauth2]
+/// grant = "client_credentials"
+/// access_token_url = "https://example.com/oauth/token"
+/// client_id = "{{?client_id}}"
+/// client_secret = "{{!client_secret}}"
+///
+/// [auth.bearer]
+/// token = "{{@auth.oauth2.access_token}}"
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedConfigAuth {
+    pub oauth2: Option<ParsedOAuth2Config>,
+    pub basic: Option<ParsedBasicAuthConfig>,
+    pub bearer: Option<ParsedBearerAuthConfig>,
+    pub api_key: Option<ParsedApiKeyAuthConfig>,
+}
+
+impl ParsedConfigAuth {
+    /// Apply the `basic`, `bearer` and `api_key` auth to a request
+    ///
+    /// If `redact` is true the credentials are replaced with [REDACTED].
+    pub fn apply(&self, request: &HttpRequest, redact: bool) -> HttpRequest {
+        let mut request = request.clone();
+
+        let credential = |value: &str| {
+            if redact {
+                REDACTED.to_string()
+            } else {
+                value.to_string()
+            }
+        };
+
+        if let Some(basic) = &self.basic {
+            let encoded = STANDARD.encode(format!(
+                "{}:{}",
+                basic.username,
+                basic.password.as_deref().unwrap_or_default()
+            ));
+
+            set_header(
+                &mut request,
+                "authorization",
+                format!("Basic {}", credential(&encoded)),
+            );
+        }
+
+        if let Some(bearer) = &self.bearer {
+            set_header(
+                &mut request,
+                "authorization",
+                format!("Bearer {}", credential(&bearer.token)),
+            );
+        }
+
+        if let Some(api_key) = &self.api_key {
+            match api_key.placement.clone().unwrap_or_default() {
+                ApiKeyPlacement::Header => {
+                    set_header(&mut request, &api_key.name, credential(&api_key.value));
+                }
+                ApiKeyPlacement::Query => {
+                    let param = form_urlencoded::Serializer::new(String::new())
+                        .append_pair(&api_key.name, &credential(&api_key.value))
+                        .finish();
+
+                    let separator = if request.target.contains('?') {
+                        '&'
+                    } else {
+                        '?'
+                    };
+
+                    request.target = format!("{}{separator}{param}", request.target);
+                }
+            }
+        }
+
+        request
+    }
+
+    /// The provider value names (e.g. `auth.oauth2.access_token`) set by the declared authentication
+    pub fn provider_values(&self) -> Vec<String> {
+        let mut names = vec![];
+
+        if self.oauth2.is_some() {
+            names.push(OAUTH2_ACCESS_TOKEN.to_string());
+        }
+
+        names
+    }
+}
+
+/// Provider value name for the access token obtained using the `[auth.oauth2]` config
+pub const OAUTH2_ACCESS_TOKEN: &str = "auth.oauth2.access_token";
+
+/// OAuth2 grant used to obtain an access token
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum OAuth2Grant {
+    #[default]
+    ClientCredentials,
+    Password,
+    RefreshToken,
+}
+
+impl OAuth2Grant {
+    /// The `grant_type` sent to the token endpoint
+    pub fn grant_type(&self) -> &'static str {
+        match self {
+            OAuth2Grant::ClientCredentials => "client_credentials",
+            OAuth2Grant::Password => "password",
+            OAuth2Grant::RefreshToken => "refresh_token",
+        }
+    }
+}
+
+/// The `[auth.oauth2]` config
+///
+/// Required fields are optional here so missing fields are reported as
+/// [crate::errors::ParseError::MissingOAuth2ConfigField] by the parser.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedOAuth2Config {
+    /// Defaults to `client_credentials`
+    pub grant: Option<OAuth2Grant>,
+    pub access_token_url: Option<String>,
+    pub authorize_url: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// Space separated scopes to request
+    pub scopes: Option<String>,
+    /// Required by the `password` grant
+    pub username: Option<String>,
+    /// Required by the `password` grant
+    pub password: Option<String>,
+    /// Required by the `refresh_token` grant
+    pub refresh_token: Option<String>,
+}
+
+impl ParsedOAuth2Config {
+    /// The grant, defaulting to `client_credentials`
+    pub fn grant(&self) -> OAuth2Grant {
+        self.grant.clone().unwrap_or_default()
+    }
+
+    /// The names of required fields that are missing for the grant
+    pub fn missing_fields(&self) -> Vec<String> {
+        let mut required = vec![
+            ("access_token_url", &self.access_token_url),
+            ("client_id", &self.client_id),
+        ];
+
+        match self.grant() {
+            OAuth2Grant::ClientCredentials => {}
+            OAuth2Grant::Password => {
+                required.push(("username", &self.username));
+                required.push(("password", &self.password));
+            }
+            OAuth2Grant::RefreshToken => {
+                required.push(("refresh_token", &self.refresh_token));
+            }
+        }
+
+        required
+            .into_iter()
+            .filter(|(_, value)| value.is_none())
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+}
+
+/// The `[auth.basic]` config
+///
+/// Sets the `authorization` header to `Basic <base64 username:password>`
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedBasicAuthConfig {
+    pub username: String,
+    pub password: Option<String>,
+}
+
+/// The `[auth.bearer]` config
+///
+/// Sets the `authorization` header to `Bearer <token>`
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedBearerAuthConfig {
+    pub token: String,
+}
+
+/// Where an API key is placed in the request
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum ApiKeyPlacement {
+    #[default]
+    Header,
+    Query,
+}
+
+/// The `[auth.api_key]` config
+///
+/// Sets a header (default) or query parameter `name` to `value`
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedApiKeyAuthConfig {
+    pub name: String,
+    pub value: String,
+    pub placement: Option<ApiKeyPlacement>,
+}
+
+/// Set a header on the request, replacing any existing header with the same name
+fn set_header(request: &mut HttpRequest, name: &str, value: String) {
+    request
+        .headers
+        .retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+
+    request.headers.push((name.to_string(), value));
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::types::http::HttpRequest;
+
+    use super::{
+        ApiKeyPlacement, ParsedApiKeyAuthConfig, ParsedBasicAuthConfig, ParsedBearerAuthConfig,
+        ParsedConfigAuth,
+    };
+
+    fn request() -> HttpRequest {
+        HttpRequest::get(
+            "https://example.com/?page=1",
+            "1.1",
+            vec![("Authorization".to_string(), "Bearer old".to_string())],
+        )
+    }
+
+    #[test]
+    fn apply_basic_auth() {
+        let auth = ParsedConfigAuth {
+            basic: Some(ParsedBasicAuthConfig {
+                username: "user".to_string(),
+                password: Some("pass".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![(
+                "authorization".to_string(),
+                "Basic dXNlcjpwYXNz".to_string()
+            )],
+            auth.apply(&request(), false).headers
+        );
+    }
+
+    #[test]
+    fn apply_bearer_auth() {
+        let auth = ParsedConfigAuth {
+            bearer: Some(ParsedBearerAuthConfig {
+                token: "abc123".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![("authorization".to_string(), "Bearer abc123".to_string())],
+            auth.apply(&request(), false).headers
+        );
+    }
+
+    #[test]
+    fn apply_api_key_header() {
+        let auth = ParsedConfigAuth {
+            api_key: Some(ParsedApiKeyAuthConfig {
+                name: "x-api-key".to_string(),
+                value: "abc123".to_string(),
+                placement: None,
+            }),
+            ..Default::default()
+        };
+
+        let request = auth.apply(&request(), false);
+
+        assert_eq!(
+            Some(&("x-api-key".to_string(), "abc123".to_string())),
+            request.headers.last()
+        );
+    }
+
+    #[test]
+    fn apply_api_key_query() {
+        let auth = ParsedConfigAuth {
+            api_key: Some(ParsedApiKeyAuthConfig {
+                name: "api key".to_string(),
+                value: "abc&123".to_string(),
+                placement: Some(ApiKeyPlacement::Query),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            "https://example.com/?page=1&api+key=abc%26123",
+            auth.apply(&request(), false).target
+        );
+    }
+
+    #[test]
+    fn apply_redacted_auth() {
+        let auth = ParsedConfigAuth {
+            basic: Some(ParsedBasicAuthConfig {
+                username: "user".to_string(),
+                password: Some("pass".to_string()),
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![("authorization".to_string(), "Basic <redacted>".to_string())],
+            auth.apply(&request(), true).headers
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use std::{fmt::Display, path::Path, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Options for the HTTP client sending the request
+///
+/// ```toml
+/// [client]
+/// timeout = "30s"
+/// connect_timeout = "5s"
+/// follow_redirects = true
+/// max_redirects = 5
+/// proxy = "http://localhost:8080"
+/// ca_cert = "./certs/ca.pem"
+/// client_cert = "./certs/client.pem"
+/// insecure = false
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(deny_unknown_fields)]
+pub struct ParsedConfigClient {
+    /// Time allowed for the whole request, e.g. `30s` or `500ms`
+    #[ts(as = "Option<String>")]
+    pub timeout: Option<ClientDuration>,
+    /// Time allowed to connect to the server
+    #[ts(as = "Option<String>")]
+    pub connect_timeout: Option<ClientDuration>,
+    /// Follow redirect responses, defaults to `true`
+    pub follow_redirects: Option<bool>,
+    /// The most redirects to follow, defaults to 10
+    pub max_redirects: Option<usize>,
+    /// URL of the proxy to send requests through
+    pub proxy: Option<String>,
+    /// Path to PEM encoded certificates to trust, in addition to the system's
+    pub ca_cert: Option<String>,
+    /// Path to a PEM encoded client certificate and private key
+    pub client_cert: Option<String>,
+    /// Accept invalid TLS certificates
+    pub insecure: Option<bool>,
+}
+
+impl ParsedConfigClient {
+    /// Options set here, falling back to the options in `fallback`
+    ///
+    /// `follow_redirects` and `max_redirects` are merged as a pair, so setting
+    /// `max_redirects` here follows redirects even if `fallback` doesn't.
+    pub fn merge(&self, fallback: &ParsedConfigClient) -> ParsedConfigClient {
+        let (follow_redirects, max_redirects) = match (self.follow_redirects, self.max_redirects) {
+            (None, None) => (fallback.follow_redirects, fallback.max_redirects),
+            (None, Some(max_redirects)) => (Some(true), Some(max_redirects)),
+            (Some(false), _) => (Some(false), None),
+            (Some(true), max_redirects) => (Some(true), max_redirects.or(fallback.max_redirects)),
+        };
+
+        ParsedConfigClient {
+            timeout: self.timeout.or(fallback.timeout),
+            connect_timeout: self.connect_timeout.or(fallback.connect_timeout),
+            follow_redirects,
+            max_redirects,
+            proxy: self.proxy.clone().or_else(|| fallback.proxy.clone()),
+            ca_cert: self.ca_cert.clone().or_else(|| fallback.ca_cert.clone()),
+            client_cert: self
+                .client_cert
+                .clone()
+                .or_else(|| fallback.client_cert.clone()),
+            insecure: self.insecure.or(fallback.insecure),
+        }
+    }
+
+    /// The `ca_cert` and `client_cert` paths
+    pub fn paths(&self) -> Vec<&str> {
+        [&self.ca_cert, &self.client_cert]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Resolve the `ca_cert` and `client_cert` paths with `resolve`
+    pub fn resolve_paths(&mut self, resolve: impl Fn(&str) -> String) {
+        for path in [&mut self.ca_cert, &mut self.client_cert]
+            .into_iter()
+            .flatten()
+        {
+            *path = resolve(path);
+        }
+    }
+
+    /// Resolve the `ca_cert` and `client_cert` paths relative to `dir`
+    pub fn with_paths_relative_to(mut self, dir: &Path) -> ParsedConfigClient {
+        self.resolve_paths(|path| dir.join(path).display().to_string());
+
+        self
+    }
+
+    /// Validate the options that don't depend on the file system
+    ///
+    /// Template references in the `proxy` aren't validated until they're templated.
+    pub fn validate(&self) -> Vec<String> {
+        let mut messages = vec![];
+
+        if self.follow_redirects == Some(false) && self.max_redirects.is_some() {
+            messages.push("max_redirects can't be set when follow_redirects is false".to_string());
+        }
+
+        if let Some(proxy) = &self.proxy
+            && !proxy.contains("{{")
+            && let Err(err) = reqwest::Proxy::all(proxy)
+        {
+            messages.push(format!("Invalid proxy '{proxy}': {err}"));
+        }
+
+        messages
+    }
+}
+
+/// A duration written as a number followed by `ms`, `s`, or `m`, e.g. `30s`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClientDuration(pub Duration);
+
+impl FromStr for ClientDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!("Invalid duration '{s}', expected a number followed by ms, s, or m (e.g. 30s)")
+        };
+
+        let s = s.trim();
+
+        let (value, to_duration): (&str, fn(u64) -> Option<Duration>) =
+            if let Some(value) = s.strip_suffix("ms") {
+                (value, |millis| Some(Duration::from_millis(millis)))
+            } else if let Some(value) = s.strip_suffix('s') {
+                (value, |secs| Some(Duration::from_secs(secs)))
+            } else if let Some(value) = s.strip_suffix('m') {
+                (value, |minutes| {
+                    minutes.checked_mul(60).map(Duration::from_secs)
+                })
+            } else {
+                return Err(invalid());
+            };
+
+        let value = value.parse::<u64>().map_err(|_| invalid())?;
+
+        to_duration(value)
+            .map(ClientDuration)
+            .ok_or_else(|| format!("Duration '{s}' is too long"))
+    }
+}
+
+impl Display for ClientDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = self.0.as_millis();
+
+        if millis.is_multiple_of(1000) {
+            write!(f, "{}s", millis / 1000)
+        } else {
+            write!(f, "{millis}ms")
+        }
+    }
+}
+
+impl Serialize for ClientDuration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ClientDuration {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::{ClientDuration, ParsedConfigClient};
+
+    #[rstest]
+    #[case("500ms", Duration::from_millis(500), "500ms")]
+    #[case("1000ms", Duration::from_secs(1), "1s")]
+    #[case("30s", Duration::from_secs(30), "30s")]
+    #[case("2m", Duration::from_secs(120), "120s")]
+    #[case(" 5s ", Duration::from_secs(5), "5s")]
+    fn parse_duration(#[case] input: &str, #[case] expected: Duration, #[case] display: &str) {
+        let duration: ClientDuration = input.parse().unwrap();
+
+        assert_eq!(ClientDuration(expected), duration);
+        assert_eq!(display, duration.to_string());
+    }
+
+    #[rstest]
+    #[case("30")]
+    #[case("s")]
+    #[case("-1s")]
+    #[case("1.5s")]
+    #[case("1h")]
+    fn parse_invalid_duration(#[case] input: &str) {
+        assert_eq!(
+            Err(format!(
+                "Invalid duration '{input}', expected a number followed by ms, s, or m (e.g. 30s)"
+            )),
+            input.parse::<ClientDuration>()
+        );
+    }
+
+    #[test]
+    fn parse_too_long_duration() {
+        let input = format!("{}m", u64::MAX);
+
+        assert_eq!(
+            Err(format!("Duration '{input}' is too long")),
+            input.parse::<ClientDuration>()
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let client: ParsedConfigClient = toml::from_str(
+            r#"
+            timeout = "30s"
+            connect_timeout = "500ms"
+            max_redirects = 3
+            proxy = "http://localhost:8080"
+            insecure = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ParsedConfigClient {
+                timeout: Some(ClientDuration(Duration::from_secs(30))),
+                connect_timeout: Some(ClientDuration(Duration::from_millis(500))),
+                max_redirects: Some(3),
+                proxy: Some("http://localhost:8080".to_string()),
+                insecure: Some(true),
+                ..Default::default()
+            },
+            client
+        );
+    }
+
+    #[test]
+    fn deserialize_unknown_option() {
+        let err = toml::from_str::<ParsedConfigClient>(r#"timout = "30s""#).unwrap_err();
+
+        assert_eq!(Some(0..6), err.span());
+    }
+
+    #[test]
+    fn merge() {
+        let client = ParsedConfigClient {
+            timeout: Some(ClientDuration(Duration::from_secs(5))),
+            ..Default::default()
+        };
+
+        let fallback = ParsedConfigClient {
+            timeout: Some(ClientDuration(Duration::from_secs(30))),
+            insecure: Some(true),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            ParsedConfigClient {
+                timeout: Some(ClientDuration(Duration::from_secs(5))),
+                insecure: Some(true),
+                ..Default::default()
+            },
+            client.merge(&fallback)
+        );
+    }
+
+    #[rstest]
+    #[case((None, Some(3)), (Some(false), None), (Some(true), Some(3)))]
+    #[case((Some(false), None), (None, Some(3)), (Some(false), None))]
+    #[case((Some(true), None), (None, Some(3)), (Some(true), Some(3)))]
+    #[case((None, None), (Some(false), None), (Some(false), None))]
+    fn merge_redirects(
+        #[case] client: (Option<bool>, Option<usize>),
+        #[case] fallback: (Option<bool>, Option<usize>),
+        #[case] expected: (Option<bool>, Option<usize>),
+    ) {
+        let client = ParsedConfigClient {
+            follow_redirects: client.0,
+            max_redirects: client.1,
+            ..Default::default()
+        };
+
+        let fallback = ParsedConfigClient {
+            follow_redirects: fallback.0,
+            max_redirects: fallback.1,
+            ..Default::default()
+        };
+
+        let merged = client.merge(&fallback);
+
+        assert_eq!(expected, (merged.follow_redirects, merged.max_redirects));
+    }
+
+    #[test]
+    fn paths_relative_to() {
+        let client = ParsedConfigClient {
+            ca_cert: Some("certs/ca.pem".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            Some("/workspace/certs/ca.pem".to_string()),
+            client
+                .with_paths_relative_to(Path::new("/workspace"))
+                .ca_cert
+        );
+    }
+
+    #[rstest]
+    #[case(ParsedConfigClient::default(), vec![])]
+    #[case(
+        ParsedConfigClient { follow_redirects: Some(false), max_redirects: Some(3), ..Default::default() },
+        vec!["max_redirects can't be set when follow_redirects is false"]
+    )]
+    #[case(
+        ParsedConfigClient { proxy: Some("{{:proxy}}".to_string()), ..Default::default() },
+        vec![]
+    )]
+    fn validate(#[case] client: ParsedConfigClient, #[case] expected: Vec<&str>) {
+        assert_eq!(expected, client.validate());
+    }
+
+    #[test]
+    fn validate_invalid_proxy() {
+        let client = ParsedConfigClient {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+
+        let messages = client.validate();
+
+        assert_eq!(1, messages.len());
+        assert!(messages[0].starts_with("Invalid proxy 'not a url': "));
+    }
+}
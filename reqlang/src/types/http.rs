@@ -1,5 +1,6 @@
 use std::fmt::{self, Display};
 
+use base64::{Engine, engine::general_purpose::STANDARD};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -16,6 +17,34 @@ impl HttpVerb {
     pub fn post() -> Self {
         "POST".into()
     }
+
+    pub fn put() -> Self {
+        "PUT".into()
+    }
+
+    pub fn patch() -> Self {
+        "PATCH".into()
+    }
+
+    pub fn delete() -> Self {
+        "DELETE".into()
+    }
+
+    pub fn head() -> Self {
+        "HEAD".into()
+    }
+
+    pub fn options() -> Self {
+        "OPTIONS".into()
+    }
+
+    pub fn connect() -> Self {
+        "CONNECT".into()
+    }
+
+    pub fn trace() -> Self {
+        "TRACE".into()
+    }
 }
 
 impl From<String> for HttpVerb {
@@ -38,6 +67,9 @@ impl Display for HttpVerb {
 
 /// Http version used in [HttpRequest] and [HttpResponse]
 ///
+/// Requests can be sent as `HTTP/1.0`, `HTTP/1.1`, or `HTTP/2`. HTTP/2 requests over `http://` are
+/// sent with prior knowledge (h2c), and over `https://` HTTP/2 is negotiated with ALPN.
+///
 /// ```
 /// let http_version: reqlang::types::http::HttpVersion = "1.1".into();
 /// ```
@@ -46,9 +78,22 @@ impl Display for HttpVerb {
 pub struct HttpVersion(String);
 
 impl HttpVersion {
+    pub fn one_point_zero() -> Self {
+        "1.0".into()
+    }
+
     pub fn one_point_one() -> Self {
         "1.1".into()
     }
+
+    pub fn two() -> Self {
+        "2".into()
+    }
+
+    /// If this is HTTP/2
+    pub fn is_two(&self) -> bool {
+        matches!(self.0.as_str(), "2" | "2.0")
+    }
 }
 
 impl Default for HttpVersion {
@@ -84,6 +129,10 @@ pub struct HttpRequest {
     pub http_version: HttpVersion,
     pub headers: Vec<(String, String)>,
     pub body: Option<String>,
+    /// Path of a file to send as the body, written as `< ./payload.json` in place of the body
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub body_file: Option<String>,
 }
 impl HttpRequest {
     pub fn new(
@@ -99,6 +148,7 @@ impl HttpRequest {
             http_version: http_version.into(),
             headers,
             body,
+            body_file: None,
         }
     }
 
@@ -136,6 +186,133 @@ impl HttpRequest {
 
         self
     }
+
+    /// Move a body written as `< ./payload.json` to [HttpRequest::body_file]
+    ///
+    /// Only called on requests as they're written in the request file, so a templated value
+    /// starting with `< ` is sent as the body instead of being read as a file.
+    pub fn split_body_file(&mut self) {
+        let path = self
+            .body
+            .as_deref()
+            .and_then(|body| body.trim().strip_prefix("< "))
+            .map(str::trim)
+            .filter(|path| !path.is_empty() && !path.contains('\n'))
+            .map(str::to_string);
+
+        if path.is_some() {
+            self.body = None;
+            self.body_file = path;
+        }
+    }
+
+    /// If the body is written as [MultipartPart]s
+    ///
+    /// The `content-type` is `multipart/form-data` without a boundary, which is set when the request is sent.
+    pub fn is_multipart(&self) -> bool {
+        self.headers.iter().any(|(key, value)| {
+            key.eq_ignore_ascii_case("content-type")
+                && value.trim().eq_ignore_ascii_case("multipart/form-data")
+        })
+    }
+
+    /// The parts of a multipart body, one per line
+    pub fn multipart_parts(&self) -> Option<Result<Vec<MultipartPart>, String>> {
+        if !self.is_multipart() {
+            return None;
+        }
+
+        Some(
+            self.body
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(MultipartPart::parse)
+                .collect(),
+        )
+    }
+}
+
+/// A part of a `multipart/form-data` body, written like a `curl -F` argument
+///
+/// ```
+/// use reqlang::types::http::{MultipartPart, MultipartValue};
+///
+/// let part = MultipartPart::parse("avatar=@./ada.png;type=image/png").unwrap();
+///
+/// assert_eq!("avatar", part.name);
+/// assert_eq!(MultipartValue::File("./ada.png".to_string()), part.value);
+/// assert_eq!(Some("image/png".to_string()), part.content_type);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultipartPart {
+    pub name: String,
+    pub value: MultipartValue,
+    pub content_type: Option<String>,
+}
+
+/// [MultipartPart] value
+#[derive(Clone, Debug, PartialEq)]
+pub enum MultipartValue {
+    /// `name=value`
+    Text(String),
+    /// `name=@path`
+    File(String),
+}
+
+impl MultipartPart {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("Expected `name=value` or `name=@path` but got `{line}`"))?;
+
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(format!("Part is missing a name: `{line}`"));
+        }
+
+        let (value, content_type) = match value.rsplit_once(";type=") {
+            Some((value, content_type)) => (value, Some(content_type.trim().to_string())),
+            None => (value, None),
+        };
+
+        let value = match value.strip_prefix('@') {
+            Some(path) => MultipartValue::File(path.trim().to_string()),
+            None => MultipartValue::Text(value.to_string()),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            value,
+            content_type,
+        })
+    }
+
+    /// Path of the file to send as the part, if it's a file part
+    pub fn file(&self) -> Option<&str> {
+        match &self.value {
+            MultipartValue::File(path) => Some(path),
+            MultipartValue::Text(_) => None,
+        }
+    }
+}
+
+impl Display for MultipartPart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            MultipartValue::Text(value) => write!(f, "{}={value}", self.name)?,
+            MultipartValue::File(path) => write!(f, "{}=@{path}", self.name)?,
+        }
+
+        if let Some(content_type) = &self.content_type {
+            write!(f, ";type={content_type}")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Display for HttpRequest {
@@ -155,10 +332,13 @@ impl Display for HttpRequest {
             ))
         };
 
-        let body = self
-            .body
-            .clone()
-            .and_then(|x| if x.is_empty() { None } else { Some(x) });
+        let body = match &self.body_file {
+            Some(path) => Some(format!("< {path}")),
+            None => self
+                .body
+                .clone()
+                .and_then(|x| if x.is_empty() { None } else { Some(x) }),
+        };
 
         let the_rest = match (&headers, &body) {
             (Some(headers), Some(body)) => format!("{headers}\n{body}"),
@@ -194,6 +374,11 @@ impl HttpStatusCode {
     pub fn is_valid(status_code: u16) -> bool {
         (100..=599).contains(&status_code)
     }
+
+    /// If the status code is in the 2xx range
+    pub fn is_success(&self) -> bool {
+        (200..=299).contains(&self.0)
+    }
 }
 
 impl TryFrom<u16> for HttpStatusCode {
@@ -233,7 +418,90 @@ pub struct HttpResponse {
     pub status_code: HttpStatusCode,
     pub status_text: String,
     pub headers: Vec<(String, String)>,
+    /// The body, if it's valid UTF-8 text
     pub body: Option<String>,
+    /// The raw bytes of a fetched body that isn't valid UTF-8 (e.g. an image), serialized as base64
+    #[serde(default)]
+    #[ts(as = "Option<String>")]
+    pub binary_body: Option<Base64Bytes>,
+    /// Timing and size of a fetched response
+    #[serde(default)]
+    pub metrics: Option<HttpResponseMetrics>,
+}
+
+impl HttpResponse {
+    /// Set the body from the raw bytes of a fetched response
+    ///
+    /// Bytes that aren't valid UTF-8 are kept in [HttpResponse::binary_body].
+    pub fn set_body_bytes(&mut self, bytes: Vec<u8>) {
+        match String::from_utf8(bytes) {
+            Ok(body) => {
+                self.body = Some(body);
+                self.binary_body = None;
+            }
+            Err(err) => {
+                self.body = None;
+                self.binary_body = Some(Base64Bytes(err.into_bytes()));
+            }
+        }
+    }
+
+    /// The raw bytes of the body
+    pub fn body_bytes(&self) -> Vec<u8> {
+        match (&self.binary_body, &self.body) {
+            (Some(bytes), _) => bytes.0.clone(),
+            (None, Some(body)) => body.as_bytes().to_vec(),
+            (None, None) => vec![],
+        }
+    }
+}
+
+/// Timing and size of a fetched response
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct HttpResponseMetrics {
+    /// Milliseconds from sending the request to receiving the whole body
+    pub total_ms: f64,
+    /// Milliseconds from sending the request to receiving the response headers
+    pub time_to_first_byte_ms: f64,
+    /// Size of the body in bytes
+    pub size: usize,
+    /// Number of redirects followed
+    pub redirects: usize,
+}
+
+impl Display for HttpResponseMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Total: {:.1} ms", self.total_ms)?;
+        writeln!(
+            f,
+            "Time to first byte: {:.1} ms",
+            self.time_to_first_byte_ms
+        )?;
+        writeln!(f, "Size: {} bytes", self.size)?;
+        write!(f, "Redirects: {}", self.redirects)
+    }
+}
+
+/// Bytes serialized as base64
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Base64Bytes(pub Vec<u8>);
+
+impl Serialize for Base64Bytes {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(&self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64Bytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+
+        STANDARD
+            .decode(encoded)
+            .map(Self)
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 impl Display for HttpResponse {
@@ -367,4 +635,165 @@ mod tests {
             HttpStatusCode::new(status_code);
         }
     }
+
+    mod http_request {
+        use rstest::rstest;
+
+        use crate::types::http::HttpRequest;
+
+        #[rstest]
+        #[case("< ./payload.json\n\n", Some("./payload.json"))]
+        #[case("<  /tmp/image.png ", Some("/tmp/image.png"))]
+        #[case("<root />\n\n", None)]
+        #[case("< ./a.json\n< ./b.json", None)]
+        #[case("< ", None)]
+        #[case("", None)]
+        fn split_body_file(#[case] body: &str, #[case] expected: Option<&str>) {
+            let mut request = HttpRequest::post("https://example.com", "1.1", vec![], Some(body));
+
+            request.split_body_file();
+
+            assert_eq!(expected, request.body_file.as_deref());
+            assert_eq!(expected.is_none(), request.body.as_deref() == Some(body));
+        }
+
+        #[test]
+        fn display_body_file() {
+            let mut request = HttpRequest::post("https://example.com", "1.1", vec![], None);
+            request.body_file = Some("./payload.json".to_string());
+
+            assert_eq!(
+                "POST https://example.com HTTP/1.1\n\n< ./payload.json",
+                request.to_string()
+            );
+        }
+    }
+
+    mod multipart_part {
+        use rstest::rstest;
+
+        use crate::types::http::{HttpRequest, MultipartPart, MultipartValue};
+
+        #[rstest]
+        #[case("name=Ada", "name", MultipartValue::Text("Ada".to_string()), None)]
+        #[case("name=", "name", MultipartValue::Text("".to_string()), None)]
+        #[case("equation=a=b", "equation", MultipartValue::Text("a=b".to_string()), None)]
+        #[case(
+            "meta={};type=application/json",
+            "meta",
+            MultipartValue::Text("{}".to_string()),
+            Some("application/json")
+        )]
+        #[case("file=@./a.txt", "file", MultipartValue::File("./a.txt".to_string()), None)]
+        #[case(
+            "avatar=@./ada.png;type=image/png",
+            "avatar",
+            MultipartValue::File("./ada.png".to_string()),
+            Some("image/png")
+        )]
+        fn parse(
+            #[case] line: &str,
+            #[case] name: &str,
+            #[case] value: MultipartValue,
+            #[case] content_type: Option<&str>,
+        ) {
+            let part = MultipartPart::parse(line).unwrap();
+
+            assert_eq!(name, part.name);
+            assert_eq!(value, part.value);
+            assert_eq!(content_type.map(str::to_string), part.content_type);
+            assert_eq!(line, part.to_string());
+        }
+
+        #[rstest]
+        #[case("name")]
+        #[case("=value")]
+        fn parse_invalid(#[case] line: &str) {
+            assert!(MultipartPart::parse(line).is_err());
+        }
+
+        #[test]
+        fn multipart_parts() {
+            let request = HttpRequest::post(
+                "/",
+                "1.1",
+                vec![(
+                    "Content-Type".to_string(),
+                    "multipart/form-data".to_string(),
+                )],
+                Some("name=Ada\n\navatar=@./ada.png\n\n"),
+            );
+
+            assert_eq!(
+                Some(Ok(vec![
+                    MultipartPart::parse("name=Ada").unwrap(),
+                    MultipartPart::parse("avatar=@./ada.png").unwrap()
+                ])),
+                request.multipart_parts()
+            );
+        }
+
+        #[test]
+        fn multipart_parts_with_boundary_is_raw_body() {
+            let request = HttpRequest::post(
+                "/",
+                "1.1",
+                vec![(
+                    "content-type".to_string(),
+                    "multipart/form-data; boundary=abc".to_string(),
+                )],
+                Some("--abc\n"),
+            );
+
+            assert_eq!(None, request.multipart_parts());
+        }
+    }
+
+    mod http_response {
+        use pretty_assertions::assert_eq;
+
+        use crate::types::http::{Base64Bytes, HttpResponse, HttpStatusCode, HttpVersion};
+
+        fn response() -> HttpResponse {
+            HttpResponse {
+                http_version: HttpVersion::one_point_one(),
+                status_code: HttpStatusCode::new(200),
+                status_text: "OK".to_string(),
+                headers: vec![],
+                body: None,
+                binary_body: None,
+                metrics: None,
+            }
+        }
+
+        #[test]
+        fn text_body_bytes() {
+            let mut response = response();
+            response.set_body_bytes(b"Hello".to_vec());
+
+            assert_eq!(Some("Hello".to_string()), response.body);
+            assert_eq!(None, response.binary_body);
+            assert_eq!(b"Hello".to_vec(), response.body_bytes());
+        }
+
+        #[test]
+        fn binary_body_bytes_serialized_as_base64() {
+            let mut response = response();
+            response.set_body_bytes(vec![0x89, 0x50, 0x4e, 0x47]);
+
+            assert_eq!(None, response.body);
+            assert_eq!(
+                Some(Base64Bytes(vec![0x89, 0x50, 0x4e, 0x47])),
+                response.binary_body
+            );
+
+            let json = serde_json::to_value(&response).unwrap();
+
+            assert_eq!("iVBORw==", json["binary_body"]);
+            assert_eq!(
+                response,
+                serde_json::from_value::<HttpResponse>(json).unwrap()
+            );
+        }
+    }
 }
@@ -0,0 +1,130 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A check for a problem in a request file that doesn't stop it from being used
+///
+/// Lints are named by stable codes (e.g. `unused_value`) used to set their
+/// level in the config or with the CLI.
+///
+/// ```toml
+/// [lints]
+/// missing_content_type = "allow"
+/// secret_in_url = "deny"
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Lint {
+    /// A variable, prompt, secret, or dependency value is declared but not referenced
+    UnusedValue,
+    /// A request has a body but no `content-type` header
+    MissingContentType,
+    /// A request target uses HTTP instead of HTTPS in a `prod` environment
+    InsecureProdTarget,
+    /// A secret is referenced in a request target, where it may be logged
+    SecretInUrl,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnusedValue,
+        Lint::MissingContentType,
+        Lint::InsecureProdTarget,
+        Lint::SecretInUrl,
+    ];
+
+    /// The stable code naming the lint
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lint::UnusedValue => "unused_value",
+            Lint::MissingContentType => "missing_content_type",
+            Lint::InsecureProdTarget => "insecure_prod_target",
+            Lint::SecretInUrl => "secret_in_url",
+        }
+    }
+
+    /// The level used when it isn't set in the config or with the CLI
+    pub fn default_level(&self) -> LintLevel {
+        match self {
+            Lint::UnusedValue => LintLevel::Warn,
+            Lint::MissingContentType => LintLevel::Info,
+            Lint::InsecureProdTarget => LintLevel::Warn,
+            Lint::SecretInUrl => LintLevel::Warn,
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .into_iter()
+            .find(|lint| lint.code() == s)
+            .ok_or_else(|| {
+                let codes: Vec<&str> = Lint::ALL.iter().map(Lint::code).collect();
+
+                format!("Unknown lint '{s}', expected one of: {}", codes.join(", "))
+            })
+    }
+}
+
+/// How a [Lint] is reported
+///
+/// Findings from denied lints are errors, allowed lints aren't checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    Allow,
+    Hint,
+    Info,
+    Warn,
+    Deny,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{Lint, LintLevel};
+
+    #[test]
+    fn lint_codes_round_trip() {
+        for lint in Lint::ALL {
+            assert_eq!(Ok(lint), lint.code().parse::<Lint>());
+        }
+    }
+
+    #[test]
+    fn unknown_lint_code() {
+        assert_eq!(
+            Err("Unknown lint 'unused', expected one of: unused_value, missing_content_type, insecure_prod_target, secret_in_url".to_string()),
+            "unused".parse::<Lint>()
+        );
+    }
+
+    #[test]
+    fn deserialize_lint_levels() {
+        let levels: HashMap<Lint, LintLevel> =
+            toml::from_str("unused_value = \"allow\"\nsecret_in_url = \"deny\"").unwrap();
+
+        assert_eq!(
+            HashMap::from([
+                (Lint::UnusedValue, LintLevel::Allow),
+                (Lint::SecretInUrl, LintLevel::Deny),
+            ]),
+            levels
+        );
+    }
+}
@@ -5,13 +5,18 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use ts_rs::TS;
 
-use crate::span::Spanned;
+use crate::{extractor::ResponseExtractor, parser::ParseContext, span::Spanned};
+use auth::ParsedConfigAuth;
+use client::{ClientDuration, ParsedConfigClient};
 
+pub mod auth;
+pub mod client;
 pub mod http;
+pub mod lint;
 
 /// Template reference in a request file
 ///
-/// Syntax: `{{:variable}}`, `{{?prompt}}`, `{{!secret}}`
+/// Syntax: `{{:variable}}`, `{{?prompt}}`, `{{!secret}}`, `{{@provider}}`, `{{>dependency.value}}`
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum ReferenceType {
@@ -19,6 +24,7 @@ pub enum ReferenceType {
     Prompt(String),
     Secret(String),
     Provider(String),
+    Dependency(String),
     Unknown(String),
 }
 
@@ -29,6 +35,7 @@ impl ReferenceType {
             ReferenceType::Prompt(name) => format!("?{name}"),
             ReferenceType::Secret(name) => format!("!{name}"),
             ReferenceType::Provider(name) => format!("@{name}"),
+            ReferenceType::Dependency(name) => format!(">{name}"),
             ReferenceType::Unknown(_) => panic!("Invalid reference type"),
         }
     }
@@ -44,12 +51,24 @@ impl Display for ReferenceType {
                 ReferenceType::Prompt(name) => format!("?{name}"),
                 ReferenceType::Secret(name) => format!("!{name}"),
                 ReferenceType::Provider(name) => format!("@{name}"),
+                ReferenceType::Dependency(name) => format!(">{name}"),
                 ReferenceType::Unknown(_) => panic!("Invalid reference type"),
             }
         )
     }
 }
 
+/// A request in a request file with the response it's paired with
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedRequest {
+    /// Name of the request block, if it's named: ```` ```%request name ````
+    pub name: Option<String>,
+    pub request: Spanned<HttpRequest>,
+    /// The response block with the same name
+    pub response: Option<Spanned<HttpResponse>>,
+}
+
 /// Request file parsed from a string input
 ///
 /// All template references are still in place
@@ -57,14 +76,50 @@ impl Display for ReferenceType {
 #[ts(export)]
 pub struct ParsedRequestFile {
     pub config: Option<Spanned<ParsedConfig>>,
-    pub request: Spanned<HttpRequest>,
-    pub response: Option<Spanned<HttpResponse>>,
+    /// All requests in the request file, in order
+    pub requests: Vec<ParsedRequest>,
+    /// Index of the selected request in `requests`, see [crate::parser::ParseContext::request]
+    pub selected: usize,
     pub refs: Vec<Spanned<ReferenceType>>,
     pub exprs: Vec<Spanned<String>>,
     pub comments: Vec<Spanned<String>>,
 }
 
 impl ParsedRequestFile {
+    /// The selected request with the response it's paired with
+    pub fn selected_request(&self) -> &ParsedRequest {
+        &self.requests[self.selected]
+    }
+
+    /// The selected request
+    pub fn request(&self) -> &Spanned<HttpRequest> {
+        &self.selected_request().request
+    }
+
+    /// The response paired with the selected request
+    pub fn response(&self) -> Option<&Spanned<HttpResponse>> {
+        self.selected_request().response.as_ref()
+    }
+
+    /// If the config or the selected request and response reference the value
+    ///
+    /// Values only referenced by other requests aren't needed for the selected request.
+    pub fn is_referenced(&self, reference: &ReferenceType) -> bool {
+        let selected_spans = [
+            self.config.as_ref().map(|(_, span)| span),
+            Some(&self.request().1),
+            self.response().map(|(_, span)| span),
+        ];
+
+        self.refs.iter().any(|(x, span)| {
+            x == reference
+                && selected_spans
+                    .iter()
+                    .flatten()
+                    .any(|selected| selected.start <= span.start && span.end <= selected.end)
+        })
+    }
+
     /// The variable names declared in the config
     pub fn vars(&self) -> Vec<String> {
         self.config
@@ -115,11 +170,8 @@ impl ParsedRequestFile {
         let mut all_prompts_map: HashMap<String, String> = HashMap::new();
 
         for prompt in all_prompts.iter() {
-            if prompt.default.is_some() {
-                all_prompts_map.insert(
-                    prompt.name.clone(),
-                    prompt.default.as_ref().unwrap().clone(),
-                );
+            if let Some(default) = &prompt.default {
+                all_prompts_map.insert(prompt.name.clone(), default.clone());
             }
         }
 
@@ -155,6 +207,49 @@ impl ParsedRequestFile {
             .map(|(config, _)| config.secrets())
             .unwrap_or_default()
     }
+
+    /// The dependency value names (`dependency.value`) declared in the config
+    pub fn dependency_values(&self) -> Vec<String> {
+        self.config
+            .as_ref()
+            .map(|(config, _)| config.dependency_values())
+            .unwrap_or_default()
+    }
+
+    /// The auth provider values (e.g. `auth.oauth2.access_token`) referenced in the request file
+    pub fn auth_provider_values(&self) -> Vec<String> {
+        let declared = self
+            .config
+            .as_ref()
+            .and_then(|(config, _)| config.auth.as_ref())
+            .map(|auth| auth.provider_values())
+            .unwrap_or_default();
+
+        declared
+            .into_iter()
+            .filter(|name| {
+                self.refs
+                    .iter()
+                    .any(|(reference, _)| *reference == ReferenceType::Provider(name.clone()))
+            })
+            .collect()
+    }
+
+    /// The values to capture from the response declared in the config
+    pub fn captures(&self) -> Vec<ParsedConfigCapture> {
+        self.config
+            .as_ref()
+            .and_then(|(config, _)| config.captures.clone())
+            .unwrap_or_default()
+    }
+
+    /// The options for the response assertion declared in the config
+    pub fn assert_options(&self) -> ParsedConfigAssert {
+        self.config
+            .as_ref()
+            .and_then(|(config, _)| config.assert.clone())
+            .unwrap_or_default()
+    }
 }
 
 /// A parsed variable definition
@@ -174,6 +269,47 @@ pub struct ParsedConfigPrompt {
     pub default: Option<String>,
 }
 
+/// A parsed dependency on another request file
+///
+/// The dependency is executed before the request file and its declared
+/// values are extracted from the response.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedConfigDependency {
+    pub name: String,
+    /// Path to the request file, relative to the request file declaring the dependency
+    pub path: String,
+    pub values: Option<Vec<ParsedConfigCapture>>,
+}
+
+/// A named value extracted from a response
+///
+/// Used by dependency values and captures.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedConfigCapture {
+    pub name: String,
+    #[serde(flatten)]
+    pub extractor: ResponseExtractor,
+}
+
+/// Options for comparing the actual response to the expected response
+///
+/// ```toml
+/// [assert]
+/// allow_extra_fields = true
+/// max_latency = "500ms"
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ParsedConfigAssert {
+    /// Allow fields in actual JSON objects that aren't in the expected JSON body
+    pub allow_extra_fields: Option<bool>,
+    /// The longest the whole response can take to be received, e.g. `500ms`
+    #[ts(as = "Option<String>")]
+    pub max_latency: Option<ClientDuration>,
+}
+
 /// Request file config parsed from a string input
 ///
 /// All template references are still in place
@@ -190,7 +326,22 @@ pub struct ParsedConfig {
     pub prompts: Option<Vec<ParsedConfigPrompt>>,
     /// The secret names declared in the config
     pub secrets: Option<Vec<String>>,
-    pub auth: Option<HashMap<String, HashMap<String, String>>>,
+    /// Authentication used to obtain provider values (e.g. `{{@auth.oauth2.access_token}}`)
+    pub auth: Option<ParsedConfigAuth>,
+    /// Request files to execute before this one
+    pub depends: Option<Vec<ParsedConfigDependency>>,
+    /// Values to capture from the response
+    pub captures: Option<Vec<ParsedConfigCapture>>,
+    /// Options for the response assertion
+    pub assert: Option<ParsedConfigAssert>,
+    /// Options for the HTTP client sending the request
+    pub client: Option<ParsedConfigClient>,
+    /// Paths to TOML files to import `vars`, `envs`, `prompts`, and `secrets` from
+    ///
+    /// Paths are relative to the request file
+    pub imports: Option<Vec<String>>,
+    /// Levels for lints, overriding their defaults
+    pub lints: Option<HashMap<lint::Lint, lint::LintLevel>>,
 }
 
 impl ParsedConfig {
@@ -298,6 +449,90 @@ impl ParsedConfig {
             None => vec![],
         }
     }
+
+    /// The dependency value names declared, formatted as `dependency.value`
+    pub fn dependency_values(&self) -> Vec<String> {
+        self.depends
+            .as_ref()
+            .map(|depends| {
+                depends
+                    .iter()
+                    .flat_map(|dependency| {
+                        dependency
+                            .values
+                            .iter()
+                            .flatten()
+                            .map(|value| format!("{}.{}", dependency.name, value.name))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Merge declarations shared by a workspace in to the config
+    ///
+    /// Only the shared variables, prompts, and secrets that are referenced in
+    /// `refs` and aren't already declared are merged. The shared environments
+    /// are always merged, with values in the config taking precedence.
+    pub fn merge_shared(&mut self, shared: &ParsedConfig, refs: &[ReferenceType]) {
+        let vars = self.vars();
+        let prompts = self.prompts();
+        let secrets = self.secrets();
+
+        let shared_vars: Vec<&ParsedConfigVariable> = shared
+            .vars
+            .iter()
+            .flatten()
+            .filter(|var| !vars.contains(&var.name))
+            .filter(|var| refs.contains(&ReferenceType::Variable(var.name.clone())))
+            .collect();
+
+        if !shared_vars.is_empty() {
+            self.vars
+                .get_or_insert_default()
+                .extend(shared_vars.iter().map(|var| (*var).clone()));
+        }
+
+        if let Some(shared_envs) = &shared.envs {
+            let envs = self.envs.get_or_insert_default();
+
+            for (env_name, shared_values) in shared_envs {
+                let values = envs.entry(env_name.clone()).or_default();
+
+                for var in &shared_vars {
+                    if let Some(value) = shared_values.get(&var.name) {
+                        values
+                            .entry(var.name.clone())
+                            .or_insert_with(|| value.clone());
+                    }
+                }
+            }
+        }
+
+        let shared_prompts: Vec<ParsedConfigPrompt> = shared
+            .prompts
+            .iter()
+            .flatten()
+            .filter(|prompt| !prompts.contains(&prompt.name))
+            .filter(|prompt| refs.contains(&ReferenceType::Prompt(prompt.name.clone())))
+            .cloned()
+            .collect();
+
+        if !shared_prompts.is_empty() {
+            self.prompts.get_or_insert_default().extend(shared_prompts);
+        }
+
+        let shared_secrets: Vec<String> = shared
+            .secrets()
+            .into_iter()
+            .filter(|secret| !secrets.contains(secret))
+            .filter(|secret| refs.contains(&ReferenceType::Secret(secret.clone())))
+            .collect();
+
+        if !shared_secrets.is_empty() {
+            self.secrets.get_or_insert_default().extend(shared_secrets);
+        }
+    }
 }
 
 /// Parameters sent from the client to execute a request.
@@ -313,6 +548,39 @@ pub struct RequestParamsFromClient {
     pub prompts: HashMap<String, String>,
     pub secrets: HashMap<String, String>,
     pub provider_values: HashMap<String, String>,
+    /// URI of the request file, used to resolve paths and the workspace
+    #[serde(default)]
+    #[ts(optional)]
+    pub uri: Option<String>,
+    /// Name of the request to execute when the request file has more than one
+    #[serde(default)]
+    #[ts(optional)]
+    pub request: Option<String>,
+}
+
+impl RequestParamsFromClient {
+    /// The [ParseContext] of the request file, sharing the declarations of its workspace
+    pub fn parse_context(&self) -> ParseContext {
+        let path = self
+            .uri
+            .as_deref()
+            .and_then(|uri| reqwest::Url::parse(uri).ok())
+            .and_then(|uri| uri.to_file_path().ok());
+
+        let context = path
+            .map(|path| {
+                ParseContext::for_path(&path).unwrap_or(ParseContext {
+                    path: Some(path),
+                    ..Default::default()
+                })
+            })
+            .unwrap_or_default();
+
+        ParseContext {
+            request: self.request.clone(),
+            ..context
+        }
+    }
 }
 
 impl From<Value> for RequestParamsFromClient {
@@ -373,6 +641,13 @@ impl From<Value> for RequestParamsFromClient {
 
         let provider_values: HashMap<String, String> = HashMap::default();
 
+        let string = |key: &str| {
+            params_value
+                .get(key)
+                .and_then(|x| x.as_str())
+                .map(|x| x.to_string())
+        };
+
         RequestParamsFromClient {
             reqfile,
             env,
@@ -380,6 +655,8 @@ impl From<Value> for RequestParamsFromClient {
             prompts,
             secrets,
             provider_values,
+            uri: string("uri"),
+            request: string("request"),
         }
     }
 }
@@ -431,7 +708,7 @@ impl From<ParsedRequestFile> for ParseResult {
             optional_prompts,
             default_prompt_values,
             secrets,
-            request: value.clone().request.0,
+            request: value.request().0.clone(),
             full: value,
         }
     }
@@ -443,6 +720,22 @@ impl From<ParsedRequestFile> for ParseResult {
 pub struct TemplatedRequestFile {
     pub request: HttpRequest,
     pub response: Option<HttpResponse>,
+    /// Templated auth config applied to the request when it's fetched or exported
+    pub auth: Option<ParsedConfigAuth>,
+    /// Templated client options used when the request is fetched
+    pub client: Option<ParsedConfigClient>,
+}
+
+impl TemplatedRequestFile {
+    /// The request with the `basic`, `bearer` and `api_key` auth applied
+    ///
+    /// If `redact` is true the credentials are replaced with [auth::REDACTED].
+    pub fn authenticated_request(&self, redact: bool) -> HttpRequest {
+        match &self.auth {
+            Some(auth) => auth.apply(&self.request, redact),
+            None => self.request.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -451,10 +744,12 @@ mod tests {
         use std::{collections::HashMap, vec};
 
         use crate::{
+            ast::Ast,
+            parser::{ParseContext, parse_with_context},
             span::NO_SPAN,
             types::{
                 ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, ParsedRequestFile,
-                ReferenceType, http::HttpRequest,
+                ReferenceType,
             },
         };
 
@@ -472,11 +767,17 @@ mod tests {
                         }]),
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -495,11 +796,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -514,8 +821,8 @@ mod tests {
         fn get_prompt_names_when_config_undefined() {
             let reqfile = ParsedRequestFile {
                 config: None,
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -536,11 +843,17 @@ mod tests {
                         prompts: None,
                         secrets: Some(vec!["secret_name".to_owned()]),
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -559,11 +872,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -578,8 +897,8 @@ mod tests {
         fn get_secret_names_when_config_undefined() {
             let reqfile = ParsedRequestFile {
                 config: None,
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -612,11 +931,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -642,11 +967,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -670,11 +1001,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -698,11 +1035,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -717,8 +1060,8 @@ mod tests {
         fn get_envs_when_config_is_missing() {
             let reqfile = ParsedRequestFile {
                 config: None,
-                request: (HttpRequest::get("/", "1.1", vec![]), NO_SPAN),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![],
                 exprs: vec![],
                 comments: vec![],
@@ -742,18 +1085,17 @@ mod tests {
                         prompts: None,
                         secrets: None,
                         auth: None,
+                        depends: None,
+                        captures: None,
+                        assert: None,
+                        client: None,
+                        imports: None,
+                        lints: None,
                     },
                     NO_SPAN,
                 )),
-                request: (
-                    HttpRequest::get(
-                        "/",
-                        "1.1",
-                        vec![("x-foo".to_string(), "{{:foo}}".to_string())],
-                    ),
-                    NO_SPAN,
-                ),
-                response: None,
+                requests: vec![],
+                selected: 0,
                 refs: vec![(ReferenceType::Variable("foo".to_string()), NO_SPAN)],
                 exprs: vec![],
                 comments: vec![],
@@ -764,6 +1106,39 @@ mod tests {
                 reqfile.env("test")
             );
         }
+
+        #[test]
+        fn referenced_by_selected_request() {
+            let reqfile = textwrap::dedent(
+                r#"
+                ```%config
+                secrets = ["api_key", "admin_key"]
+                ```
+
+                ```%request list-users
+                GET https://example.com/users HTTP/1.1
+                x-api-key: {{!api_key}}
+                ```
+
+                ```%request delete-user
+                DELETE https://example.com/users/1 HTTP/1.1
+                x-api-key: {{!admin_key}}
+                ```
+                "#,
+            );
+
+            let reqfile = parse_with_context(
+                &Ast::from(&reqfile),
+                &ParseContext {
+                    request: Some("delete-user".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert!(reqfile.is_referenced(&ReferenceType::Secret("admin_key".to_string())));
+            assert!(!reqfile.is_referenced(&ReferenceType::Secret("api_key".to_string())));
+        }
     }
 
     mod request_display {
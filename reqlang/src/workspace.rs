@@ -0,0 +1,506 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    errors::{SecretProviderError, WorkspaceError},
+    secrets::{SecretProvider, SecretProviderConfig},
+    types::{ParsedConfig, ParsedConfigPrompt, ParsedConfigVariable, client::ParsedConfigClient},
+};
+
+/// File name of the workspace manifest
+pub const WORKSPACE_MANIFEST: &str = "reqlang.toml";
+
+/// File extension of request files
+pub const REQUEST_FILE_EXTENSION: &str = "reqlang";
+
+/// Declarations shared by the request files in a workspace
+///
+/// ```toml
+/// secrets = ["api_key"]
+///
+/// [[vars]]
+/// name = "base_url"
+///
+/// [envs.dev]
+/// base_url = "https://dev.example.com"
+///
+/// [envs.prod]
+/// base_url = "https://example.com"
+///
+/// [[secret_providers]]
+/// type = "dotenv"
+/// path = ".env"
+///
+/// [client]
+/// timeout = "30s"
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct WorkspaceManifest {
+    /// The variable names shared with request files
+    pub vars: Option<Vec<ParsedConfigVariable>>,
+    /// Environments available to every request file
+    pub envs: Option<HashMap<String, HashMap<String, String>>>,
+    /// The prompt names shared with request files
+    pub prompts: Option<Vec<ParsedConfigPrompt>>,
+    /// The secret names shared with request files
+    pub secrets: Option<Vec<String>>,
+    /// Where to get secret values that weren't passed, in order of precedence
+    pub secret_providers: Option<Vec<SecretProviderConfig>>,
+    /// Client options for request files that don't set them
+    ///
+    /// Paths are relative to the workspace root.
+    pub client: Option<ParsedConfigClient>,
+}
+
+impl FromStr for WorkspaceManifest {
+    type Err = WorkspaceError;
+
+    /// Parse a manifest from a string, validating the variables are defined in each environment
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let manifest: Self = toml::from_str(input)
+            .map_err(|err| WorkspaceError::InvalidManifest(err.message().to_string()))?;
+
+        if let Some(message) = manifest
+            .client
+            .as_ref()
+            .and_then(|client| client.validate().into_iter().next())
+        {
+            return Err(WorkspaceError::InvalidManifest(message));
+        }
+
+        let config = manifest.shared_config();
+
+        let mut env_names = config.envs();
+        env_names.sort();
+
+        for var in config.vars() {
+            for env_name in &env_names {
+                let defined = config
+                    .env(env_name)
+                    .is_some_and(|values| values.contains_key(&var));
+
+                if !defined {
+                    return Err(WorkspaceError::VariableUndefinedInEnvironment(
+                        var,
+                        env_name.clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+impl WorkspaceManifest {
+    /// The declarations as a [ParsedConfig] to share with request files
+    ///
+    /// See [ParsedConfig::merge_shared]
+    pub fn shared_config(&self) -> ParsedConfig {
+        ParsedConfig {
+            vars: self.vars.clone(),
+            envs: self.envs.clone(),
+            prompts: self.prompts.clone(),
+            secrets: self.secrets.clone(),
+            client: self.client.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A directory of request files with a [WORKSPACE_MANIFEST]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Workspace {
+    /// The directory containing the manifest
+    pub root: PathBuf,
+    pub manifest: WorkspaceManifest,
+}
+
+impl Workspace {
+    /// Load the workspace whose manifest is in the `root` directory
+    pub fn load(root: impl AsRef<Path>) -> Result<Self, WorkspaceError> {
+        let root = root.as_ref();
+        let manifest_path = root.join(WORKSPACE_MANIFEST);
+
+        let input = fs::read_to_string(&manifest_path).map_err(|err| {
+            WorkspaceError::ReadError(manifest_path.display().to_string(), err.to_string())
+        })?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            manifest: input.parse()?,
+        })
+    }
+
+    /// Find the workspace containing `path`, a request file or directory
+    ///
+    /// The directory of `path` and its ancestors are searched for a [WORKSPACE_MANIFEST].
+    pub fn discover(path: impl AsRef<Path>) -> Result<Option<Self>, WorkspaceError> {
+        let path = fs::canonicalize(path.as_ref()).unwrap_or_else(|_| path.as_ref().to_path_buf());
+
+        let start = if path.is_dir() {
+            Some(path.as_path())
+        } else {
+            path.parent()
+        };
+
+        for dir in start.into_iter().flat_map(Path::ancestors) {
+            if dir.join(WORKSPACE_MANIFEST).is_file() {
+                return Self::load(dir).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The declarations to share with the request files in the workspace
+    ///
+    /// Client certificate paths are resolved relative to the workspace root.
+    pub fn shared_config(&self) -> ParsedConfig {
+        let mut config = self.manifest.shared_config();
+
+        config.client = config
+            .client
+            .map(|client| client.with_paths_relative_to(&self.root));
+
+        config
+    }
+
+    /// The secret providers configured in the manifest
+    ///
+    /// Paths are relative to the workspace root.
+    pub fn secret_providers(&self) -> Result<Vec<Box<dyn SecretProvider>>, SecretProviderError> {
+        self.manifest
+            .secret_providers
+            .iter()
+            .flatten()
+            .map(|config| config.provider(&self.root))
+            .collect()
+    }
+
+    /// The request files in the workspace
+    pub fn request_files(&self) -> Vec<PathBuf> {
+        discover_request_files(&self.root)
+    }
+}
+
+/// Find the request files in a directory and its subdirectories, sorted by path
+///
+/// Hidden directories (e.g. `.git`) are skipped.
+pub fn discover_request_files(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+            if path.is_dir() {
+                if !is_hidden {
+                    dirs.push(path);
+                }
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == REQUEST_FILE_EXTENSION)
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, str::FromStr, time::Duration};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        errors::{ParseError, ReqlangError, WorkspaceError},
+        parser::ParseContext,
+        secrets::resolve_secrets,
+        templater::template_with_context,
+        test_utils::write_files,
+        types::{
+            ReferenceType,
+            client::{ClientDuration, ParsedConfigClient},
+            http::HttpRequest,
+        },
+    };
+
+    use super::{Workspace, WorkspaceManifest, discover_request_files};
+
+    const MANIFEST: &str = r#"
+secrets = ["api_key"]
+
+[[vars]]
+name = "base_url"
+
+[[vars]]
+name = "unused"
+default = "value"
+
+[envs.dev]
+base_url = "https://dev.example.com"
+
+[envs.prod]
+base_url = "https://example.com"
+"#;
+
+    #[test]
+    fn discover_workspace_from_request_file() {
+        let dir = write_files(
+            "workspace-discover_workspace_from_request_file",
+            &[
+                ("reqlang.toml", MANIFEST),
+                ("users/get.reqlang", "```%request\nGET / HTTP/1.1\n```"),
+            ],
+        );
+
+        let workspace = Workspace::discover(dir.join("users/get.reqlang"))
+            .unwrap()
+            .expect("should find the workspace");
+
+        assert_eq!(dir, workspace.root);
+        assert_eq!(
+            Some(vec!["api_key".to_string()]),
+            workspace.manifest.secrets
+        );
+    }
+
+    #[test]
+    fn discover_request_files_recursively() {
+        let dir = write_files(
+            "workspace-discover_request_files_recursively",
+            &[
+                ("reqlang.toml", MANIFEST),
+                ("b.reqlang", ""),
+                ("a/c.reqlang", ""),
+                ("a/notes.md", ""),
+                (".hidden/d.reqlang", ""),
+            ],
+        );
+
+        assert_eq!(
+            vec![dir.join("a/c.reqlang"), dir.join("b.reqlang")],
+            discover_request_files(&dir)
+        );
+    }
+
+    #[test]
+    fn secret_providers_relative_to_workspace() {
+        let dir = write_files(
+            "workspace-secret_providers_relative_to_workspace",
+            &[
+                (
+                    "reqlang.toml",
+                    format!(
+                        "{MANIFEST}\n[[secret_providers]]\ntype = \"dotenv\"\npath = \"secrets/.env\"\n"
+                    ),
+                ),
+                ("secrets/.env", "api_key=abc123\n".to_string()),
+            ],
+        );
+
+        let workspace = Workspace::load(&dir).unwrap();
+
+        let providers = workspace.secret_providers().unwrap();
+
+        assert_eq!(
+            Ok(HashMap::from([(
+                "api_key".to_string(),
+                "abc123".to_string()
+            )])),
+            resolve_secrets(&["api_key".to_string()], &HashMap::new(), &providers)
+        );
+    }
+
+    #[test]
+    fn manifest_variable_undefined_in_environment() {
+        assert_eq!(
+            Err(WorkspaceError::VariableUndefinedInEnvironment(
+                "base_url".to_string(),
+                "prod".to_string()
+            )),
+            WorkspaceManifest::from_str(
+                r#"
+                [[vars]]
+                name = "base_url"
+
+                [envs.dev]
+                base_url = "https://dev.example.com"
+
+                [envs.prod]
+                "#
+            )
+        );
+    }
+
+    #[test]
+    fn template_with_workspace_declarations() {
+        let manifest = WorkspaceManifest::from_str(MANIFEST).unwrap();
+
+        let reqfile = template_with_context(
+            "```%request\nGET {{:base_url}}/users HTTP/1.1\nx-api-key: {{!api_key}}\n```",
+            Some("prod"),
+            &HashMap::new(),
+            &HashMap::from([("api_key".to_string(), "abc123".to_string())]),
+            &HashMap::new(),
+            &ParseContext {
+                path: None,
+                shared_config: Some(manifest.shared_config()),
+                request: None,
+            },
+        );
+
+        assert_eq!(
+            HttpRequest::get(
+                "https://example.com/users",
+                "1.1",
+                vec![("x-api-key".to_string(), "abc123".to_string())]
+            ),
+            reqfile.unwrap().request
+        );
+    }
+
+    #[test]
+    fn request_file_declarations_take_precedence() {
+        let manifest = WorkspaceManifest::from_str(MANIFEST).unwrap();
+
+        let reqfile = template_with_context(
+            &textwrap::dedent(
+                r#"
+                ```%config
+                [[vars]]
+                name = "base_url"
+
+                [envs.dev]
+                base_url = "http://localhost:3000"
+
+                [envs.prod]
+                base_url = "https://example.com"
+                ```
+
+                ```%request
+                GET {{:base_url}}/users HTTP/1.1
+                ```
+                "#,
+            ),
+            Some("dev"),
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &ParseContext {
+                path: None,
+                shared_config: Some(manifest.shared_config()),
+                request: None,
+            },
+        );
+
+        assert_eq!(
+            "http://localhost:3000/users",
+            reqfile.unwrap().request.target
+        );
+    }
+
+    #[test]
+    fn undeclared_references_are_still_undefined() {
+        let manifest = WorkspaceManifest::from_str(MANIFEST).unwrap();
+
+        let reqfile = template_with_context(
+            "```%request\nGET {{:other_url}} HTTP/1.1\n```",
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &ParseContext {
+                path: None,
+                shared_config: Some(manifest.shared_config()),
+                request: None,
+            },
+        );
+
+        assert_eq!(
+            Err(vec![(
+                ReqlangError::ParseError(ParseError::UndefinedReferenceError(
+                    ReferenceType::Variable("other_url".to_string())
+                )),
+                16..30
+            )]),
+            reqfile
+        );
+    }
+
+    #[test]
+    fn client_options_fall_back_to_workspace() {
+        let dir = write_files(
+            "workspace-client_options_fall_back_to_workspace",
+            &[
+                (
+                    "reqlang.toml",
+                    "[client]\ntimeout = \"30s\"\nca_cert = \"certs/ca.pem\"\ninsecure = true",
+                ),
+                ("certs/ca.pem", ""),
+                (
+                    "users/get.reqlang",
+                    "```%config\n[client]\ntimeout = \"5s\"\n```\n\n```%request\nGET https://example.com HTTP/1.1\n```",
+                ),
+            ],
+        );
+
+        let path = dir.join("users/get.reqlang");
+        let workspace = Workspace::discover(&path).unwrap().unwrap();
+
+        let reqfile = template_with_context(
+            &fs::read_to_string(&path).unwrap(),
+            None,
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &ParseContext {
+                path: Some(path.clone()),
+                shared_config: Some(workspace.shared_config()),
+                request: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some(ParsedConfigClient {
+                timeout: Some(ClientDuration(Duration::from_secs(5))),
+                ca_cert: Some(dir.join("certs/ca.pem").display().to_string()),
+                insecure: Some(true),
+                ..Default::default()
+            }),
+            reqfile.client
+        );
+    }
+
+    #[test]
+    fn manifest_invalid_client_options() {
+        assert_eq!(
+            Err(WorkspaceError::InvalidManifest(
+                "max_redirects can't be set when follow_redirects is false".to_string()
+            )),
+            WorkspaceManifest::from_str("[client]\nfollow_redirects = false\nmax_redirects = 3")
+        );
+    }
+}
@@ -74,7 +74,7 @@ export class ReqlangCodeLensProvider implements CodeLensProvider {
      * Get the request span from the parsed reqfile.
      * This will be used to position the lens above the request in the request file.
      */
-    const [_, requestSpan] = reqFile.request;
+    const [_, requestSpan] = reqFile.requests[reqFile.selected].request;
 
     const requestLensRange = new Range(
       document.positionAt(requestSpan.start),
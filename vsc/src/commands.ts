@@ -332,6 +332,10 @@ export const openMdnHttpDocsSpecs = () => {
 type RunRequestArgs = {
   prompts?: Record<string, string>;
   secrets?: Record<string, string>;
+  /**
+   * Name of the request to run when the request file has more than one
+   */
+  request?: string;
 };
 
 /**
@@ -438,15 +442,25 @@ export const runRequest =
           prompts: promptsObj,
           secrets: secretsObj,
           provider_values: {},
+          uri,
+          request: args.request,
         };
 
         /**
          * HTTP Response from language server
          */
-        const responseJson = await commands.executeCommand<string>(
-          Commands.Execute,
-          requestParamsToServer,
-        );
+        let responseJson: string;
+
+        try {
+          responseJson = await commands.executeCommand<string>(
+            Commands.Execute,
+            requestParamsToServer,
+          );
+        } catch (err) {
+          state.setIsWaitingForResponse(uri, context, false);
+          window.showErrorMessage(`Unable to run the request: ${err}`);
+          return;
+        }
 
         const response: HttpResponse = JSON.parse(responseJson);
         const statusCode = response.status_code;
@@ -560,10 +574,14 @@ export const exportToFile = (context: ExtensionContext) => async () => {
       format: "CurlScript",
     };
 
-    const response = await commands.executeCommand<string>(
-      Commands.Export,
-      params,
-    );
+    let response: string;
+
+    try {
+      response = await commands.executeCommand<string>(Commands.Export, params);
+    } catch (err) {
+      window.showErrorMessage(`Unable to export the request: ${err}`);
+      return;
+    }
 
     // Put response string in to a new file in the workspace
     // Create a new untitled document